@@ -0,0 +1,70 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use rust_snake::block::Block;
+use rust_snake::config::GameConfig;
+use rust_snake::direction::Direction;
+use rust_snake::food;
+use rust_snake::game::Game;
+use rust_snake::snake::Snake;
+
+const TICKS: u64 = 10_000;
+const SIZE: i32 = 20;
+
+/// Run the game logic for a fixed number of steps on a 20x20 board with a Snake of length 50.
+/// Whenever the Snake dies, the game is restarted so every step does real work.
+fn bench_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("game");
+    group.throughput(Throughput::Elements(TICKS));
+    group.bench_function("tick", |b| {
+        let mut game = Game::new(GameConfig {
+            width: SIZE,
+            height: SIZE,
+            starting_length: Some(50),
+            seed: Some(0),
+            ..GameConfig::default()
+        });
+        b.iter(|| {
+            for _ in 0..TICKS {
+                if game.game_over() {
+                    game.restart();
+                }
+                game.tick();
+            }
+            black_box(game.score())
+        })
+    });
+    group.finish();
+}
+
+/// Build a Snake winding back and forth through the top half of the board.
+fn half_filled_snake() -> Snake {
+    let mut blocks = Vec::new();
+    for y in 1..SIZE / 2 {
+        let row: Vec<i32> = (1..SIZE - 1).collect();
+        if y % 2 == 1 {
+            blocks.extend(row.into_iter().rev().map(|x| Block::new(x, y)));
+        } else {
+            blocks.extend(row.into_iter().map(|x| Block::new(x, y)));
+        }
+    }
+    // The head is the last placed block, closest to the empty half of the board.
+    blocks.reverse();
+    Snake::from_blocks(blocks, Direction::Down)
+}
+
+/// Find the escape offset of food on a board where the Snake fills half of the playing field.
+fn bench_escape_offset(c: &mut Criterion) {
+    let snake = half_filled_snake();
+    let food = Block::new(SIZE / 2, SIZE - 4);
+    let mut rng = StdRng::seed_from_u64(0);
+    c.bench_function("food::get_escape_offset", |b| {
+        b.iter(|| {
+            food::get_escape_offset(black_box(food), &snake, &[], [0, SIZE], [0, SIZE], &mut rng)
+        })
+    });
+}
+
+criterion_group!(benches, bench_tick, bench_escape_offset);
+criterion_main!(benches);
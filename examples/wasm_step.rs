@@ -0,0 +1,45 @@
+//! Steps the game the way a web page would: with key names as JavaScript reports them and a fixed frame time, and
+//! without any graphics. Run with `cargo run --example wasm_step --no-default-features --features wasm`.
+
+use rust_snake::wasm::WebGame;
+
+// The time between two animation frames of a 60 Hz display, in seconds.
+const FRAME_TIME: f64 = 1.0 / 60.0;
+
+fn main() {
+    let mut game = WebGame::new(42);
+    // The keys a player presses, each with the number of frames that pass before it.
+    let inputs = [
+        (30, "ArrowRight"),
+        (60, "ArrowDown"),
+        (60, "ArrowLeft"),
+        (60, "ArrowUp"),
+    ];
+    for (frames, key) in inputs {
+        for _ in 0..frames {
+            game.update(FRAME_TIME);
+        }
+        game.key_pressed(key);
+        println!(
+            "Pressed {key}: score {}, game over {}",
+            game.score(),
+            game.game_over()
+        );
+    }
+    let cells = game.cells();
+    let width = game.width() as usize;
+    // The top left corner is always border, and the cell diagonally next to it is empty at the start.
+    let (border, background) = (cells[0], cells[width + 1]);
+    println!("Board of {}x{} cells:", width, game.height());
+    for row in cells.chunks(width) {
+        let line: String = row
+            .iter()
+            .map(|color| match *color {
+                color if color == border => '#',
+                color if color == background => '.',
+                _ => 'o',
+            })
+            .collect();
+        println!("{line}");
+    }
+}
@@ -0,0 +1,308 @@
+// External imports.
+use std::collections::HashMap;
+use std::path::Path;
+
+// Local imports.
+use crate::event::GameEvent;
+use crate::settings::MAX_VOLUME;
+
+/// All sound effects, each backed by a file in the sounds folder.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Sound {
+    Eat,
+    Die,
+    SpeedUp,
+    MenuSelect,
+    HighScore,
+}
+
+impl Sound {
+    pub const ALL: [Sound; 5] = [
+        Sound::Eat,
+        Sound::Die,
+        Sound::SpeedUp,
+        Sound::MenuSelect,
+        Sound::HighScore,
+    ];
+
+    /// The file name of the sound in the sounds folder.
+    pub fn file_name(&self) -> &'static str {
+        match *self {
+            Sound::Eat => "eat.wav",
+            Sound::Die => "die.wav",
+            Sound::SpeedUp => "speed_up.wav",
+            Sound::MenuSelect => "menu_select.wav",
+            Sound::HighScore => "high_score.wav",
+        }
+    }
+
+    /// The sound to play in response to a game event.
+    pub fn for_event(event: GameEvent) -> Sound {
+        match event {
+            GameEvent::AteFood { .. } => Sound::Eat,
+            GameEvent::Died => Sound::Die,
+            GameEvent::SpeedUp => Sound::SpeedUp,
+            GameEvent::OpenedDoor => Sound::MenuSelect,
+            GameEvent::MenuSelect => Sound::MenuSelect,
+            GameEvent::NewHighScore => Sound::HighScore,
+        }
+    }
+}
+
+/// All background music tracks, each backed by a file in the music folder.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Music {
+    /// Played while the Snake is moving.
+    Game,
+    /// A calmer variant, played on the game over screen.
+    GameOver,
+}
+
+impl Music {
+    pub const ALL: [Music; 2] = [Music::Game, Music::GameOver];
+
+    /// The file name of the track in the music folder.
+    pub fn file_name(&self) -> &'static str {
+        match *self {
+            Music::Game => "game.wav",
+            Music::GameOver => "game_over.wav",
+        }
+    }
+}
+
+/// Plays sound effects and looping background music.
+/// Missing files or an unavailable audio device result in silence rather than a crash.
+pub struct Audio {
+    muted: bool,
+    effects_volume: u8,
+    music_volume: u8,
+    music_paused: bool,
+    /// The track that should be playing, even while muted.
+    music: Option<Music>,
+    sounds: HashMap<Sound, Vec<u8>>,
+    tracks: HashMap<Music, Vec<u8>>,
+    #[cfg(feature = "audio")]
+    output: Option<(rodio::OutputStream, rodio::OutputStreamHandle)>,
+    /// The sink decoding the current track. Only present while the music is audible.
+    #[cfg(feature = "audio")]
+    music_sink: Option<rodio::Sink>,
+}
+
+/// Read all files of a set of sounds from a folder, skipping the missing ones.
+fn load_files<T: Copy + Eq + std::hash::Hash>(
+    folder: &Path,
+    items: &[T],
+    file_name: fn(&T) -> &'static str,
+) -> HashMap<T, Vec<u8>> {
+    items
+        .iter()
+        .filter_map(|item| {
+            std::fs::read(folder.join(file_name(item)))
+                .ok()
+                .map(|bytes| (*item, bytes))
+        })
+        .collect()
+}
+
+impl Audio {
+    /// Load all sound effects and music tracks and open the default audio device.
+    /// # Arguments
+    /// * `sounds_folder: &Path` - The folder containing the sound effect files.
+    /// * `music_folder: &Path` - The folder containing the music files.
+    /// * `muted: bool` - Whether (true) or not (false) all audio is silenced.
+    /// # Returns
+    /// * `Audio` - The new Audio instance.
+    pub fn new(sounds_folder: &Path, music_folder: &Path, muted: bool) -> Audio {
+        Audio {
+            muted,
+            effects_volume: MAX_VOLUME,
+            music_volume: MAX_VOLUME,
+            music_paused: false,
+            music: None,
+            sounds: load_files(sounds_folder, &Sound::ALL, Sound::file_name),
+            tracks: load_files(music_folder, &Music::ALL, Music::file_name),
+            #[cfg(feature = "audio")]
+            output: rodio::OutputStream::try_default().ok(),
+            #[cfg(feature = "audio")]
+            music_sink: None,
+        }
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Silence or restore all audio. Muting stops the music from being decoded at all.
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.refresh_music();
+    }
+
+    pub fn effects_volume(&self) -> u8 {
+        self.effects_volume
+    }
+
+    pub fn set_effects_volume(&mut self, volume: u8) {
+        self.effects_volume = volume.min(MAX_VOLUME);
+    }
+
+    pub fn music_volume(&self) -> u8 {
+        self.music_volume
+    }
+
+    pub fn set_music_volume(&mut self, volume: u8) {
+        self.music_volume = volume.min(MAX_VOLUME);
+        self.refresh_music();
+    }
+
+    /// Check whether a sound effect was loaded and can be played.
+    pub fn is_loaded(&self, sound: Sound) -> bool {
+        self.sounds.contains_key(&sound)
+    }
+
+    /// Check whether a music track was loaded and can be played.
+    pub fn is_music_loaded(&self, music: Music) -> bool {
+        self.tracks.contains_key(&music)
+    }
+
+    /// Check whether the music is currently being decoded and played.
+    pub fn music_audible(&self) -> bool {
+        self.music.is_some() && !self.muted && self.music_volume > 0 && !self.music_paused
+    }
+
+    /// Play a sound effect once, unless muted.
+    /// # Arguments
+    /// * `sound: Sound` - The sound effect to play.
+    pub fn play(&self, sound: Sound) {
+        if self.muted || self.effects_volume == 0 {
+            return;
+        }
+        #[cfg(feature = "audio")]
+        if let (Some((_, handle)), Some(bytes)) = (&self.output, self.sounds.get(&sound)) {
+            use rodio::Source;
+            if let Ok(source) = rodio::Decoder::new(std::io::Cursor::new(bytes.clone())) {
+                // Failing to play a sound is never worth interrupting the game for.
+                let _ = handle.play_raw(
+                    source
+                        .convert_samples()
+                        .amplify(self.effects_volume as f32 / MAX_VOLUME as f32),
+                );
+            }
+        }
+        #[cfg(not(feature = "audio"))]
+        let _ = sound;
+    }
+
+    /// Loop a music track, switching over if another track is playing.
+    /// # Arguments
+    /// * `music: Music` - The track to play.
+    pub fn play_music(&mut self, music: Music) {
+        if self.music != Some(music) {
+            self.music = Some(music);
+            #[cfg(feature = "audio")]
+            {
+                self.music_sink = None;
+            }
+            self.refresh_music();
+        }
+    }
+
+    /// Pause or resume the music, e.g. while the game is paused.
+    pub fn pause_music(&mut self, paused: bool) {
+        if self.music_paused != paused {
+            self.music_paused = paused;
+            #[cfg(feature = "audio")]
+            if let Some(sink) = &self.music_sink {
+                if paused {
+                    sink.pause();
+                } else {
+                    sink.play();
+                }
+            }
+        }
+    }
+
+    /// Start, stop or adjust the music sink to match the current state.
+    fn refresh_music(&mut self) {
+        #[cfg(feature = "audio")]
+        {
+            if self.muted || self.music_volume == 0 {
+                // Dropping the sink stops decoding entirely.
+                self.music_sink = None;
+                return;
+            }
+            let volume = self.music_volume as f32 / MAX_VOLUME as f32;
+            if let Some(sink) = &self.music_sink {
+                sink.set_volume(volume);
+                return;
+            }
+            let (Some((_, handle)), Some(bytes)) = (
+                &self.output,
+                self.music.and_then(|music| self.tracks.get(&music)),
+            ) else {
+                return;
+            };
+            let Ok(sink) = rodio::Sink::try_new(handle) else {
+                return;
+            };
+            if let Ok(source) = rodio::Decoder::new_looped(std::io::Cursor::new(bytes.clone())) {
+                sink.set_volume(volume);
+                sink.append(source);
+                if self.music_paused {
+                    sink.pause();
+                }
+                self.music_sink = Some(sink);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn missing() -> Audio {
+        Audio::new(
+            Path::new("does/not/exist"),
+            Path::new("does/not/exist"),
+            false,
+        )
+    }
+
+    #[test]
+    fn missing_sounds_are_silent() {
+        let mut audio = missing();
+        for sound in Sound::ALL {
+            assert!(!audio.is_loaded(sound));
+            audio.play(sound);
+        }
+        for music in Music::ALL {
+            assert!(!audio.is_music_loaded(music));
+            audio.play_music(music);
+        }
+    }
+
+    #[test]
+    fn muted_or_silent_music_is_not_audible() {
+        let mut audio = missing();
+        audio.play_music(Music::Game);
+        assert!(audio.music_audible());
+        audio.set_muted(true);
+        assert!(!audio.music_audible());
+        audio.set_muted(false);
+        audio.set_music_volume(0);
+        assert!(!audio.music_audible());
+        audio.set_music_volume(40);
+        audio.pause_music(true);
+        assert!(!audio.music_audible());
+    }
+
+    #[test]
+    fn volumes_are_capped() {
+        let mut audio = missing();
+        audio.set_effects_volume(250);
+        audio.set_music_volume(120);
+        assert_eq!(audio.effects_volume(), MAX_VOLUME);
+        assert_eq!(audio.music_volume(), MAX_VOLUME);
+    }
+}
@@ -0,0 +1,138 @@
+// External imports.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Local imports.
+use crate::block::Block;
+use crate::breakdown::ScoreBreakdown;
+use crate::config::GameConfig;
+use crate::direction::Direction;
+use crate::food::FoodKind;
+use crate::levels::Door;
+
+// Constants.
+/// The time between two autosaves of a running game, in seconds of play.
+pub const AUTOSAVE_INTERVAL: f64 = 10.0;
+
+/// Everything needed to continue a run, cheap enough to save every few seconds.
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
+pub struct GameSnapshot {
+    /// The options the run was started with, including the board size.
+    pub config: GameConfig,
+    /// The Snake, head first.
+    pub snake: Vec<Block>,
+    pub direction: Direction,
+    /// The Snake blocks still digesting food, with the number of moves left.
+    pub digesting: Vec<(Block, i32)>,
+    pub food: Option<Block>,
+    pub food_kind: FoodKind,
+    /// The move the food spawned at, which its value decays from.
+    #[serde(default)]
+    pub food_spawned_at: u32,
+    /// The number of times the food escaped since it spawned.
+    #[serde(default)]
+    pub food_moves: u32,
+    pub snake_moves: u32,
+    pub base_score: i32,
+    pub bonus_score: i32,
+    /// The points per category, adding up to the base and bonus score.
+    #[serde(default)]
+    pub breakdown: ScoreBreakdown,
+    pub eat_streak: u32,
+    pub max_eat_streak: u32,
+    pub peak_length: i32,
+    pub foods_eaten: u32,
+    /// The number of moves that brought the head right next to the body.
+    #[serde(default)]
+    pub near_miss_count: u32,
+    /// The star multiplying the points of the next foods, if one is waiting to be picked up.
+    #[serde(default)]
+    pub star: Option<Block>,
+    /// The number of foods the points are still multiplied for.
+    #[serde(default)]
+    pub multiplied_foods: u32,
+    /// The number of stars stacked into the multiplier.
+    #[serde(default)]
+    pub multiplier_stacks: u32,
+    /// The walls and random obstacles of the run.
+    pub walls: Vec<Block>,
+    /// The doors of the level, and whether they were opened.
+    #[serde(default)]
+    pub doors: Vec<Door>,
+}
+
+impl GameSnapshot {
+    /// Write the snapshot without ever leaving a half-written file behind, by replacing the file in a single rename.
+    /// # Arguments
+    /// * `path: &Path` - The snapshot file.
+    /// # Returns
+    /// * `io::Result<()>` - Whether the snapshot could be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let temporary = path.with_extension("json.tmp");
+        fs::write(&temporary, serde_json::to_string(self)?)?;
+        fs::rename(temporary, path)
+    }
+
+    /// Read a snapshot in an infallible way.
+    /// # Arguments
+    /// * `path: &Path` - The snapshot file.
+    /// # Returns
+    /// * `Option<GameSnapshot>` - The snapshot, or None if the file is missing or invalid.
+    pub fn load(path: &Path) -> Option<GameSnapshot> {
+        let data = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Delete a snapshot file, after a finished run or a clean exit. A missing file is not an error.
+    /// # Arguments
+    /// * `path: &Path` - The snapshot file.
+    pub fn remove(path: &Path) {
+        fs::remove_file(path).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_survive_a_save_and_load() {
+        let path = std::env::temp_dir().join("rust-snake-autosave-test.json");
+        let snapshot = GameSnapshot {
+            config: GameConfig::default(),
+            snake: vec![Block::new(3, 2), Block::new(2, 2)],
+            direction: Direction::Right,
+            digesting: vec![(Block::new(2, 2), 1)],
+            food: Some(Block::new(7, 7)),
+            food_kind: FoodKind::Bonus,
+            food_spawned_at: 3,
+            food_moves: 2,
+            snake_moves: 12,
+            base_score: 4,
+            bonus_score: 3,
+            breakdown: ScoreBreakdown {
+                food: 4,
+                speed: 3,
+                ..ScoreBreakdown::default()
+            },
+            eat_streak: 1,
+            max_eat_streak: 2,
+            peak_length: 5,
+            foods_eaten: 4,
+            near_miss_count: 1,
+            star: Some(Block::new(4, 4)),
+            multiplied_foods: 7,
+            multiplier_stacks: 1,
+            walls: vec![Block::new(9, 9)],
+            doors: Vec::new(),
+        };
+        snapshot.save(&path).unwrap();
+        assert!(GameSnapshot::load(&path) == Some(snapshot));
+        GameSnapshot::remove(&path);
+        assert!(GameSnapshot::load(&path).is_none());
+        // Removing twice is fine.
+        GameSnapshot::remove(&path);
+    }
+}
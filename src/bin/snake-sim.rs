@@ -0,0 +1,109 @@
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use rust_snake::block::Block;
+use rust_snake::cell::Cell;
+use rust_snake::config::GameConfig;
+use rust_snake::direction::Direction;
+use rust_snake::game::Game;
+use rust_snake::results::ResultsWriter;
+use rust_snake::settings::Settings;
+
+const ASSETS_FOLDER: &str = "assets";
+const ASSETS_SETTINGS_NAME: &str = "settings.json";
+const DEFAULT_RUNS: u64 = 100;
+// Stopping runs that would go on forever, e.g. a Snake circling without ever reaching the food.
+const MAX_TICKS: u32 = 100_000;
+const USAGE: &str = "Usage: snake-sim --out DIR [--runs N] [--seed N] [--min-score N]";
+
+/// Get the value following a command line flag.
+/// # Arguments
+/// * `flag: &str` - The flag, e.g. `--runs`.
+/// # Returns
+/// * `Option<T>` - The parsed value, or None if the flag is not given. Exits on values that cannot be parsed.
+fn arg<T: std::str::FromStr>(flag: &str) -> Option<T> {
+    let value = env::args().skip_while(|arg| arg != flag).nth(1)?;
+    Some(value.parse().unwrap_or_else(|_| {
+        eprintln!("Invalid value {value} for {flag}.\n{USAGE}");
+        process::exit(1);
+    }))
+}
+
+/// Find the first cell showing something.
+/// # Arguments
+/// * `game: &Game` - The game to search.
+/// * `wanted: impl Fn(Cell) -> bool` - Whether a cell shows what is searched for.
+/// # Returns
+/// * `Option<Block>` - The first matching cell, row by row.
+fn find(game: &Game, wanted: impl Fn(Cell) -> bool) -> Option<Block> {
+    let (width, height) = game.size();
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| Block::new(x, y)))
+        .find(|block| wanted(game.cell(*block)))
+}
+
+/// Steer greedily towards the food, only taking moves the Snake survives.
+/// # Arguments
+/// * `game: &mut Game` - The game to steer.
+fn autopilot(game: &mut Game) {
+    let (Some(head), Some(food)) = (
+        find(game, |cell| cell == Cell::SnakeHead),
+        find(game, |cell| matches!(cell, Cell::Food(_))),
+    ) else {
+        return;
+    };
+    // Trying the directions in a fixed order, so seeded runs are reproducible.
+    let best = Direction::CARDINAL_OFFSETS
+        .into_iter()
+        .filter(|(direction, _, _)| game.check_snake_alive(Some(*direction)))
+        .min_by_key(|(_, dx, dy)| Block::new(head.x + dx, head.y + dy).manhattan_distance(food));
+    if let Some((direction, _, _)) = best {
+        game.steer(direction);
+    }
+}
+
+/// Play seeded runs without a window, writing the final board of every run that scores high enough.
+fn main() {
+    #[cfg(feature = "logging")]
+    env_logger::init();
+    let Some(out) = arg::<PathBuf>("--out") else {
+        eprintln!("{USAGE}");
+        process::exit(1);
+    };
+    let runs = arg("--runs").unwrap_or(DEFAULT_RUNS);
+    let first_seed = arg("--seed").unwrap_or(0);
+    let mut writer = ResultsWriter::new(out, arg("--min-score")).unwrap_or_else(|error| {
+        eprintln!("Cannot write results, {error}.");
+        process::exit(1);
+    });
+
+    // Playing with the options of the windowed game, so the results match what the player sees.
+    let assets = find_folder::Search::ParentsThenKids(3, 3)
+        .for_folder(ASSETS_FOLDER)
+        .unwrap_or_else(|_| PathBuf::from(ASSETS_FOLDER));
+    let mut config = Settings::load(assets.join(ASSETS_SETTINGS_NAME)).game;
+    if let Err(error) = config.validate() {
+        eprintln!("Invalid settings, {error}. Using the default game options.");
+        config = GameConfig::default();
+    }
+
+    for seed in first_seed..first_seed + runs {
+        let mut game = Game::new(GameConfig {
+            seed: Some(seed),
+            ..config.clone()
+        });
+        while !game.game_over() && game.snake_moves() < MAX_TICKS {
+            autopilot(&mut game);
+            game.tick();
+        }
+        match writer.write(&game) {
+            Ok(Some(path)) => println!("Seed {seed}: {} -> {}", game.score(), path.display()),
+            Ok(None) => println!("Seed {seed}: {}", game.score()),
+            Err(error) => {
+                eprintln!("Cannot write the result of seed {seed}, {error}.");
+                process::exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rust_snake::block::Block;
+use rust_snake::config::GameConfig;
+use rust_snake::direction::Direction;
+use rust_snake::game::Game;
+use rust_snake::locale::Locale;
+use rust_snake::score::{self, Score};
+use rust_snake::settings::Settings;
+use rust_snake::storage::FileStorage;
+use rust_snake::theme::load_themes;
+
+const ASSETS_FOLDER: &str = "assets";
+const ASSETS_SETTINGS_NAME: &str = "settings.json";
+const ASSETS_LOCALES_FOLDER: &str = "locales";
+const ASSETS_THEMES_FOLDER: &str = "themes";
+// The time between two redraws, in which keys are read as they come in.
+const FRAME_TIME: Duration = Duration::from_millis(16);
+// Every block is two characters wide, as terminal characters are about twice as high as they are wide.
+const CELL: &str = "  ";
+
+/// Convert a color of the game theme to a terminal color.
+/// # Arguments
+/// * `color: [f32; 4]` - The RGBA color with channels between 0 and 1. The alpha channel is ignored.
+/// # Returns
+/// * `Color` - The same color as a true color terminal color.
+fn to_terminal_color(color: [f32; 4]) -> Color {
+    let [r, g, b] = [color[0], color[1], color[2]].map(|channel| (channel * 255.0).round() as u8);
+    Color::Rgb { r, g, b }
+}
+
+/// Draw the board cell by cell, followed by the text of the score strip.
+/// # Arguments
+/// * `out: &mut impl Write` - The terminal to draw on.
+/// * `game: &Game` - The game to draw.
+/// * `locale: &Locale` - The strings of the language to write all text in.
+fn draw(out: &mut impl Write, game: &Game, locale: &Locale) -> io::Result<()> {
+    let (width, height) = game.size();
+    for y in 0..height {
+        queue!(out, MoveTo(0, y as u16))?;
+        for x in 0..width {
+            let color = game.cell(Block::new(x, y)).color(game.theme());
+            queue!(
+                out,
+                SetBackgroundColor(to_terminal_color(color)),
+                Print(CELL)
+            )?;
+        }
+        queue!(out, ResetColor)?;
+    }
+    for (row, line) in game.hud_lines(locale).iter().enumerate() {
+        queue!(
+            out,
+            MoveTo(0, (height as usize + row) as u16),
+            Clear(ClearType::UntilNewLine),
+            Print(line)
+        )?;
+    }
+    queue!(out, Clear(ClearType::FromCursorDown))?;
+    out.flush()
+}
+
+/// React to a keypress, mapping it onto the same game logic the windowed game uses.
+/// # Arguments
+/// * `key: KeyEvent` - The key being pressed.
+/// * `game: &mut Game` - The game to control.
+/// * `scores: &mut Vec<Score>` - The high scores a new one is written to after entering a name.
+/// * `storage: &mut FileStorage` - Where the score files are kept, shared with the windowed game.
+/// # Returns
+/// * `bool` - Whether (true) or not (false) the player wants to quit.
+fn key_pressed(
+    key: KeyEvent,
+    game: &mut Game,
+    scores: &mut Vec<Score>,
+    storage: &mut FileStorage,
+) -> bool {
+    if key.code == KeyCode::Esc {
+        return true;
+    }
+    // Any key dismisses the controls overlay, without doing anything else.
+    if game.dismiss_hints() {
+        return false;
+    }
+    // Letter keys type the name of a new high score instead of controlling the game.
+    if game.entering_name() {
+        match key.code {
+            KeyCode::Char(letter) => game.type_letter(letter),
+            KeyCode::Backspace => game.erase_letter(),
+            KeyCode::Enter => game.confirm_name(scores, storage),
+            _ => (),
+        }
+        return false;
+    }
+    match key.code {
+        KeyCode::Char('q') => return true,
+        KeyCode::Char('p') => game.toggle_pause(),
+        KeyCode::Char('u') => {
+            game.undo();
+        }
+        KeyCode::Char(' ') => game.play_again(),
+        KeyCode::Up => game.steer(Direction::Up),
+        KeyCode::Down => game.steer(Direction::Down),
+        KeyCode::Left => game.steer(Direction::Left),
+        KeyCode::Right => game.steer(Direction::Right),
+        _ => (),
+    }
+    false
+}
+
+/// Run the game until the player quits.
+/// # Arguments
+/// * `out: &mut impl Write` - The terminal to draw on.
+/// * `game: &mut Game` - The game to play.
+/// * `locale: &Locale` - The strings of the language to write all text in.
+/// * `scores: &mut Vec<Score>` - The high scores, updated when a new one is written.
+/// * `storage: &mut FileStorage` - Where the score files are kept, shared with the windowed game.
+fn run(
+    out: &mut impl Write,
+    game: &mut Game,
+    locale: &Locale,
+    scores: &mut Vec<Score>,
+    storage: &mut FileStorage,
+) -> io::Result<()> {
+    let mut last_update = Instant::now();
+    loop {
+        // Reading keys until the next frame is due.
+        let frame_end = last_update + FRAME_TIME;
+        while let Some(timeout) = frame_end.checked_duration_since(Instant::now()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+            if let Event::Key(key) = event::read()? {
+                // Some terminals also report releasing a key, which should not count twice.
+                if key.kind == KeyEventKind::Press && key_pressed(key, game, scores, storage) {
+                    return Ok(());
+                }
+            }
+        }
+        let now = Instant::now();
+        game.update(now.duration_since(last_update).as_secs_f64());
+        last_update = now;
+        game.update_high_score(scores);
+        // There is no audio in the terminal, so all events are dropped.
+        game.drain_events().for_each(drop);
+        draw(out, game, locale)?;
+    }
+}
+
+fn main() -> io::Result<()> {
+    // Sharing the settings, locales and score files with the windowed game.
+    let assets = find_folder::Search::ParentsThenKids(3, 3)
+        .for_folder(ASSETS_FOLDER)
+        .unwrap_or_else(|_| PathBuf::from(ASSETS_FOLDER));
+    let mut settings = Settings::load(assets.join(ASSETS_SETTINGS_NAME));
+    if let Err(error) = settings.game.validate() {
+        eprintln!("Invalid settings, {error}. Using the default game options.");
+        settings.game = GameConfig::default();
+    }
+    settings.themes = load_themes(&assets.join(ASSETS_THEMES_FOLDER));
+    settings.select_theme(settings.game.theme_file.clone());
+    let locale = Locale::load(&assets.join(ASSETS_LOCALES_FOLDER), &settings.language);
+    let mut game = Game::new(settings.game.clone());
+    let mut storage = FileStorage::new(&assets);
+    let mut scores = score::parse_scores(
+        &storage,
+        &score::scores_file_name(game.size(), game.survival()),
+    );
+
+    // Score files are written on this runtime in the background, which waits for them when dropped on exit.
+    #[cfg(feature = "async")]
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    #[cfg(feature = "async")]
+    let _runtime_guard = runtime.enter();
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+    let result = run(&mut out, &mut game, &locale, &mut scores, &mut storage);
+    // Restoring the terminal before reporting any error, so it stays usable.
+    execute!(out, ResetColor, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    // Never lose a high score by quitting during name entry.
+    game.write_pending_score(&mut scores, &mut storage);
+    result
+}
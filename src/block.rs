@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 // A simple Block struct, combining an x- and y-coordinate. Will not be exported so not pub.
 // It is required to derive copy and clone allow movement of this type.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub struct Block {
     pub x: i32,
     pub y: i32,
@@ -12,11 +14,29 @@ impl Block {
     /// * `x: i32` - The Blocks x-coordinate.
     /// * `y: i32` - The Blocks y-coordinate.
     /// # Returns
-    /// * `Block` - The new Block instance.
-    pub fn new(x: i32, y: i32) -> Block {
+    /// * `Block` - The new Block instance, which can also be used as a `const` value.
+    pub const fn new(x: i32, y: i32) -> Block {
         Block { x, y }
     }
 
+    /// Calculate the Manhattan distance to another Block, i.e. the number of moves needed to reach it.
+    /// # Arguments
+    /// * `other: Block` - The Block to measure to.
+    /// # Returns
+    /// * `i32` - The sum of the absolute x- and y-differences.
+    pub fn manhattan_distance(&self, other: Block) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Check whether another Block is directly above, below, left or right of this Block.
+    /// # Arguments
+    /// * `other: Block` - The Block to compare to.
+    /// # Returns
+    /// * `bool` - Whether (true) or not (false) the Blocks are one move apart. Diagonal Blocks are not adjacent.
+    pub fn is_adjacent(&self, other: Block) -> bool {
+        self.manhattan_distance(other) == 1
+    }
+
     /// Check whether this block falls within given bounds.
     /// # Arguments
     /// * `x: [i32; 2]` - The x-bounds as [lower, higher].
@@ -30,3 +50,98 @@ impl Block {
             || self.y >= y_bounds[1] - 1
     }
 }
+
+impl From<(i32, i32)> for Block {
+    fn from((x, y): (i32, i32)) -> Self {
+        Block::new(x, y)
+    }
+}
+
+impl From<Block> for (i32, i32) {
+    fn from(block: Block) -> Self {
+        (block.x, block.y)
+    }
+}
+
+impl From<[i32; 2]> for Block {
+    fn from([x, y]: [i32; 2]) -> Self {
+        Block::new(x, y)
+    }
+}
+
+impl From<Block> for [i32; 2] {
+    fn from(block: Block) -> Self {
+        [block.x, block.y]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: [i32; 2] = [0, 10];
+    const CORNER: Block = Block::new(3, 4);
+
+    #[test]
+    fn blocks_can_be_built_at_compile_time() {
+        static LINE: [Block; 2] = [CORNER, Block::new(4, 4)];
+        assert!(LINE[0] == Block::new(3, 4));
+        assert!(LINE[0].is_adjacent(LINE[1]));
+    }
+
+    #[test]
+    fn out_of_bounds_at_lower_bound() {
+        assert!(Block::new(0, 5).out_of_bounds(BOUNDS, BOUNDS));
+        assert!(Block::new(5, 0).out_of_bounds(BOUNDS, BOUNDS));
+    }
+
+    #[test]
+    fn out_of_bounds_at_upper_bound() {
+        // The last row and column are occupied by the border.
+        assert!(Block::new(9, 5).out_of_bounds(BOUNDS, BOUNDS));
+        assert!(Block::new(5, 9).out_of_bounds(BOUNDS, BOUNDS));
+    }
+
+    #[test]
+    fn in_bounds_one_inside_lower_bound() {
+        assert!(!Block::new(1, 5).out_of_bounds(BOUNDS, BOUNDS));
+        assert!(!Block::new(5, 1).out_of_bounds(BOUNDS, BOUNDS));
+    }
+
+    #[test]
+    fn in_bounds_one_inside_upper_bound() {
+        assert!(!Block::new(8, 5).out_of_bounds(BOUNDS, BOUNDS));
+        assert!(!Block::new(5, 8).out_of_bounds(BOUNDS, BOUNDS));
+    }
+
+    #[test]
+    fn is_adjacent_in_all_directions_but_not_diagonally() {
+        let block = Block::new(5, 5);
+        for other in [
+            Block::new(5, 4),
+            Block::new(5, 6),
+            Block::new(4, 5),
+            Block::new(6, 5),
+        ] {
+            assert!(block.is_adjacent(other));
+        }
+        assert!(!block.is_adjacent(Block::new(6, 6)));
+        assert!(!block.is_adjacent(block));
+    }
+
+    #[test]
+    fn out_of_bounds_in_both_dimensions() {
+        assert!(Block::new(-1, -1).out_of_bounds(BOUNDS, BOUNDS));
+        assert!(Block::new(12, 12).out_of_bounds(BOUNDS, BOUNDS));
+        assert!(Block::new(-3, 15).out_of_bounds(BOUNDS, [0, 20]));
+    }
+
+    #[test]
+    fn blocks_convert_to_and_from_coordinates() {
+        assert!(Block::from((3, 4)) == Block::new(3, 4));
+        assert!(Block::from([3, 4]) == Block::new(3, 4));
+        assert_eq!(<(i32, i32)>::from(Block::new(3, 4)), (3, 4));
+        let offset: [i32; 2] = Block::new(-1, 0).into();
+        assert_eq!(offset, [-1, 0]);
+    }
+}
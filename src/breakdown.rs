@@ -0,0 +1,134 @@
+// External imports.
+use serde::{Deserialize, Serialize};
+
+// Local imports.
+use crate::food::FoodKind;
+
+/// Why points were added to or taken from the score.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScoreReason {
+    /// The value of a food, as it would have been at the first speed level.
+    Food(FoodKind),
+    /// The extra points for eating a food at a higher speed level.
+    Speed(FoodKind),
+    Combo,
+    Risky,
+    Milestone,
+    Multiplier,
+    /// A move survived in survival mode.
+    Survival,
+    /// Points lost, which never take the score below zero.
+    Penalty,
+}
+
+impl ScoreReason {
+    /// Check whether the points count towards the base score, which breaks ties between equal scores, rather than
+    /// the bonus score.
+    pub fn is_base(&self) -> bool {
+        matches!(
+            self,
+            ScoreReason::Food(FoodKind::Normal)
+                | ScoreReason::Speed(FoodKind::Normal)
+                | ScoreReason::Survival
+        )
+    }
+}
+
+/// The points of a run per category, together adding up to its score.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ScoreBreakdown {
+    /// The value of all food eaten, as it would have been at the first speed level.
+    pub food: i32,
+    /// The extra points for eating food at higher speed levels.
+    pub speed: i32,
+    /// The bonuses for eating several foods on consecutive moves.
+    pub combo: i32,
+    /// The bonuses for eating food with its sides blocked.
+    pub risky: i32,
+    /// The bonuses for reaching Snake length milestones.
+    pub milestones: i32,
+    /// The extra points while a star multiplied them.
+    pub multiplier: i32,
+    /// The moves survived in survival mode.
+    pub survival: i32,
+    /// All points lost, including those the score could not drop by.
+    pub penalties: i32,
+    /// The points given back to keep the score from dropping below zero.
+    pub floor: i32,
+}
+
+impl ScoreBreakdown {
+    /// Add up all categories.
+    /// # Returns
+    /// * `i32` - The score of the run.
+    pub fn total(&self) -> i32 {
+        self.rows().iter().map(|(_, points)| points).sum()
+    }
+
+    /// Add points to the category of a reason.
+    /// # Arguments
+    /// * `points: i32` - The points, negative for a penalty.
+    /// * `reason: ScoreReason` - Why the points were earned or lost.
+    pub fn record(&mut self, points: i32, reason: ScoreReason) {
+        let category = match reason {
+            ScoreReason::Food(_) => &mut self.food,
+            ScoreReason::Speed(_) => &mut self.speed,
+            ScoreReason::Combo => &mut self.combo,
+            ScoreReason::Risky => &mut self.risky,
+            ScoreReason::Milestone => &mut self.milestones,
+            ScoreReason::Multiplier => &mut self.multiplier,
+            ScoreReason::Survival => &mut self.survival,
+            ScoreReason::Penalty => &mut self.penalties,
+        };
+        *category += points;
+    }
+
+    /// List every category with the locale key of its label.
+    /// # Returns
+    /// * `[(&str, i32); 9]` - The locale key and the points of every category, in the order they are shown.
+    pub fn rows(&self) -> [(&'static str, i32); 9] {
+        [
+            ("breakdown_food", self.food),
+            ("breakdown_speed", self.speed),
+            ("breakdown_combo", self.combo),
+            ("breakdown_risky", self.risky),
+            ("breakdown_milestones", self.milestones),
+            ("breakdown_multiplier", self.multiplier),
+            ("breakdown_survival", self.survival),
+            ("breakdown_penalties", self.penalties),
+            ("breakdown_floor", self.floor),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_adds_up_all_categories() {
+        let breakdown = ScoreBreakdown {
+            food: 9,
+            speed: 3,
+            combo: 1,
+            risky: 1,
+            milestones: 5,
+            multiplier: 14,
+            survival: 0,
+            penalties: -4,
+            floor: 0,
+        };
+        assert_eq!(breakdown.total(), 29);
+        assert_eq!(ScoreBreakdown::default().total(), 0);
+    }
+
+    #[test]
+    fn only_normal_food_and_survival_count_as_base() {
+        assert!(ScoreReason::Food(FoodKind::Normal).is_base());
+        assert!(ScoreReason::Speed(FoodKind::Normal).is_base());
+        assert!(ScoreReason::Survival.is_base());
+        assert!(!ScoreReason::Food(FoodKind::Bonus).is_base());
+        assert!(!ScoreReason::Penalty.is_base());
+    }
+}
@@ -0,0 +1,85 @@
+// Local imports.
+use crate::food::FoodKind;
+use crate::theme::{Color, Theme};
+
+/// What is shown on a single cell of the board, for frontends that draw the game cell by cell.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    /// The borders around the playing field and the score strip.
+    Border,
+    Wall,
+    /// A door, which the Snake can only move through once it is open.
+    Door {
+        open: bool,
+    },
+    /// The key opening a closed door.
+    Key,
+    SnakeHead,
+    SnakeBody,
+    Food(FoodKind),
+    /// The star multiplying the points of the next foods.
+    Star,
+    /// A row about to turn lethal, or striking.
+    Hazard,
+    /// A block shrinking the Snake when its head enters it.
+    HazardBlock,
+    /// A cell hidden by the fog of war.
+    Fog,
+}
+
+impl Cell {
+    /// Get the color a cell is filled with.
+    /// # Arguments
+    /// * `theme: &Theme` - The colors the game is drawn with.
+    /// # Returns
+    /// * `Color` - The opaque color of the cell.
+    pub fn color(&self, theme: &Theme) -> Color {
+        match self {
+            Cell::Empty => theme.background,
+            Cell::Border | Cell::Wall => theme.border,
+            Cell::Door { open: false } => theme.door,
+            Cell::Door { open: true } => theme.door_open,
+            Cell::Key => theme.key,
+            Cell::SnakeHead => theme.snake_head,
+            Cell::SnakeBody => theme.snake_body,
+            Cell::Food(kind) => kind.color(theme),
+            Cell::Star => theme.star,
+            Cell::Hazard | Cell::HazardBlock => theme.hazard,
+            Cell::Fog => [0.0, 0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Get the character a cell is written as in the plain text view of the board.
+    /// # Returns
+    /// * `char` - A single character, which is a space for empty cells.
+    pub fn symbol(&self) -> char {
+        match self {
+            Cell::Empty => ' ',
+            Cell::Border | Cell::Wall => '#',
+            Cell::Door { open: false } => '+',
+            Cell::Door { open: true } => '/',
+            Cell::Key => 'k',
+            Cell::SnakeHead => 'O',
+            Cell::SnakeBody => 'o',
+            Cell::Food(_) => '*',
+            Cell::Star => '$',
+            Cell::Hazard => '!',
+            Cell::HazardBlock => '^',
+            Cell::Fog => '~',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doors_change_color_when_opened() {
+        let theme = Theme::classic();
+        assert_eq!(Cell::Door { open: false }.color(&theme), theme.door);
+        assert_eq!(Cell::Door { open: true }.color(&theme), theme.door_open);
+        assert_eq!(Cell::Wall.color(&theme), theme.border);
+    }
+}
@@ -0,0 +1,686 @@
+// External imports.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Local imports.
+use crate::block::Block;
+use crate::dateformat::DisplayFormat;
+use crate::direction::Direction;
+use crate::food::{FoodStrategy, FoodWeights, DEFAULT_MINIMAX_DEPTH};
+use crate::levels::Level;
+use crate::snake::SNAKE_STARTING_LENGTH;
+use crate::theme::{Palette, Theme};
+
+// Constants.
+pub const DEFAULT_WIDTH: i32 = 20;
+pub const DEFAULT_HEIGHT: i32 = 20;
+/// The narrowest board that fits the borders around a playing field the Snake can turn in.
+pub const MIN_WIDTH: i32 = 5;
+/// The lowest board that fits the borders and the score strip around a playing field the Snake can turn in.
+pub const MIN_HEIGHT: i32 = 7;
+/// The factor everything is scaled up by in high contrast mode.
+pub const HIGH_CONTRAST_SCALE: f64 = 1.5;
+/// The size of a block on screen in pixels, unless configured otherwise.
+pub const DEFAULT_CELL_SIZE: u32 = 25;
+/// The smallest size of a block on screen in pixels, at which text still fits the score strip.
+pub const MIN_CELL_SIZE: u32 = 12;
+/// The largest size of a block on screen in pixels.
+pub const MAX_CELL_SIZE: u32 = 64;
+/// The smallest factor all text sizes can be multiplied by, at which text stays readable.
+pub const MIN_FONT_SCALE: f64 = 0.5;
+/// The largest factor all text sizes can be multiplied by.
+pub const MAX_FONT_SCALE: f64 = 2.0;
+
+/// The time between two Snake moves at the first speed level in seconds, unless configured otherwise.
+pub const DEFAULT_MOVING_PERIOD: f64 = 0.5;
+/// The factor the moving period is multiplied by at every next speed level, unless configured otherwise.
+pub const DEFAULT_SPEED_FACTOR: f64 = 0.8;
+/// The number of foods after which the game speeds up, unless configured otherwise.
+pub const DEFAULT_FOODS_PER_SPEED_INCREASE: u32 = 5;
+
+/// The speed levels the game can be capped at, from the settings screen.
+pub const MAX_SPEED_LEVELS: [i32; 4] = [6, 8, 10, 12];
+
+/// The sizes a board can be picked from, in blocks including the score strip, in the order of `BoardPreset::ALL`.
+pub const BOARD_SIZES: [(i32, i32); 4] = [(15, 15), (20, 20), (30, 25), (40, 30)];
+
+/// The named sizes a board can be picked from before a game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BoardPreset {
+    Small,
+    Classic,
+    Large,
+    Huge,
+}
+
+impl BoardPreset {
+    pub const ALL: [BoardPreset; 4] = [
+        BoardPreset::Small,
+        BoardPreset::Classic,
+        BoardPreset::Large,
+        BoardPreset::Huge,
+    ];
+
+    /// The board width and height in blocks, including the score strip.
+    pub fn size(&self) -> (i32, i32) {
+        BOARD_SIZES[*self as usize]
+    }
+
+    /// The locale key of the name of the preset.
+    pub fn label_key(&self) -> &'static str {
+        match *self {
+            BoardPreset::Small => "board_small",
+            BoardPreset::Classic => "board_classic",
+            BoardPreset::Large => "board_large",
+            BoardPreset::Huge => "board_huge",
+        }
+    }
+
+    /// Find the preset of a board size.
+    /// # Arguments
+    /// * `size: (i32, i32)` - The board width and height in blocks, including the score strip.
+    /// # Returns
+    /// * `Option<BoardPreset>` - The preset, or None for a custom size.
+    pub fn from_size(size: (i32, i32)) -> Option<BoardPreset> {
+        BoardPreset::ALL
+            .into_iter()
+            .find(|preset| preset.size() == size)
+    }
+}
+
+/// How fast the Snake moves, relative to the normal speed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// The factor the time between two Snake moves is multiplied by.
+    pub fn period_factor(&self) -> f64 {
+        match *self {
+            Difficulty::Easy => 1.25,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.75,
+        }
+    }
+
+    /// The fraction of the playing field covered by random obstacles in arcade mode.
+    pub fn obstacle_density(&self) -> f64 {
+        match *self {
+            Difficulty::Easy => 0.02,
+            Difficulty::Normal => 0.04,
+            Difficulty::Hard => 0.06,
+        }
+    }
+
+    /// How the food escapes, only planning ahead on hard.
+    /// # Arguments
+    /// * `lookahead: u32` - The number of moves the food plans ahead.
+    pub fn food_strategy(&self, lookahead: u32) -> FoodStrategy {
+        match *self {
+            Difficulty::Easy | Difficulty::Normal => FoodStrategy::Greedy,
+            Difficulty::Hard => FoodStrategy::Minimax { depth: lookahead },
+        }
+    }
+}
+
+/// All options a Game is created from. Restarting a Game rebuilds it from these options.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// The game window width in blocks.
+    pub width: i32,
+    /// The game window height in blocks, including the score strip.
+    pub height: i32,
+    /// The initial length of the Snake, by default 3.
+    pub starting_length: Option<i32>,
+    /// The initial direction of the Snake, by default Direction::Right.
+    pub starting_direction: Option<Direction>,
+    /// The seed of the random number generator. A random seed is used when None.
+    pub seed: Option<u64>,
+    /// The name a high score is saved under when no name was entered.
+    pub default_player_name: String,
+    /// The number of Snake moves at the start of every run during which the food does not escape.
+    pub escape_grace_moves: u32,
+    /// The number of moves the food plans ahead when escaping on hard difficulty.
+    pub escape_lookahead: u32,
+    /// Whether (true) the game speeds up a little with every food, or (false) in steps of several foods.
+    pub smooth_speed: bool,
+    /// Accessibility option: the food never moves and animations are disabled. Does not affect scoring.
+    pub reduce_motion: bool,
+    /// The colors the game is drawn with.
+    pub palette: Palette,
+    /// The name of a theme file in `assets/themes`, without the `.json` extension, drawn instead of the palette.
+    pub theme_file: Option<String>,
+    /// The colors loaded from the theme file at launch. The palette is used if None, e.g. when the file is invalid.
+    #[serde(skip)]
+    pub theme_colors: Option<Theme>,
+    /// Accessibility option: everything is drawn larger, on a playing field with more contrast.
+    pub high_contrast: bool,
+    /// Whether the playing field fades to a darker color towards the bottom, instead of a flat background.
+    pub gradient_background: bool,
+    /// How the scoreboard shows when each score was set.
+    pub timestamp_format: DisplayFormat,
+    /// Whether the scoreboard shows dates in UTC, e.g. for score files shared across timezones, rather than in the
+    /// local timezone. Scores are always stored in UTC.
+    pub utc_timestamps: bool,
+    /// How fast the Snake moves.
+    pub difficulty: Difficulty,
+    /// The speed level after which the game does not speed up any further.
+    pub max_speed_level: i32,
+    /// The time between two Snake moves at the first speed level in seconds, before the difficulty factor.
+    pub moving_period: f64,
+    /// The factor the moving period is multiplied by at every next speed level, between 0 and 1.
+    pub speed_factor: f64,
+    /// The number of foods after which the game speeds up.
+    pub foods_per_speed_increase: u32,
+    /// The moving period of every speed level from the first one, replacing `moving_period` and `speed_factor`.
+    /// Levels past the end of the table keep its last period.
+    pub speed_periods: Option<Vec<f64>>,
+    /// The maximum number of frames of an exported GIF. Longer runs skip ticks to fit.
+    pub gif_max_frames: usize,
+    /// The number of foods after which no new food spawns, for a survival challenge. Unlimited when None.
+    pub max_foods_per_game: Option<u32>,
+    /// The walls on the board, besides the border.
+    pub level: Level,
+    /// Whether random obstacles are placed on top of the level at the start of every run.
+    pub arcade: bool,
+    /// Survival mode: the score counts the moves survived instead of the food eaten, and the game speeds up over time.
+    pub survival: bool,
+    /// Practice mode: moves can be undone with <U>, also after dying, and scores are not saved.
+    pub practice: bool,
+    /// The number of moves that can be undone in a row in practice mode, up to `MAX_UNDO_STEPS`.
+    pub undo_steps: usize,
+    /// Challenge mode: only the cells around the head of the Snake are visible.
+    pub fog_of_war: bool,
+    /// Whether a random row of the board periodically becomes lethal, on hard difficulty only.
+    pub hazard_rows: bool,
+    /// The maximum number of foods the Snake digests at once. Eating more drops the food closest to being digested.
+    pub max_digesting: usize,
+    /// Where the score, speed, length bar and combo are drawn.
+    pub hud_layout: HudLayout,
+    /// The size of a block on screen in pixels, e.g. 16, 25 or 32, before high contrast mode scales it up.
+    pub cell_size: u32,
+    /// How often every kind of food spawns, relative to the other kinds.
+    pub food_weights: FoodWeights,
+    /// The factor all text sizes are multiplied by, e.g. for fonts with smaller or larger glyphs.
+    pub font_scale: f64,
+    /// Where the first food of every run is placed, inside the borders. Random if None, or if the Snake or a wall
+    /// starts there.
+    pub initial_food: Option<Block>,
+    /// The smallest distance in blocks between the head and newly spawned food, leaving time to react. Halved when the
+    /// board is too full for it.
+    pub food_spawn_min_distance: i32,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            starting_length: None,
+            starting_direction: None,
+            seed: None,
+            default_player_name: String::from("ANON"),
+            escape_grace_moves: 10,
+            escape_lookahead: DEFAULT_MINIMAX_DEPTH,
+            smooth_speed: false,
+            reduce_motion: false,
+            palette: Palette::Classic,
+            theme_file: None,
+            theme_colors: None,
+            high_contrast: false,
+            gradient_background: false,
+            timestamp_format: DisplayFormat::Relative,
+            utc_timestamps: false,
+            difficulty: Difficulty::Normal,
+            max_speed_level: 10,
+            moving_period: DEFAULT_MOVING_PERIOD,
+            speed_factor: DEFAULT_SPEED_FACTOR,
+            foods_per_speed_increase: DEFAULT_FOODS_PER_SPEED_INCREASE,
+            speed_periods: None,
+            gif_max_frames: 600,
+            max_foods_per_game: None,
+            level: Level::Open,
+            arcade: false,
+            survival: false,
+            practice: false,
+            undo_steps: 1,
+            fog_of_war: false,
+            hazard_rows: false,
+            max_digesting: SNAKE_STARTING_LENGTH as usize,
+            hud_layout: HudLayout::default(),
+            cell_size: DEFAULT_CELL_SIZE,
+            food_weights: FoodWeights::default(),
+            font_scale: 1.0,
+            initial_food: None,
+            food_spawn_min_distance: 3,
+        }
+    }
+}
+
+/// Where the elements of the HUD are drawn, in blocks. Negative coordinates count back from the right and bottom edge
+/// of the window, and elements at a negative x are aligned to the right of their position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HudLayout {
+    pub score_pos: Block,
+    /// The speed level text, with the progress bar towards the next level below it.
+    pub speed_pos: Block,
+    /// The bar showing how much of the playing field the Snake fills.
+    pub length_pos: Block,
+    /// The combo counter, only shown while a combo is active.
+    pub combo_pos: Block,
+}
+
+impl Default for HudLayout {
+    /// Putting the score and length bar on the left of the score strip, the speed on the right and the combo on the
+    /// top border.
+    fn default() -> Self {
+        Self {
+            score_pos: Block::new(1, -1),
+            speed_pos: Block::new(-1, -1),
+            length_pos: Block::new(1, -1),
+            combo_pos: Block::new(1, 0),
+        }
+    }
+}
+
+impl HudLayout {
+    /// Find where an element is drawn on a window of a given size.
+    /// # Arguments
+    /// * `position: Block` - The position of the element in the layout.
+    /// * `size: (i32, i32)` - The width and height of the window in blocks, including the score strip.
+    /// # Returns
+    /// * `(Block, bool)` - The position counted from the top left corner, and whether (true) or not (false) the
+    ///   element ends at it rather than starts at it.
+    pub fn resolve(position: Block, (width, height): (i32, i32)) -> (Block, bool) {
+        let x = if position.x < 0 {
+            width + position.x
+        } else {
+            position.x
+        };
+        let y = if position.y < 0 {
+            height + position.y
+        } else {
+            position.y
+        };
+        (Block::new(x, y), position.x < 0)
+    }
+}
+
+/// Why the options cannot be played with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The board is smaller than `MIN_WIDTH` by `MIN_HEIGHT`, with both sizes as (width, height).
+    BoardTooSmall { found: (i32, i32), min: (i32, i32) },
+    /// A moving period, either the base one or one from the table, is not a positive number of seconds.
+    NonPositivePeriod(f64),
+    /// The speed factor would not speed the game up, or even stop it.
+    InvalidSpeedFactor(f64),
+    /// The game would speed up without eating any food.
+    NoFoodsPerSpeedIncrease,
+    /// The size of a block on screen is not between `MIN_CELL_SIZE` and `MAX_CELL_SIZE` pixels.
+    InvalidCellSize(u32),
+    /// Every kind of food has a weight of zero, so no food can spawn.
+    NoFoodWeight,
+    /// The factor all text sizes are multiplied by is not between `MIN_FONT_SCALE` and `MAX_FONT_SCALE`.
+    InvalidFontScale(f64),
+    /// The first food would be placed on or outside the borders.
+    InitialFoodOutOfBounds(Block),
+    /// The smallest distance between the head and newly spawned food is negative.
+    NegativeFoodSpawnDistance(i32),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::BoardTooSmall { found, min } => write!(
+                f,
+                "the board is {}x{} but should be at least {}x{}",
+                found.0, found.1, min.0, min.1
+            ),
+            ConfigError::NonPositivePeriod(period) => {
+                write!(f, "the moving period {} is not positive", period)
+            }
+            ConfigError::InvalidSpeedFactor(factor) => {
+                write!(f, "the speed factor {} is not between 0 and 1", factor)
+            }
+            ConfigError::NoFoodsPerSpeedIncrease => {
+                write!(f, "the game should speed up after at least one food")
+            }
+            ConfigError::InvalidCellSize(size) => write!(
+                f,
+                "the cell size {} is not between {} and {} pixels",
+                size, MIN_CELL_SIZE, MAX_CELL_SIZE
+            ),
+            ConfigError::NoFoodWeight => write!(f, "at least one kind of food should spawn"),
+            ConfigError::InvalidFontScale(scale) => write!(
+                f,
+                "the font scale {} is not between {} and {}",
+                scale, MIN_FONT_SCALE, MAX_FONT_SCALE
+            ),
+            ConfigError::InitialFoodOutOfBounds(food) => write!(
+                f,
+                "the initial food at ({}, {}) is not inside the borders",
+                food.x, food.y
+            ),
+            ConfigError::NegativeFoodSpawnDistance(distance) => write!(
+                f,
+                "the food spawn distance {} should not be negative",
+                distance
+            ),
+        }
+    }
+}
+
+impl GameConfig {
+    /// Check whether a game can be created from the options.
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Nothing, or why the options cannot be played with.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.width < MIN_WIDTH || self.height < MIN_HEIGHT {
+            return Err(ConfigError::BoardTooSmall {
+                found: (self.width, self.height),
+                min: (MIN_WIDTH, MIN_HEIGHT),
+            });
+        }
+        let periods = self.speed_periods.iter().flatten();
+        if let Some(period) = std::iter::once(&self.moving_period)
+            .chain(periods)
+            .find(|period| period.is_nan() || **period <= 0.0)
+        {
+            return Err(ConfigError::NonPositivePeriod(*period));
+        }
+        if !(self.speed_factor > 0.0 && self.speed_factor < 1.0) {
+            return Err(ConfigError::InvalidSpeedFactor(self.speed_factor));
+        }
+        if self.foods_per_speed_increase == 0 {
+            return Err(ConfigError::NoFoodsPerSpeedIncrease);
+        }
+        if !(MIN_CELL_SIZE..=MAX_CELL_SIZE).contains(&self.cell_size) {
+            return Err(ConfigError::InvalidCellSize(self.cell_size));
+        }
+        if self.food_weights.total() == 0 {
+            return Err(ConfigError::NoFoodWeight);
+        }
+        if !(MIN_FONT_SCALE..=MAX_FONT_SCALE).contains(&self.font_scale) {
+            return Err(ConfigError::InvalidFontScale(self.font_scale));
+        }
+        if let Some(food) = self
+            .initial_food
+            .filter(|food| food.out_of_bounds([0, self.width], [0, self.height]))
+        {
+            return Err(ConfigError::InitialFoodOutOfBounds(food));
+        }
+        if self.food_spawn_min_distance < 0 {
+            return Err(ConfigError::NegativeFoodSpawnDistance(
+                self.food_spawn_min_distance,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the time between two Snake moves after a number of speed increases, before the difficulty factor.
+    /// # Arguments
+    /// * `steps: f64` - The number of speed increases, where a fraction lies in between two speed levels.
+    /// # Returns
+    /// * `f64` - The period from the table, interpolated between levels and clamped past its end, or else
+    ///   from `moving_period` and `speed_factor`.
+    pub fn period_at(&self, steps: f64) -> f64 {
+        match self.speed_periods.as_deref() {
+            Some([first, rest @ ..]) => {
+                let last = rest.len();
+                let index = (steps.max(0.0) as usize).min(last);
+                let period = |index: usize| if index == 0 { *first } else { rest[index - 1] };
+                if index == last {
+                    return period(last);
+                }
+                let t = steps - index as f64;
+                period(index) + (period(index + 1) - period(index)) * t
+            }
+            _ => self.moving_period * self.speed_factor.powf(steps),
+        }
+    }
+
+    /// Take over the options that can change in the middle of a run: how the game looks and the accessibility options.
+    /// Everything that changes how the game plays or is scored is kept, to apply at the next run.
+    /// # Arguments
+    /// * `other: &GameConfig` - The options to take the cosmetic and accessibility ones from.
+    /// # Returns
+    /// * `GameConfig` - These options, with the cosmetic and accessibility ones of `other`.
+    pub fn with_live_options(&self, other: &GameConfig) -> GameConfig {
+        GameConfig {
+            default_player_name: other.default_player_name.clone(),
+            reduce_motion: other.reduce_motion,
+            palette: other.palette,
+            theme_file: other.theme_file.clone(),
+            theme_colors: other.theme_colors.clone(),
+            high_contrast: other.high_contrast,
+            gradient_background: other.gradient_background,
+            timestamp_format: other.timestamp_format,
+            utc_timestamps: other.utc_timestamps,
+            hud_layout: other.hud_layout,
+            cell_size: other.cell_size,
+            font_scale: other.font_scale,
+            ..self.clone()
+        }
+    }
+
+    /// Get all colors the game is drawn with, taking the palette or theme file and high contrast mode into account.
+    pub fn theme(&self) -> Theme {
+        let theme = self
+            .theme_colors
+            .clone()
+            .unwrap_or_else(|| self.palette.theme());
+        let theme = if self.high_contrast {
+            theme.with_high_contrast()
+        } else {
+            theme
+        };
+        // A flat background is a gradient between the same colors.
+        if self.gradient_background {
+            theme
+        } else {
+            Theme {
+                background_bottom: theme.background,
+                ..theme
+            }
+        }
+    }
+
+    /// Get the factor the whole game, including all text, is scaled up by when drawn. Everything is laid out for
+    /// blocks of `DEFAULT_CELL_SIZE` pixels, so the scale maps those to the configured cell size.
+    pub fn render_scale(&self) -> f64 {
+        let contrast = if self.high_contrast {
+            HIGH_CONTRAST_SCALE
+        } else {
+            1.0
+        };
+        contrast * self.cell_size as f64 / DEFAULT_CELL_SIZE as f64
+    }
+
+    /// Get a text size from the block size, scaled by the configured font scale. Text is laid out in blocks of
+    /// `DEFAULT_CELL_SIZE` pixels like everything else, so it grows along with the cell size on screen.
+    /// # Arguments
+    /// * `ratio: f64` - The text size relative to the block size, e.g. 0.8 for the score.
+    /// # Returns
+    /// * `u32` - The text size to draw with.
+    pub fn font_size(&self, ratio: f64) -> u32 {
+        (ratio * DEFAULT_CELL_SIZE as f64 * self.font_scale).round() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hud_positions_count_back_from_negative_coordinates() {
+        let size = (20, 20);
+        assert_eq!(
+            HudLayout::resolve(Block::new(1, 0), size),
+            (Block::new(1, 0), false)
+        );
+        assert_eq!(
+            HudLayout::resolve(Block::new(-1, -1), size),
+            (Block::new(19, 19), true)
+        );
+        // A layout missing positions keeps the defaults for them.
+        let layout: HudLayout =
+            serde_json::from_str(r#"{"score_pos": {"x": -2, "y": 0}}"#).unwrap();
+        assert_eq!(layout.score_pos, Block::new(-2, 0));
+        assert_eq!(layout.speed_pos, HudLayout::default().speed_pos);
+    }
+
+    #[test]
+    fn boards_below_the_minimum_size_are_invalid() {
+        assert!(GameConfig::default().validate().is_ok());
+        for (width, height) in BOARD_SIZES {
+            assert!(GameConfig {
+                width,
+                height,
+                ..GameConfig::default()
+            }
+            .validate()
+            .is_ok());
+        }
+        let config = GameConfig {
+            width: MIN_WIDTH,
+            height: MIN_HEIGHT - 1,
+            ..GameConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::BoardTooSmall {
+                found: (MIN_WIDTH, MIN_HEIGHT - 1),
+                min: (MIN_WIDTH, MIN_HEIGHT),
+            })
+        );
+    }
+
+    #[test]
+    fn speed_periods_interpolate_and_clamp() {
+        let config = GameConfig::default();
+        assert_eq!(config.period_at(0.0), DEFAULT_MOVING_PERIOD);
+        assert!((config.period_at(2.0) - 0.32).abs() < 1e-12);
+
+        let config = GameConfig {
+            speed_periods: Some(vec![0.4, 0.3, 0.1]),
+            ..GameConfig::default()
+        };
+        assert_eq!(config.period_at(0.0), 0.4);
+        assert!((config.period_at(0.5) - 0.35).abs() < 1e-12);
+        assert!((config.period_at(1.5) - 0.2).abs() < 1e-12);
+        assert_eq!(config.period_at(2.0), 0.1);
+        assert_eq!(config.period_at(7.5), 0.1);
+    }
+
+    #[test]
+    fn speed_curves_that_slow_down_are_invalid() {
+        let config = GameConfig {
+            speed_periods: Some(vec![0.4, 0.0]),
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::NonPositivePeriod(0.0)));
+        let config = GameConfig {
+            moving_period: -0.5,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::NonPositivePeriod(-0.5)));
+        let config = GameConfig {
+            speed_factor: 1.0,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::InvalidSpeedFactor(1.0)));
+        let config = GameConfig {
+            foods_per_speed_increase: 0,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::NoFoodsPerSpeedIncrease));
+    }
+
+    #[test]
+    fn render_scale_follows_the_cell_size() {
+        let config = GameConfig {
+            cell_size: 32,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.render_scale(), 1.28);
+        assert_eq!(GameConfig::default().render_scale(), 1.0);
+        let config = GameConfig {
+            cell_size: 16,
+            high_contrast: true,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.render_scale(), 16.0 / 25.0 * HIGH_CONTRAST_SCALE);
+        let config = GameConfig {
+            cell_size: 4,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::InvalidCellSize(4)));
+    }
+
+    #[test]
+    fn some_food_should_spawn() {
+        let config = GameConfig {
+            food_weights: FoodWeights {
+                normal: 0,
+                bonus: 0,
+            },
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::NoFoodWeight));
+    }
+
+    #[test]
+    fn font_scale_multiplies_text_sizes() {
+        let config = GameConfig {
+            font_scale: 1.5,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.font_size(0.8), 30);
+        assert_eq!(GameConfig::default().font_size(0.6), 15);
+        let config = GameConfig {
+            font_scale: 4.0,
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::InvalidFontScale(4.0)));
+    }
+
+    #[test]
+    fn food_spawn_distance_should_not_be_negative() {
+        let config = GameConfig {
+            food_spawn_min_distance: -1,
+            ..GameConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::NegativeFoodSpawnDistance(-1))
+        );
+    }
+
+    #[test]
+    fn initial_food_should_be_inside_the_borders() {
+        let config = GameConfig {
+            initial_food: Some(Block::new(DEFAULT_WIDTH - 2, 1)),
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+        for food in [Block::new(0, 5), Block::new(5, DEFAULT_HEIGHT - 1)] {
+            let config = GameConfig {
+                initial_food: Some(food),
+                ..GameConfig::default()
+            };
+            assert_eq!(
+                config.validate(),
+                Err(ConfigError::InitialFoodOutOfBounds(food))
+            );
+        }
+    }
+}
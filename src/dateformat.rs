@@ -1,9 +1,82 @@
-use chrono::{DateTime, TimeZone, Utc};
-use serde::{self, Deserialize, Deserializer, Serializer};
+use crate::locale::Locale;
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 
 pub const FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+/// A format other tools commonly write timestamps in, read next to `FORMAT` and RFC 3339.
+pub const DASHED_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 pub const DISPLAY_FORMAT: &str = "%Y/%m/%d";
 
+/// The ways a timestamp can be shown on screen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum DisplayFormat {
+    /// The date, formatted with `DISPLAY_FORMAT`.
+    Absolute,
+    /// The time elapsed since, like "5 min ago".
+    #[default]
+    Relative,
+}
+
+impl DisplayFormat {
+    /// Format a timestamp for display, in the local timezone or in UTC.
+    /// # Arguments
+    /// * `dt: &DateTime<Utc>` - The timestamp to format.
+    /// * `utc: bool` - Whether (true) or not (false) to show the date in UTC rather than the local timezone.
+    /// * `locale: &Locale` - The language relative times are described in.
+    /// # Returns
+    /// * `String` - The formatted timestamp.
+    pub fn format(&self, dt: &DateTime<Utc>, utc: bool, locale: &Locale) -> String {
+        if utc {
+            self.format_in(dt, &Utc, locale)
+        } else {
+            self.format_in(dt, &Local, locale)
+        }
+    }
+
+    /// Format a timestamp for display in a timezone, see `format`.
+    /// # Arguments
+    /// * `dt: &DateTime<Utc>` - The timestamp to format.
+    /// * `zone: &Tz` - The timezone to show the date in. Relative times are the same in every timezone.
+    /// * `locale: &Locale` - The language relative times are described in.
+    /// # Returns
+    /// * `String` - The formatted timestamp.
+    pub fn format_in<Tz: TimeZone>(&self, dt: &DateTime<Utc>, zone: &Tz, locale: &Locale) -> String
+    where
+        Tz::Offset: fmt::Display,
+    {
+        match *self {
+            DisplayFormat::Absolute => dt.with_timezone(zone).format(DISPLAY_FORMAT).to_string(),
+            DisplayFormat::Relative => display_relative_time(dt, locale),
+        }
+    }
+}
+
+/// Describe the time elapsed since a timestamp in the largest fitting unit.
+/// # Arguments
+/// * `dt: &DateTime<Utc>` - The timestamp in the past.
+/// * `locale: &Locale` - The language to describe the time in.
+/// # Returns
+/// * `String` - "just now" within the last minute, otherwise e.g. "5 min ago" or "2 weeks ago" in English.
+pub fn display_relative_time(dt: &DateTime<Utc>, locale: &Locale) -> String {
+    relative_time(Utc::now() - *dt, locale)
+}
+
+/// Describe an elapsed duration, see `display_relative_time`.
+fn relative_time(elapsed: Duration, locale: &Locale) -> String {
+    if elapsed < Duration::minutes(1) {
+        locale.get("time_just_now").to_string()
+    } else if elapsed < Duration::hours(1) {
+        locale.format_count("time_minutes_ago", elapsed.num_minutes())
+    } else if elapsed < Duration::days(1) {
+        locale.format_count("time_hours_ago", elapsed.num_hours())
+    } else if elapsed < Duration::weeks(1) {
+        locale.format_count("time_days_ago", elapsed.num_days())
+    } else {
+        locale.format_count("time_weeks_ago", elapsed.num_weeks())
+    }
+}
+
 pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -12,11 +85,155 @@ where
     serializer.serialize_str(&s)
 }
 
+/// Parse a timestamp, trying `FORMAT` first, then RFC 3339 and finally `DASHED_FORMAT`.
+/// # Arguments
+/// * `s: &str` - The timestamp to parse.
+/// # Returns
+/// * `Option<DateTime<Utc>>` - The timestamp, or None if it matches none of the formats.
+pub fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    Utc.datetime_from_str(s, FORMAT)
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc))
+        })
+        .or_else(|| Utc.datetime_from_str(s, DASHED_FORMAT).ok())
+}
+
 pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    Utc.datetime_from_str(&s, FORMAT)
-        .map_err(serde::de::Error::custom)
+    parse_timestamp(&s).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "invalid timestamp \"{}\", expected one like \"2024/02/29 23:59:58\"",
+            s
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Stamped {
+        #[serde(with = "super")]
+        timestamp: DateTime<Utc>,
+    }
+
+    fn leap_day() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 2, 29, 23, 59, 58).unwrap()
+    }
+
+    #[test]
+    fn serialize_deserialize_round_trip() {
+        let stamped = Stamped {
+            timestamp: leap_day(),
+        };
+        let json = serde_json::to_string(&stamped).unwrap();
+        assert_eq!(json, r#"{"timestamp":"2024/02/29 23:59:58"}"#);
+        assert_eq!(serde_json::from_str::<Stamped>(&json).unwrap(), stamped);
+    }
+
+    #[test]
+    fn format_parses_back() {
+        let formatted = leap_day().format(FORMAT).to_string();
+        assert_eq!(
+            Utc.datetime_from_str(&formatted, FORMAT).unwrap(),
+            leap_day()
+        );
+    }
+
+    #[test]
+    fn display_format_shows_only_the_date() {
+        assert_eq!(leap_day().format(DISPLAY_FORMAT).to_string(), "2024/02/29");
+        assert_eq!(
+            DisplayFormat::Absolute.format(&leap_day(), true, &Locale::default()),
+            "2024/02/29"
+        );
+    }
+
+    #[test]
+    fn dates_are_shown_in_the_given_timezone() {
+        let east = FixedOffset::east_opt(2 * 3600).unwrap();
+        let west = FixedOffset::west_opt(3600).unwrap();
+        assert_eq!(
+            DisplayFormat::Absolute.format_in(&leap_day(), &east, &Locale::default()),
+            "2024/03/01"
+        );
+        assert_eq!(
+            DisplayFormat::Absolute.format_in(&leap_day(), &west, &Locale::default()),
+            "2024/02/29"
+        );
+        assert_eq!(
+            DisplayFormat::Absolute.format_in(&leap_day(), &Utc, &Locale::default()),
+            "2024/02/29"
+        );
+        let now = Utc::now();
+        assert_eq!(
+            DisplayFormat::Relative.format_in(&now, &east, &Locale::default()),
+            "just now"
+        );
+    }
+
+    #[test]
+    fn invalid_dates_do_not_deserialize() {
+        let error = serde_json::from_str::<Stamped>(r#"{"timestamp":"2023/02/29 12:00:00"}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("\"2023/02/29 12:00:00\""), "{error}");
+    }
+
+    #[test]
+    fn other_formats_deserialize_and_serialize_canonically() {
+        for json in [
+            r#"{"timestamp":"2024/02/29 23:59:58"}"#,
+            r#"{"timestamp":"2024-02-29T23:59:58Z"}"#,
+            r#"{"timestamp":"2024-03-01T01:59:58+02:00"}"#,
+            r#"{"timestamp":"2024-02-29 23:59:58"}"#,
+        ] {
+            let stamped: Stamped = serde_json::from_str(json).unwrap();
+            assert_eq!(stamped.timestamp, leap_day(), "{json}");
+            assert_eq!(
+                serde_json::to_string(&stamped).unwrap(),
+                r#"{"timestamp":"2024/02/29 23:59:58"}"#
+            );
+        }
+    }
+
+    #[test]
+    fn relative_time_picks_the_largest_unit() {
+        let english = Locale::default();
+        let relative_time = |elapsed| relative_time(elapsed, &english);
+        assert_eq!(relative_time(Duration::seconds(59)), "just now");
+        assert_eq!(relative_time(Duration::seconds(-5)), "just now");
+        assert_eq!(relative_time(Duration::minutes(5)), "5 min ago");
+        assert_eq!(relative_time(Duration::minutes(61)), "1 hour ago");
+        assert_eq!(relative_time(Duration::hours(23)), "23 hours ago");
+        assert_eq!(relative_time(Duration::days(6)), "6 days ago");
+        assert_eq!(relative_time(Duration::days(15)), "2 weeks ago");
+    }
+
+    #[test]
+    fn relative_time_follows_the_language() {
+        let dutch = Locale::load(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/locales"),
+            "nl",
+        );
+        assert_eq!(relative_time(Duration::seconds(5), &dutch), "zojuist");
+        assert_eq!(relative_time(Duration::hours(1), &dutch), "1 uur geleden");
+        assert_eq!(relative_time(Duration::days(15), &dutch), "2 weken geleden");
+    }
+
+    #[test]
+    fn most_recent_score_is_just_now() {
+        assert_eq!(
+            DisplayFormat::Relative.format(&Utc::now(), false, &Locale::default()),
+            "just now"
+        );
+    }
 }
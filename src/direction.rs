@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 
 // Create a Direction enum, acting as a generic type holding all 4 possible directions.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Direction {
     Up,
     Down,
@@ -10,6 +10,20 @@ pub enum Direction {
 }
 
 impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+    /// Every direction paired with the x- and y-difference of a single step in it.
+    pub const CARDINAL_OFFSETS: [(Direction, i32, i32); 4] = [
+        (Direction::Up, 0, -1),
+        (Direction::Down, 0, 1),
+        (Direction::Left, -1, 0),
+        (Direction::Right, 1, 0),
+    ];
+
     /// Returns the opposite direction from the current.
     pub fn opposite(&self) -> Direction {
         match *self {
@@ -20,12 +34,70 @@ impl Direction {
         }
     }
 
-    pub fn offsets() -> HashMap<Direction, [i32; 2]> {
-        HashMap::from([
-            (Direction::Up, [0, -1]),
-            (Direction::Down, [0, 1]),
-            (Direction::Left, [-1, 0]),
-            (Direction::Right, [1, 0]),
-        ])
+    /// Check whether the direction points Left or Right.
+    #[inline]
+    pub fn is_horizontal(&self) -> bool {
+        matches!(*self, Direction::Left | Direction::Right)
+    }
+
+    /// Check whether the direction points Up or Down.
+    #[inline]
+    pub fn is_vertical(&self) -> bool {
+        !self.is_horizontal()
+    }
+
+    /// Find the direction that moves one block by the given offset.
+    /// # Arguments
+    /// * `offset: [i32; 2]` - The x- and y-difference between two blocks.
+    /// # Returns
+    /// * `Option<Direction>` - The direction, or None if the offset is not a single step.
+    pub fn from_offset(offset: [i32; 2]) -> Option<Direction> {
+        Direction::offsets()
+            .into_iter()
+            .find(|(_, direction_offset)| *direction_offset == offset)
+            .map(|(direction, _)| direction)
+    }
+
+    /// Get the x- and y-difference of a single step in this direction.
+    pub fn offset(&self) -> [i32; 2] {
+        // The offsets are listed in the order the variants are declared in.
+        let (_, dx, dy) = Direction::CARDINAL_OFFSETS[*self as usize];
+        [dx, dy]
+    }
+
+    /// Get every direction paired with the x- and y-difference of a single step in it.
+    pub fn offsets() -> [(Direction, [i32; 2]); 4] {
+        Direction::CARDINAL_OFFSETS.map(|(direction, dx, dy)| (direction, [dx, dy]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn horizontal_and_vertical() {
+        assert!(Direction::Left.is_horizontal() && Direction::Right.is_horizontal());
+        assert!(Direction::Up.is_vertical() && Direction::Down.is_vertical());
+        assert!(!Direction::Up.is_horizontal() && !Direction::Left.is_vertical());
+    }
+
+    #[test]
+    fn from_offset_inverts_offsets() {
+        for (direction, offset) in Direction::offsets() {
+            assert_eq!(Direction::from_offset(offset), Some(direction));
+        }
+        assert_eq!(Direction::from_offset([1, 1]), None);
+    }
+
+    #[test]
+    fn offsets_cover_all_directions_in_order() {
+        for (direction, (offset_direction, dx, dy)) in
+            Direction::ALL.into_iter().zip(Direction::CARDINAL_OFFSETS)
+        {
+            assert_eq!(direction, offset_direction);
+            assert_eq!(direction.offset(), [dx, dy]);
+            assert_eq!(Direction::from_offset([dx, dy]), Some(direction));
+        }
     }
 }
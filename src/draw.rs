@@ -1,16 +1,24 @@
 // External imports.
 use piston_window::text;
 use piston_window::types::Color;
-use piston_window::{rectangle, Context, G2d, Glyphs, Transformed};
+use piston_window::{line, rectangle, CharacterCache, Context, G2d, Glyphs, Transformed};
 
 // Local imports.
 use crate::block::Block;
-use crate::dateformat;
+use crate::config::DEFAULT_CELL_SIZE;
+use crate::dateformat::DisplayFormat;
+use crate::direction::Direction;
+use crate::locale::Locale;
 use crate::score;
+use crate::theme::SNAKE_BLOCK_RATIO;
 
-// Setting up a constant for the block size in pixels.
-pub const BLOCK_SIZE: f64 = 25.0;
-pub const SNAKE_BLOCK_SIZE: f64 = 20.0;
+// The block size everything is laid out in. The render scale maps it to the configured cell size on screen.
+pub const BLOCK_SIZE: f64 = DEFAULT_CELL_SIZE as f64;
+pub const SNAKE_BLOCK_SIZE: f64 = BLOCK_SIZE * SNAKE_BLOCK_RATIO;
+// The distance between two lines of text, relative to the font size.
+pub const LINE_SPACING: f64 = 1.1;
+// The smallest size text is shrunk to when it does not fit.
+pub const MIN_FONT_SIZE: u32 = 8;
 
 /// Convert game coordinates to pixel values.
 /// # Arguments
@@ -43,6 +51,48 @@ pub fn draw_block(
     rectangle(color, [gui_x, gui_y, size[0], size[1]], con.transform, g)
 }
 
+/// Draw a single block filling its entire cell, with an outline along the inside of its edges.
+/// # Arguments
+/// * `block: Block` - The block to draw.
+/// * `fill: piston_window::types::Color` - The color of the block.
+/// * `outline: piston_window::types::Color` - The color of the outline.
+/// * `outline_width: f64` - The width of the outline in pixels.
+/// * `con`: &piston_window::Context - A reference to the games context.
+/// * `g`: &mut piston_window::G2d - A mutable reference to the graphics engine used for drawing.
+pub fn draw_outlined_block(
+    block: Block,
+    fill: Color,
+    outline: Color,
+    outline_width: f64,
+    con: &Context,
+    g: &mut G2d,
+) {
+    draw_block(block, fill, [0.0, 0.0], [BLOCK_SIZE, BLOCK_SIZE], con, g);
+    let far_edge = BLOCK_SIZE - outline_width;
+    // Drawing the top, bottom, left and right edge.
+    for (offset, size) in [
+        ([0.0, 0.0], [BLOCK_SIZE, outline_width]),
+        ([0.0, far_edge], [BLOCK_SIZE, outline_width]),
+        ([0.0, 0.0], [outline_width, BLOCK_SIZE]),
+        ([far_edge, 0.0], [outline_width, BLOCK_SIZE]),
+    ] {
+        draw_block(block, outline, offset, size, con, g);
+    }
+}
+
+/// Draw a small square marker centered on a block, to tell blocks apart by shape rather than by color alone.
+/// # Arguments
+/// * `block: Block` - The block to mark.
+/// * `color: piston_window::types::Color` - The color of the marker.
+/// * `ratio: f64` - The side length of the marker, relative to the block size.
+/// * `con`: &piston_window::Context - A reference to the games context.
+/// * `g`: &mut piston_window::G2d - A mutable reference to the graphics engine used for drawing.
+pub fn draw_marker(block: Block, color: Color, ratio: f64, con: &Context, g: &mut G2d) {
+    let size = BLOCK_SIZE * ratio;
+    let offset = (BLOCK_SIZE - size) / 2.0;
+    draw_block(block, color, [offset, offset], [size, size], con, g)
+}
+
 /// Draw a rectangle composed of blocks in the context.
 /// # Arguments
 /// * `color: piston_window::types::Color` - The color of the rectangle.
@@ -75,11 +125,39 @@ pub fn draw_rectangle(
     )
 }
 
-/// Draw a string in the context.
+/// Draw a vertical gradient in the context, from the top left corner of the window, as horizontal strips of two rows
+/// each. The color of every strip is linearly interpolated at its center.
+/// # Arguments
+/// * `top_color: piston_window::types::Color` - The color at the top edge.
+/// * `bottom_color: piston_window::types::Color` - The color at the bottom edge.
+/// * `width: i32` - The width of the gradient in blocks.
+/// * `height: i32` - The height of the gradient in blocks.
+/// * `con: &piston_window::Context` - A reference to the games context.
+/// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
+pub fn draw_gradient_background(
+    top_color: Color,
+    bottom_color: Color,
+    width: i32,
+    height: i32,
+    con: &Context,
+    g: &mut G2d,
+) {
+    for y in (0..height).step_by(2) {
+        let rows = (height - y).min(2);
+        let t = ((y as f32) + (rows as f32) / 2.0) / (height as f32);
+        let mut color = top_color;
+        for (channel, bottom) in color.iter_mut().zip(bottom_color) {
+            *channel += (bottom - *channel) * t;
+        }
+        draw_rectangle(color, Block::new(0, y), width, rows, con, g);
+    }
+}
+
+/// Draw a string in the context, anchored to a Block.
 /// # Arguments
 /// * `text: &str` - The string to draw.
-/// * `x: i32` - The x coordinate in game coordinates.
-/// * `y: i32` - The y coordinate in game coordinates.
+/// * `top_left: Block` - The top left corner of the text box, in game coordinates. The first line is drawn below the
+///   top of this Block rather than on it, see `draw_text_at_pixels`.
 /// * `color: piston_window::Color` - The text color.
 /// * `font_size: u32` - The text size.
 /// * `glyphs: &mut piston_window::Glyphs` - The characterset to use.
@@ -94,39 +172,222 @@ pub fn draw_text(
     con: &Context,
     g: &mut G2d,
 ) {
+    let gui_x = to_pixels(top_left.x);
+    let gui_top = to_pixels(top_left.y);
+    draw_text_at_pixels(text, gui_x, gui_top, color, font_size, glyphs, con, g);
+}
+
+/// Get how many screen pixels a pixel of the layout covers, e.g. after the context is scaled up to the cell size.
+/// # Arguments
+/// * `con: &piston_window::Context` - The context that is drawn in.
+/// # Returns
+/// * `f64` - The scale, including the ratio between the window and its framebuffer on high-DPI screens.
+pub fn pixel_scale(con: &Context) -> f64 {
+    let dpi = con.viewport.map_or(1.0, |viewport| {
+        viewport.draw_size[0] as f64 / viewport.window_size[0]
+    });
+    con.transform[0][0] / con.view[0][0] * dpi
+}
+
+/// Draw a string in the context, anchored to a pixel position.
+/// Text is drawn upwards from its baseline, so the baseline of the first line sits one line height below `top` to keep
+/// it inside the text box. Every next line is placed `LINE_SPACING` times the font size below the previous one. The
+/// glyphs are rasterized at the size they cover on screen, so scaled up text stays sharp.
+/// # Arguments
+/// * `text: &str` - The string to draw.
+/// * `x: f64` - The x coordinate of the left edge of the text box, in pixels.
+/// * `top: f64` - The y coordinate of the top edge of the text box, in pixels.
+/// * `color: piston_window::Color` - The text color.
+/// * `font_size: u32` - The text size.
+/// * `glyphs: &mut piston_window::Glyphs` - The characterset to use.
+/// * `con: &piston_window::Context` - A refrence to the games context.
+/// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_at_pixels(
+    text: &str,
+    x: f64,
+    top: f64,
+    color: Color,
+    font_size: u32,
+    glyphs: &mut Glyphs,
+    con: &Context,
+    g: &mut G2d,
+) {
+    // The glyph cache rasterizes every new size once, so a changed scale gets its own glyphs.
+    let screen_size = ((font_size as f64 * pixel_scale(con)).round() as u32).max(1);
+    let shrink = font_size as f64 / screen_size as f64;
     for (i_line, line) in text.split('\n').enumerate() {
-        let gui_x = to_pixels(top_left.x);
-        let gui_y = to_pixels(top_left.y) + (font_size * (i_line + 1) as u32) as f64 * 1.1;
-        text::Text::new_color(color, font_size)
+        let baseline = top + font_size as f64 * (i_line + 1) as f64 * LINE_SPACING;
+        text::Text::new_color(color, screen_size)
             .draw(
                 line,
                 glyphs,
                 &con.draw_state,
-                con.transform.trans(gui_x, gui_y),
+                con.transform.trans(x, baseline).scale(shrink, shrink),
                 g,
             )
             .unwrap();
     }
 }
 
-/// Display the current highscores.
+/// Draw a horizontal bar, filled from the left up to a fraction of its width.
 /// # Arguments
-/// * `scores: &[score::Score]` - A slice of the current highscore Vec.
-/// * `top_left: Block` - The location of the top left corner of the text block.
+/// * `x: f64` - The x coordinate of the top left corner, in pixels.
+/// * `y: f64` - The y coordinate of the top left corner, in pixels.
+/// * `size: [f64; 2]` - The width and height of the bar, in pixels.
+/// * `fraction: f64` - The filled part of the bar, clamped between 0 and 1.
+/// * `colors: [piston_window::Color; 2]` - The colors of the filled and the empty part.
+/// * `con: &piston_window::Context` - A refrence to the games context.
+/// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
+pub fn draw_progress_bar(
+    x: f64,
+    y: f64,
+    size: [f64; 2],
+    fraction: f64,
+    colors: [Color; 2],
+    con: &Context,
+    g: &mut G2d,
+) {
+    let filled = size[0] * fraction.clamp(0.0, 1.0);
+    rectangle(colors[1], [x, y, size[0], size[1]], con.transform, g);
+    rectangle(colors[0], [x, y, filled, size[1]], con.transform, g);
+}
+
+/// Measure the width of the widest line of a string.
+/// # Arguments
+/// * `text: &str` - The string to measure.
+/// * `font_size: u32` - The text size.
+/// * `glyphs: &mut impl CharacterCache` - The characterset to measure with, e.g. `piston_window::Glyphs`.
+/// # Returns
+/// * `f64` - The width of the widest line, in pixels.
+pub fn text_width(text: &str, font_size: u32, glyphs: &mut impl CharacterCache) -> f64 {
+    text.split('\n')
+        .map(|line| glyphs.width(font_size, line).unwrap_or(0.0))
+        .fold(0.0, f64::max)
+}
+
+/// Shrink a font size until every line of a string fits within a width, e.g. for translations of different lengths.
+/// # Arguments
+/// * `text: &str` - The string to fit.
+/// * `font_size: u32` - The preferred text size.
+/// * `max_width: f64` - The available width, in pixels.
+/// * `glyphs: &mut impl CharacterCache` - The characterset to measure with, e.g. `piston_window::Glyphs`.
+/// # Returns
+/// * `u32` - The largest text size up to `font_size` that fits, but never below `MIN_FONT_SIZE`.
+pub fn fit_font_size(
+    text: &str,
+    font_size: u32,
+    max_width: f64,
+    glyphs: &mut impl CharacterCache,
+) -> u32 {
+    let mut size = font_size;
+    while size > MIN_FONT_SIZE && text_width(text, size, glyphs) > max_width {
+        size -= 1;
+    }
+    size
+}
+
+/// Draw a single line of text centered on a block.
+/// # Arguments
+/// * `text: &str` - The string to draw.
+/// * `block: Block` - The block to center the text on.
 /// * `color: piston_window::Color` - The text color.
 /// * `font_size: u32` - The text size.
 /// * `glyphs: &mut piston_window::Glyphs` - The characterset to use.
 /// * `con: &piston_window::Context` - A refrence to the games context.
 /// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
-pub fn show_scores(
-    scores: &[score::Score],
-    top_left: Block,
+pub fn draw_text_centered(
+    text: &str,
+    block: Block,
     color: Color,
     font_size: u32,
     glyphs: &mut Glyphs,
     con: &Context,
     g: &mut G2d,
 ) {
+    let gui_x = to_pixels(block.x) + (BLOCK_SIZE - text_width(text, font_size, glyphs)) / 2.0;
+    let gui_top = to_pixels(block.y) + (BLOCK_SIZE - font_size as f64 * LINE_SPACING) / 2.0;
+    draw_text_at_pixels(text, gui_x, gui_top, color, font_size, glyphs, con, g);
+}
+
+/// Draw a small speaker icon filling a single block, crossed out when muted.
+/// # Arguments
+/// * `block: Block` - The block to draw the icon in.
+/// * `muted: bool` - Whether (true) or not (false) the sound is muted.
+/// * `color: piston_window::Color` - The color of the speaker.
+/// * `muted_color: piston_window::Color` - The color of the cross or the sound waves.
+/// * `con: &piston_window::Context` - A refrence to the games context.
+/// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
+pub fn draw_speaker_icon(
+    block: Block,
+    muted: bool,
+    color: Color,
+    muted_color: Color,
+    con: &Context,
+    g: &mut G2d,
+) {
+    let (x, y) = (to_pixels(block.x), to_pixels(block.y));
+    let unit = BLOCK_SIZE / 10.0;
+    // The magnet and the cone of the speaker.
+    rectangle(
+        color,
+        [x + unit, y + 3.5 * unit, 2.0 * unit, 3.0 * unit],
+        con.transform,
+        g,
+    );
+    rectangle(
+        color,
+        [x + 3.0 * unit, y + 2.0 * unit, 2.0 * unit, 6.0 * unit],
+        con.transform,
+        g,
+    );
+    if muted {
+        let radius = unit * 0.4;
+        let cross = [
+            x + 6.0 * unit,
+            y + 3.0 * unit,
+            x + 9.0 * unit,
+            y + 7.0 * unit,
+        ];
+        line(muted_color, radius, cross, con.transform, g);
+        let cross = [
+            x + 6.0 * unit,
+            y + 7.0 * unit,
+            x + 9.0 * unit,
+            y + 3.0 * unit,
+        ];
+        line(muted_color, radius, cross, con.transform, g);
+    } else {
+        // Two sound waves of increasing size.
+        rectangle(
+            color,
+            [x + 6.0 * unit, y + 4.0 * unit, unit, 2.0 * unit],
+            con.transform,
+            g,
+        );
+        rectangle(
+            color,
+            [x + 8.0 * unit, y + 2.5 * unit, unit, 5.0 * unit],
+            con.transform,
+            g,
+        );
+    }
+}
+
+/// Format the current highscores, one row per rank.
+/// # Arguments
+/// * `scores: &[score::Score]` - A slice of the current highscore Vec.
+/// * `timestamp_format: DisplayFormat` - How to show when each score was set.
+/// * `utc: bool` - Whether (true) or not (false) to show dates in UTC rather than the local timezone.
+/// * `locale: &Locale` - The language relative times are described in.
+/// # Returns
+/// * `String` - The rows, each ending in a newline.
+pub fn scoreboard_text(
+    scores: &[score::Score],
+    timestamp_format: DisplayFormat,
+    utc: bool,
+    locale: &Locale,
+) -> String {
     let name_len = score::MAX_NAME_LENGTH;
     let mut text = String::new();
     for rank in 0..score::NUMBER_HIGH_SCORES {
@@ -136,19 +397,56 @@ pub fn show_scores(
             rank + 1,
             score.score(),
             score.player(),
-            score.timestamp().format(dateformat::DISPLAY_FORMAT)
+            timestamp_format.format(score.timestamp(), utc, locale)
         ));
     }
+    text
+}
+
+/// Display the current highscores.
+/// # Arguments
+/// * `scores: &[score::Score]` - A slice of the current highscore Vec.
+/// * `timestamp_format: DisplayFormat` - How to show when each score was set.
+/// * `utc: bool` - Whether (true) or not (false) to show dates in UTC rather than the local timezone.
+/// * `locale: &Locale` - The language relative times are described in.
+/// * `top_left: Block` - The location of the top left corner of the text block.
+/// * `color: piston_window::Color` - The text color.
+/// * `font_size: u32` - The text size.
+/// * `glyphs: &mut piston_window::Glyphs` - The characterset to use.
+/// * `con: &piston_window::Context` - A refrence to the games context.
+/// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
+#[allow(clippy::too_many_arguments)]
+pub fn show_scores(
+    scores: &[score::Score],
+    timestamp_format: DisplayFormat,
+    utc: bool,
+    locale: &Locale,
+    top_left: Block,
+    color: Color,
+    font_size: u32,
+    glyphs: &mut Glyphs,
+    con: &Context,
+    g: &mut G2d,
+) {
+    let text = scoreboard_text(scores, timestamp_format, utc, locale);
     draw_text(&text, top_left, color, font_size, glyphs, con, g);
 }
 
-fn _get_offset_size(delta: i32) -> [f64; 2] {
-    let shift = (BLOCK_SIZE - SNAKE_BLOCK_SIZE) / 2.0;
+/// Get the shift of a snake body block from the edges of its grid block, on either side.
+/// # Arguments
+/// * `block_size: f64` - The size of a grid block.
+/// * `ratio: f64` - The side length of a snake body block, relative to the block size.
+fn _snake_block_shift(block_size: f64, ratio: f64) -> f64 {
+    block_size * (1.0 - ratio) / 2.0
+}
+
+fn _get_offset_size(delta: i32, block_size: f64, ratio: f64) -> [f64; 2] {
+    let shift = _snake_block_shift(block_size, ratio);
     match delta {
-        0 => [shift, SNAKE_BLOCK_SIZE],
-        1 => [-shift, BLOCK_SIZE],
-        -1 => [shift, BLOCK_SIZE],
-        _ => [0.0, BLOCK_SIZE],
+        0 => [shift, block_size * ratio],
+        1 => [-shift, block_size],
+        -1 => [shift, block_size],
+        _ => [0.0, block_size],
     }
 }
 
@@ -156,13 +454,20 @@ fn _get_offset_size(delta: i32) -> [f64; 2] {
 /// # Arguments
 /// * `current: Block` - The current non-digestion block, closer to the tail.
 /// * `previous: Block` - The previous non-digestion block, closer to the head.
+/// * `block_size: f64` - The size of a grid block, which all offsets and sizes are proportional to.
+/// * `ratio: f64` - The side length of a snake body block, relative to the block size.
 /// # Returns
 /// * `[f64; 2]` - The offset and size along x.
 /// * `[f64; 2]` - The offset and size along y.
-pub fn get_offset_size_regular(current: Block, previous: Block) -> ([f64; 2], [f64; 2]) {
+pub fn get_offset_size_regular(
+    current: Block,
+    previous: Block,
+    block_size: f64,
+    ratio: f64,
+) -> ([f64; 2], [f64; 2]) {
     (
-        _get_offset_size(current.x - previous.x),
-        _get_offset_size(current.y - previous.y),
+        _get_offset_size(current.x - previous.x, block_size, ratio),
+        _get_offset_size(current.y - previous.y, block_size, ratio),
     )
 }
 
@@ -171,6 +476,8 @@ pub fn get_offset_size_regular(current: Block, previous: Block) -> ([f64; 2], [f
 /// * `current: Block` - The current non-digestion block, closer to the tail.
 /// * `previous: Block` - The previous non-digestion block, closer to the head.
 /// * `next: Block` - The next digestion block, even closer to the tail.
+/// * `block_size: f64` - The size of a grid block, which all offsets and sizes are proportional to.
+/// * `ratio: f64` - The side length of a snake body block, relative to the block size.
 /// # Returns
 /// * `[f64; 2]` - The offset and size along x.
 /// * `[f64; 2]` - The offset and size along y.
@@ -178,25 +485,66 @@ pub fn get_offset_size_digesting(
     current: Block,
     previous: Block,
     next: Block,
+    block_size: f64,
+    ratio: f64,
 ) -> ([f64; 2], [f64; 2]) {
-    let (mut x_offset_size, mut y_offset_size) = get_offset_size_regular(current, previous);
-    let shift = (BLOCK_SIZE - SNAKE_BLOCK_SIZE) / 2.0;
-
-    match current.x - next.x {
-        -1 => x_offset_size[1] += shift,
-        1 => {
-            x_offset_size[0] -= shift;
-            x_offset_size[1] += shift;
+    let (mut x_offset_size, mut y_offset_size) =
+        get_offset_size_regular(current, previous, block_size, ratio);
+    let shift = _snake_block_shift(block_size, ratio);
+
+    // Stretching the current block towards the digesting block, shifting its offset when growing backwards.
+    if let Some(direction) = Direction::from_offset([next.x - current.x, next.y - current.y]) {
+        let offset_size = if direction.is_horizontal() {
+            &mut x_offset_size
+        } else {
+            &mut y_offset_size
+        };
+        if matches!(direction, Direction::Left | Direction::Up) {
+            offset_size[0] -= shift;
         }
-        _ => match current.y - next.y {
-            1 => {
-                y_offset_size[0] -= shift;
-                y_offset_size[1] += shift;
-            }
-            -1 => y_offset_size[1] += shift,
-            _ => (),
-        },
+        offset_size[1] += shift;
     }
 
     (x_offset_size, y_offset_size)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scale both offsets and sizes of a body block.
+    fn scaled((x, y): ([f64; 2], [f64; 2]), factor: f64) -> ([f64; 2], [f64; 2]) {
+        (x.map(|v| v * factor), y.map(|v| v * factor))
+    }
+
+    #[test]
+    fn body_blocks_keep_their_proportions_at_any_block_size() {
+        let (current, previous, next) = (Block::new(5, 5), Block::new(6, 5), Block::new(5, 6));
+        // Rendering at 16 and 32 pixels, the larger blocks being exactly twice the smaller ones.
+        assert_eq!(
+            get_offset_size_regular(current, previous, 32.0, SNAKE_BLOCK_RATIO),
+            scaled(
+                get_offset_size_regular(current, previous, 16.0, SNAKE_BLOCK_RATIO),
+                2.0
+            )
+        );
+        assert_eq!(
+            get_offset_size_digesting(current, previous, next, 32.0, SNAKE_BLOCK_RATIO),
+            scaled(
+                get_offset_size_digesting(current, previous, next, 16.0, SNAKE_BLOCK_RATIO),
+                2.0
+            )
+        );
+        // A horizontal block is as high as a body block and stretches across the block towards the previous one.
+        let (x, y) = get_offset_size_regular(current, previous, 16.0, SNAKE_BLOCK_RATIO);
+        assert_eq!(y[1], 16.0 * SNAKE_BLOCK_RATIO);
+        assert_eq!(x[1], 16.0);
+    }
+
+    #[test]
+    fn pixel_scale_follows_the_context_scale() {
+        let con = Context::new_abs(250.0, 200.0);
+        assert_eq!(pixel_scale(&con), 1.0);
+        assert_eq!(pixel_scale(&con.scale(1.28, 1.28)), 1.28);
+    }
+}
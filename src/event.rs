@@ -0,0 +1,35 @@
+// External imports.
+use serde::Serialize;
+
+/// Things that happened inside the game, collected by the Game for the frontend to react to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// The Snake ate a piece of food, which escaped `food_moves` times before it was caught.
+    AteFood { food_moves: u32 },
+    /// The Snake died, ending the game.
+    Died,
+    /// The Snake picked up a key, opening its door.
+    OpenedDoor,
+    /// The game moved up a speed level.
+    SpeedUp,
+    /// A menu or prompt option was confirmed.
+    MenuSelect,
+    /// The final score earned a spot on the scoreboard.
+    NewHighScore,
+}
+
+/// What the Snake ran into, ending the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    /// The borders around the playing field.
+    Border,
+    /// Its own body.
+    Body,
+    /// A wall, random obstacle or closed door.
+    Obstacle,
+    /// A hazard row striking while the Snake was in it.
+    Hazard,
+    /// Hazard blocks shrinking it down to its head.
+    Damage,
+}
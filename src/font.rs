@@ -0,0 +1,145 @@
+// External imports.
+use piston_window::glyph_cache::rusttype::GlyphCache;
+use piston_window::texture::{CreateTexture, UpdateTexture};
+use piston_window::{ImageSize, TextureSettings};
+use std::path::Path;
+
+/// A copy of the in-game font, so the game can still draw text without the assets folder.
+pub const EMBEDDED_FONT: &[u8] = include_bytes!("../assets/joystix.monospace-regular.otf");
+
+/// Load the font from a file, falling back to the embedded copy when the file is missing or invalid.
+/// # Arguments
+/// * `path: &Path` - The font file.
+/// * `texture_context: impl FnMut() -> F` - Creates the context the glyph textures are made with.
+/// # Returns
+/// * `Result<GlyphCache<'static, F, T>, String>` - The glyphs, or a message naming the expected font file.
+pub fn load_glyphs<F, T>(
+    path: &Path,
+    mut texture_context: impl FnMut() -> F,
+) -> Result<GlyphCache<'static, F, T>, String>
+where
+    T: CreateTexture<F> + UpdateTexture<F> + ImageSize,
+{
+    match GlyphCache::new(path, texture_context(), TextureSettings::new()) {
+        Ok(glyphs) => Ok(glyphs),
+        Err(_error) => {
+            #[cfg(feature = "logging")]
+            log::warn!(
+                "Could not load the font {} ({}), using the embedded font instead",
+                path.display(),
+                _error
+            );
+            GlyphCache::from_bytes(EMBEDDED_FONT, texture_context(), TextureSettings::new())
+                .map_err(|_| format!("Could not load the font {}.", path.display()))
+        }
+    }
+}
+
+/// Load a font chosen in the settings, falling back to the bundled font when it is missing or invalid.
+/// # Arguments
+/// * `custom: Option<&Path>` - The chosen font file, if any.
+/// * `bundled: &Path` - The font file shipped with the game.
+/// * `texture_context: impl FnMut() -> F` - Creates the context the glyph textures are made with.
+/// # Returns
+/// * `Result<GlyphCache<'static, F, T>, String>` - The glyphs, or a message naming the expected font file.
+pub fn load_custom_glyphs<F, T>(
+    custom: Option<&Path>,
+    bundled: &Path,
+    mut texture_context: impl FnMut() -> F,
+) -> Result<GlyphCache<'static, F, T>, String>
+where
+    T: CreateTexture<F> + UpdateTexture<F> + ImageSize,
+{
+    if let Some(path) = custom {
+        // Checking for the file first, as a missing file gives a less helpful error than an invalid one.
+        let _error = if path.is_file() {
+            match GlyphCache::new(path, texture_context(), TextureSettings::new()) {
+                Ok(glyphs) => return Ok(glyphs),
+                Err(error) => error.to_string(),
+            }
+        } else {
+            String::from("no such file")
+        };
+        #[cfg(feature = "logging")]
+        log::warn!(
+            "Could not load the font {} ({}), using the bundled font instead",
+            path.display(),
+            _error
+        );
+    }
+    load_glyphs(bundled, texture_context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use piston_window::character::CharacterCache;
+    use piston_window::texture::{Format, TextureOp};
+
+    /// A texture that only stores its size, to build glyphs without a window.
+    struct NullTexture([u32; 2]);
+
+    impl TextureOp<()> for NullTexture {
+        type Error = ();
+    }
+
+    impl ImageSize for NullTexture {
+        fn get_size(&self) -> (u32, u32) {
+            (self.0[0], self.0[1])
+        }
+    }
+
+    impl CreateTexture<()> for NullTexture {
+        fn create<S: Into<[u32; 2]>>(
+            _factory: &mut (),
+            _format: Format,
+            _memory: &[u8],
+            size: S,
+            _settings: &TextureSettings,
+        ) -> Result<Self, Self::Error> {
+            Ok(NullTexture(size.into()))
+        }
+    }
+
+    impl UpdateTexture<()> for NullTexture {
+        fn update<O, S>(
+            &mut self,
+            _factory: &mut (),
+            _format: Format,
+            _memory: &[u8],
+            _offset: O,
+            _size: S,
+        ) -> Result<(), Self::Error>
+        where
+            O: Into<[u32; 2]>,
+            S: Into<[u32; 2]>,
+        {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn missing_font_falls_back_to_the_embedded_font() {
+        let mut glyphs: GlyphCache<(), NullTexture> =
+            load_glyphs(Path::new("does/not/exist.otf"), || ()).unwrap();
+        assert!(glyphs.width(20, "SCORE").unwrap() > 0.0);
+    }
+
+    #[test]
+    fn invalid_custom_font_falls_back_to_the_bundled_font() {
+        let invalid = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        let bundled =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/joystix.monospace-regular.otf");
+        for custom in [Path::new("does/not/exist.ttf"), &invalid] {
+            let mut glyphs: GlyphCache<(), NullTexture> =
+                load_custom_glyphs(Some(custom), &bundled, || ()).unwrap();
+            assert!(glyphs.width(20, "SCORE").unwrap() > 0.0);
+        }
+    }
+
+    #[test]
+    fn invalid_font_falls_back_to_the_embedded_font() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert!(load_glyphs::<(), NullTexture>(&path, || ()).is_ok());
+    }
+}
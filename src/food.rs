@@ -1,10 +1,138 @@
 use crate::block::Block;
 use crate::direction::Direction;
 use crate::snake::Snake;
+use crate::theme::{Color, Theme};
 
-use rand::prelude::thread_rng;
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::prelude::SliceRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The probability of a star spawning next to freshly spawned food, which multiplies the points of the next foods.
+pub const STAR_CHANCE: f64 = 0.02;
+/// The number of moves the food looks ahead when escaping with minimax, unless configured otherwise.
+pub const DEFAULT_MINIMAX_DEPTH: u32 = 3;
+/// The multiplier of the points of freshly spawned food, dropping by one every `FOOD_DECAY_MOVES` down to one.
+pub const FOOD_FRESH_VALUE: i32 = 3;
+/// The number of Snake moves after which uneaten food loses some of its value.
+pub const FOOD_DECAY_MOVES: u32 = 15;
+/// The brightness of fully decayed food, relative to its fresh color.
+const FOOD_STALE_BRIGHTNESS: f32 = 0.5;
+/// The score lost by an escape move ending next to the Snake head, where the Snake would eat the food on its next move.
+const HEAD_ADJACENT_PENALTY: f64 = 2.0;
+
+/// How the food picks its escape moves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum FoodStrategy {
+    /// Moving as far from the head as possible in a single move.
+    #[default]
+    Greedy,
+    /// Planning several moves ahead against a Snake that heads straight for the food.
+    Minimax { depth: u32 },
+}
+
+/// The different kinds of food the Snake can eat.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum FoodKind {
+    Normal,
+    Bonus,
+}
+
+/// How often every kind of food spawns, relative to the other kinds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FoodWeights {
+    pub normal: u32,
+    pub bonus: u32,
+}
+
+impl Default for FoodWeights {
+    /// Spawning bonus food one in twenty times.
+    fn default() -> Self {
+        Self {
+            normal: 19,
+            bonus: 1,
+        }
+    }
+}
+
+impl FoodWeights {
+    /// Add up the weights of all kinds.
+    pub fn total(&self) -> u32 {
+        self.normal + self.bonus
+    }
+}
+
+/// Pick the kind of a freshly spawned food.
+/// # Arguments
+/// * `weights: &FoodWeights` - How often every kind spawns, relative to the other kinds.
+/// * `rng: &mut impl Rng` - The random number generator to sample from.
+/// # Returns
+/// * `FoodKind` - A kind with a probability proportional to its weight, or normal food if all weights are zero.
+pub fn weighted_random_food_kind(weights: &FoodWeights, rng: &mut impl Rng) -> FoodKind {
+    let kinds = [
+        (FoodKind::Normal, weights.normal),
+        (FoodKind::Bonus, weights.bonus),
+    ];
+    match WeightedIndex::new(kinds.iter().map(|(_, weight)| *weight)) {
+        Ok(index) => kinds[index.sample(rng)].0,
+        Err(_) => FoodKind::Normal,
+    }
+}
+
+impl FoodKind {
+    /// The number of points eating the food is worth.
+    pub fn points(&self) -> i32 {
+        match *self {
+            FoodKind::Normal => 1,
+            FoodKind::Bonus => 3,
+        }
+    }
+
+    /// The color the food is rendered in.
+    /// # Arguments
+    /// * `theme: &Theme` - The colors the game is drawn with.
+    pub fn color(&self, theme: &Theme) -> Color {
+        match *self {
+            FoodKind::Normal => theme.food,
+            FoodKind::Bonus => theme.bonus_food,
+        }
+    }
+
+    /// The character overlaid on the food, if any.
+    pub fn glyph(&self) -> Option<char> {
+        match *self {
+            FoodKind::Normal => None,
+            FoodKind::Bonus => Some('*'),
+        }
+    }
+}
+
+/// Calculate how much the points of food are multiplied by, which decays the longer it sits uneaten.
+/// # Arguments
+/// * `age: u32` - The number of Snake moves since the food spawned.
+/// # Returns
+/// * `i32` - The multiplier, from `FOOD_FRESH_VALUE` down to 1.
+pub fn freshness(age: u32) -> i32 {
+    (FOOD_FRESH_VALUE - (age / FOOD_DECAY_MOVES).min(FOOD_FRESH_VALUE as u32) as i32).max(1)
+}
+
+/// Dull the color of food as it decays, from its fresh color down to `FOOD_STALE_BRIGHTNESS` of it.
+/// # Arguments
+/// * `color: Color` - The color of fresh food.
+/// * `freshness: i32` - The current multiplier of the food, as returned by `freshness`.
+/// # Returns
+/// * `Color` - The color to draw the food in, with the alpha channel untouched.
+pub fn decayed_color(color: Color, freshness: i32) -> Color {
+    let decay = (FOOD_FRESH_VALUE - freshness) as f32 / (FOOD_FRESH_VALUE - 1) as f32;
+    let brightness = 1.0 - (1.0 - FOOD_STALE_BRIGHTNESS) * decay.clamp(0.0, 1.0);
+    [
+        color[0] * brightness,
+        color[1] * brightness,
+        color[2] * brightness,
+        color[3],
+    ]
+}
 
 /// Calculate the Euclidian distance between two Blocks.
 /// # Arguments
@@ -16,29 +144,216 @@ pub fn get_distance(block1: Block, block2: Block) -> f64 {
     (((block1.x - block2.x).pow(2) + (block1.y - block2.y).pow(2)) as f64).sqrt()
 }
 
+/// Calculate the Euclidian distance between a Block and the closest Block of the Snake body.
+/// # Arguments
+/// * `block: Block` - The Block to measure from.
+/// * `snake: &Snake` - A reference to the Snake class to measure to.
+/// # Returns
+/// * `f64` - The smallest Euclidian distance to any body Block, in game coordinates.
+pub fn get_closest_snake_segment_distance(block: Block, snake: &Snake) -> f64 {
+    snake
+        .blocks()
+        .map(|segment| get_distance(block, segment))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Score a position the food could escape to, higher being safer.
+/// # Arguments
+/// * `block: Block` - The position the food could move to.
+/// * `head: Block` - The position of the Snake head.
+/// # Returns
+/// * `f64` - The distance to the head, lowered for positions right next to the head.
+pub fn get_escape_score(block: Block, head: Block) -> f64 {
+    // Not penalizing the head cell itself, as the food is eaten there and never scores it.
+    get_distance(block, head) - HEAD_ADJACENT_PENALTY * (block.is_adjacent(head) as i32 as f64)
+}
+
+/// Count the free cells in a straight line from a Block, up to the first border, wall or Snake block.
+/// # Arguments
+/// * `block: Block` - The Block to count from, which is not counted itself.
+/// * `direction: Direction` - The direction to count in.
+/// * `snake: &Snake` - A reference to the Snake class, whose body ends the line.
+/// * `walls: &[Block]` - The walls of the level, which end the line like the border.
+/// * `x_bounds: [i32;2]` - The x-bounds of the level, in game coordinates.
+/// * `y_bounds: [i32;2]` - The y-bounds of the level, in game coordinates.
+/// # Returns
+/// * `i32` - The number of contiguous free cells.
+pub fn count_free_in_direction(
+    block: Block,
+    direction: Direction,
+    snake: &Snake,
+    walls: &[Block],
+    x_bounds: [i32; 2],
+    y_bounds: [i32; 2],
+) -> i32 {
+    let [dx, dy] = direction.offset();
+    (1..)
+        .map(|step| Block::new(block.x + dx * step, block.y + dy * step))
+        .take_while(|cell| {
+            !cell.out_of_bounds(x_bounds, y_bounds)
+                && !snake.overlap_tail(*cell)
+                && !walls.contains(cell)
+        })
+        .count() as i32
+}
+
+/// Keep the offsets that place the food on the longest open row or column, to break ties between escape moves.
+/// Food in open areas is harder to corner.
+/// # Arguments
+/// * `block: Block` - The food Block that tries to escape.
+/// * `offsets: Vec<[i32;2]>` - The equally good escape offsets.
+/// * `snake: &Snake` - A reference to the Snake class from which the Block escapes.
+/// * `walls: &[Block]` - The walls of the level.
+/// * `x_bounds: [i32;2]` - The x-bounds of the level, in game coordinates.
+/// * `y_bounds: [i32;2]` - The y-bounds of the level, in game coordinates.
+/// # Returns
+/// * `Vec<[i32;2]>` - The offsets with the longest open row or column, in their original order.
+fn most_open_offsets(
+    block: Block,
+    offsets: Vec<[i32; 2]>,
+    snake: &Snake,
+    walls: &[Block],
+    x_bounds: [i32; 2],
+    y_bounds: [i32; 2],
+) -> Vec<[i32; 2]> {
+    let openness = |offset: &[i32; 2]| {
+        let destination = Block::new(block.x + offset[0], block.y + offset[1]);
+        let free = |direction| {
+            count_free_in_direction(destination, direction, snake, walls, x_bounds, y_bounds)
+        };
+        (free(Direction::Left) + free(Direction::Right))
+            .max(free(Direction::Up) + free(Direction::Down))
+    };
+    let longest = offsets.iter().map(openness).max().unwrap_or(0);
+    offsets
+        .into_iter()
+        .filter(|offset| openness(offset) == longest)
+        .collect()
+}
+
 /// Calculate the optimal offset to hide from the Snakes current head position.
 /// # Arguments
 /// * `block: Block` - The food Block that tries to escape.
 /// * `snake: &Snake` - A reference to the Snake class from which the Block escapes.
+/// * `walls: &[Block]` - The walls of the level, which the food cannot move onto like the border.
 /// * `x_bounds: [i32;2]` - The x-bounds of the level, in game coordinates.
 /// * `y_bounds: [i32;2]` - The y-bounds of the level, in game coordinates.
+/// * `rng: &mut R` - The random number generator to break ties with.
 /// # Returns
-/// * `[i32;2]` - A random sample from the optimal escape offsets.
-pub fn get_escape_offset(
+/// * `[i32;2]` - A random sample from the offsets with the best escape score and the longest open row or column.
+pub fn get_escape_offset<R: Rng>(
     block: Block,
     snake: &Snake,
+    walls: &[Block],
     x_bounds: [i32; 2],
     y_bounds: [i32; 2],
+    rng: &mut R,
 ) -> [i32; 2] {
-    let mut best_dist = get_distance(block, snake.head_position());
+    let mut best_score = get_escape_score(block, snake.head_position());
     let mut best_offsets: Vec<[i32; 2]> = vec![[0, 0]];
 
     for (_, offset) in Direction::offsets() {
         let destination = Block::new(block.x + offset[0], block.y + offset[1]);
-        if destination.out_of_bounds(x_bounds, y_bounds) || snake.overlap_tail(destination) {
+        if destination.out_of_bounds(x_bounds, y_bounds)
+            || snake.overlap_tail(destination)
+            || walls.contains(&destination)
+        {
             continue;
         }
-        let current_dist = get_distance(destination, snake.head_position());
+        let current_score = get_escape_score(destination, snake.head_position());
+        if current_score > best_score {
+            best_score = current_score;
+            best_offsets.clear();
+            best_offsets.push(offset);
+        } else if current_score == best_score {
+            best_offsets.push(offset);
+        }
+    }
+
+    // Choosing a random move out of all equivalent distances that keep the food in the open.
+    most_open_offsets(block, best_offsets, snake, walls, x_bounds, y_bounds)
+        .choose(rng)
+        .copied()
+        .unwrap()
+}
+
+/// Find the move that keeps food furthest from the Snake head over the next moves, with the Snake modeled as taking
+/// the step that brings its head closest to the food after every food move.
+/// # Arguments
+/// * `block: Block` - The food Block that tries to escape.
+/// * `snake: &Snake` - A reference to the Snake class from which the Block escapes.
+/// * `walls: &[Block]` - The walls of the level, which the food cannot move onto like the border.
+/// * `x_bounds: [i32;2]` - The x-bounds of the level, in game coordinates.
+/// * `y_bounds: [i32;2]` - The y-bounds of the level, in game coordinates.
+/// * `depth: u32` - The number of food moves to look ahead.
+/// * `rng: &mut R` - The random number generator to break ties with.
+/// # Returns
+/// * `[i32;2]` - A random sample from the offsets with the largest guaranteed distance and the longest open row or
+///   column.
+pub fn get_escape_offset_minimax<R: Rng>(
+    block: Block,
+    snake: &Snake,
+    walls: &[Block],
+    x_bounds: [i32; 2],
+    y_bounds: [i32; 2],
+    depth: u32,
+    rng: &mut R,
+) -> [i32; 2] {
+    let food_moves = |food: Block| {
+        std::iter::once([0, 0])
+            .chain(Direction::offsets().map(|(_, offset)| offset))
+            .map(move |offset| (offset, Block::new(food.x + offset[0], food.y + offset[1])))
+            .filter(|(_, destination)| {
+                !destination.out_of_bounds(x_bounds, y_bounds)
+                    && !snake.overlap_tail(*destination)
+                    && !walls.contains(destination)
+            })
+    };
+    // Stepping the head towards the food, which is all the Snake is assumed to do.
+    let chase = |head: Block, food: Block| {
+        Direction::offsets()
+            .into_iter()
+            .map(|(_, [dx, dy])| Block::new(head.x + dx, head.y + dy))
+            .min_by(|a, b| {
+                get_distance(*a, food)
+                    .total_cmp(&get_distance(*b, food))
+                    .then((a.x, a.y).cmp(&(b.x, b.y)))
+            })
+            .unwrap()
+    };
+    // The smallest distance between food and head the food can guarantee over the remaining moves.
+    fn lookahead<I: Iterator<Item = ([i32; 2], Block)>>(
+        food: Block,
+        head: Block,
+        depth: u32,
+        food_moves: &impl Fn(Block) -> I,
+        chase: &impl Fn(Block, Block) -> Block,
+    ) -> f64 {
+        let distance = get_distance(food, head);
+        if depth == 0 || distance == 0.0 {
+            return distance;
+        }
+        food_moves(food)
+            .map(|(_, next)| {
+                let next_head = chase(head, next);
+                lookahead(next, next_head, depth - 1, food_moves, chase)
+            })
+            .fold(f64::NEG_INFINITY, f64::max)
+            .min(distance)
+    }
+
+    let head = snake.head_position();
+    let mut best_dist = f64::NEG_INFINITY;
+    let mut best_offsets: Vec<[i32; 2]> = vec![[0, 0]];
+    for (offset, destination) in food_moves(block) {
+        let current_dist = lookahead(
+            destination,
+            chase(head, destination),
+            depth.saturating_sub(1),
+            &food_moves,
+            &chase,
+        )
+        .min(get_distance(destination, head));
         if current_dist > best_dist {
             best_dist = current_dist;
             best_offsets.clear();
@@ -48,31 +363,50 @@ pub fn get_escape_offset(
         }
     }
 
-    // Choosing a random move out of all equivalent distances.
-    let mut rng = thread_rng();
-    best_offsets.choose(&mut rng).copied().unwrap()
+    // Choosing a random move out of all equivalent distances that keep the food in the open.
+    most_open_offsets(block, best_offsets, snake, walls, x_bounds, y_bounds)
+        .choose(rng)
+        .copied()
+        .unwrap()
 }
 
 /// Escape from the snake with some probability, dependent on the length of the snake.
 /// # Arguments
 /// * `block: Block` - The food Block that tries to escape.
 /// * `snake: &Snake` - A reference to the Snake class from which the Block escapes.
+/// * `walls: &[Block]` - The walls of the level, which the food cannot move onto like the border.
 /// * `x_bounds: [i32;2]` - The x-bounds of the level, in game coordinates.
 /// * `y_bounds: [i32;2]` - The y-bounds of the level, in game coordinates.
+/// * `speed: i32` - The escape probability weight per Snake body block. The food never moves at 0.
+/// * `strategy: FoodStrategy` - How the escape move is picked.
+/// * `rng: &mut R` - The random number generator deciding whether the food moves.
 /// # Returns
 /// * `[i32;2]` - An optimal escape offset or `[0, 0]` if the food did not get lucky enough to move.
-pub fn escape(
+#[allow(clippy::too_many_arguments)]
+pub fn escape<R: Rng>(
     block: Block,
     snake: &Snake,
+    walls: &[Block],
     x_bounds: [i32; 2],
     y_bounds: [i32; 2],
     speed: i32,
+    strategy: FoodStrategy,
+    rng: &mut R,
 ) -> [i32; 2] {
-    let escape = get_escape_offset(block, snake, x_bounds, y_bounds);
+    // Food without any speed never moves, rather than moving on the rare zero roll below.
+    if speed <= 0 {
+        return [0, 0];
+    }
+    let escape = match strategy {
+        FoodStrategy::Greedy => get_escape_offset(block, snake, walls, x_bounds, y_bounds, rng),
+        FoodStrategy::Minimax { depth } => {
+            get_escape_offset_minimax(block, snake, walls, x_bounds, y_bounds, depth, rng)
+        }
+    };
 
     let area = (x_bounds[1] - x_bounds[0]) * (y_bounds[1] - y_bounds[0]);
-    let weights = [(snake.len() * speed).clamp(0, area), area];
-    let escape_weight = thread_rng().gen_range(0..weights[1]);
+    let weights = [(snake.body_len() as i32 * speed).clamp(0, area), area];
+    let escape_weight = rng.gen_range(0..weights[1]);
 
     if escape_weight <= weights[0] {
         escape
@@ -80,3 +414,153 @@ pub fn escape(
         [0, 0]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn food_kinds_look_distinct() {
+        for theme in [Theme::classic(), Theme::colorblind()] {
+            assert_ne!(
+                FoodKind::Normal.color(&theme),
+                FoodKind::Bonus.color(&theme)
+            );
+        }
+        assert_eq!(FoodKind::Normal.glyph(), None);
+        assert!(FoodKind::Bonus.glyph().is_some());
+    }
+
+    #[test]
+    fn closest_snake_segment_distance() {
+        let snake = Snake::from_blocks(
+            [Block::new(5, 5), Block::new(4, 5), Block::new(3, 5)],
+            Direction::Right,
+        );
+        assert_eq!(
+            get_closest_snake_segment_distance(Block::new(3, 5), &snake),
+            0.0
+        );
+        assert_eq!(
+            get_closest_snake_segment_distance(Block::new(3, 2), &snake),
+            3.0
+        );
+        assert_eq!(
+            get_closest_snake_segment_distance(Block::new(8, 9), &snake),
+            5.0
+        );
+    }
+
+    #[test]
+    fn random_food_kind_is_mostly_normal() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let kinds: Vec<FoodKind> = (0..1000)
+            .map(|_| weighted_random_food_kind(&FoodWeights::default(), &mut rng))
+            .collect();
+        let bonus = kinds
+            .iter()
+            .filter(|kind| **kind == FoodKind::Bonus)
+            .count();
+        assert!(bonus > 0 && bonus < 150);
+    }
+
+    #[test]
+    fn food_kinds_without_weight_never_spawn() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let only_bonus = FoodWeights {
+            normal: 0,
+            bonus: 1,
+        };
+        assert!(
+            (0..100).all(|_| weighted_random_food_kind(&only_bonus, &mut rng) == FoodKind::Bonus)
+        );
+        let nothing = FoodWeights {
+            normal: 0,
+            bonus: 0,
+        };
+        assert_eq!(
+            weighted_random_food_kind(&nothing, &mut rng),
+            FoodKind::Normal
+        );
+    }
+
+    #[test]
+    fn food_decays_every_few_moves() {
+        assert_eq!(freshness(0), FOOD_FRESH_VALUE);
+        assert_eq!(freshness(FOOD_DECAY_MOVES - 1), 3);
+        assert_eq!(freshness(FOOD_DECAY_MOVES), 2);
+        assert_eq!(freshness(2 * FOOD_DECAY_MOVES - 1), 2);
+        assert_eq!(freshness(2 * FOOD_DECAY_MOVES), 1);
+        assert_eq!(freshness(u32::MAX), 1);
+        let red = [0.8, 0.0, 0.0, 1.0];
+        assert_eq!(decayed_color(red, 3), red);
+        assert_eq!(decayed_color(red, 1), [0.4, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn escape_avoids_cells_next_to_the_head() {
+        let head = Block::new(2, 2);
+        assert_eq!(get_escape_score(Block::new(1, 1), head), 2f64.sqrt());
+        assert_eq!(get_escape_score(Block::new(2, 1), head), -1.0);
+        assert_eq!(get_escape_score(Block::new(4, 2), head), 2.0);
+        assert_eq!(get_escape_score(head, head), 0.0);
+        // Cornered diagonally to the head, both open moves end next to it, so the food stays put.
+        let snake = Snake::from_blocks([head, Block::new(3, 2)], Direction::Left);
+        let mut rng = StdRng::seed_from_u64(0);
+        let offset = get_escape_offset(Block::new(1, 1), &snake, &[], [0, 20], [0, 20], &mut rng);
+        assert_eq!(offset, [0, 0]);
+    }
+
+    #[test]
+    fn minimax_escape_avoids_corners() {
+        // Greedily, the food flees up along the border, into the corner. Looking ahead, it flees down instead.
+        let snake = Snake::from_blocks([Block::new(14, 4), Block::new(13, 4)], Direction::Right);
+        let food = Block::new(18, 3);
+        let mut rng = StdRng::seed_from_u64(0);
+        let greedy = get_escape_offset(food, &snake, &[], [0, 20], [0, 20], &mut rng);
+        assert_eq!(greedy, [0, -1]);
+        let minimax = get_escape_offset_minimax(food, &snake, &[], [0, 20], [0, 20], 5, &mut rng);
+        assert_eq!(minimax, [0, 1]);
+    }
+
+    #[test]
+    fn food_without_speed_never_escapes() {
+        let snake = Snake::from_blocks([Block::new(14, 4), Block::new(13, 4)], Direction::Right);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10_000 {
+            let offset = escape(
+                Block::new(16, 4),
+                &snake,
+                &[],
+                [0, 20],
+                [0, 20],
+                0,
+                FoodStrategy::Greedy,
+                &mut rng,
+            );
+            assert_eq!(offset, [0, 0]);
+        }
+    }
+
+    #[test]
+    fn escape_ties_are_broken_towards_open_lines() {
+        let snake = Snake::from_blocks([Block::new(10, 10), Block::new(9, 10)], Direction::Right);
+        let walls = [Block::new(12, 14), Block::new(14, 14), Block::new(13, 16)];
+        let free = |block, direction| {
+            count_free_in_direction(block, direction, &snake, &walls, [0, 20], [0, 20])
+        };
+        assert_eq!(free(Block::new(14, 13), Direction::Left), 13);
+        assert_eq!(free(Block::new(14, 13), Direction::Right), 4);
+        assert_eq!(free(Block::new(14, 13), Direction::Down), 0);
+        assert_eq!(free(Block::new(10, 13), Direction::Up), 2);
+        // Moving right or down gets the food equally far from the head, but only its right is open.
+        for seed in 0..10 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let food = Block::new(13, 13);
+            let greedy = get_escape_offset(food, &snake, &walls, [0, 20], [0, 20], &mut rng);
+            assert_eq!(greedy, [1, 0]);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// External imports.
+use image::{ImageResult, Rgba, RgbaImage};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Local imports.
+use crate::block::Block;
+use crate::cell::Cell;
+use crate::game::Game;
+use crate::recording::{Raster, GIF_BLOCK_SIZE};
+use crate::theme::{Color, Theme};
+
+// Constants.
+/// The width of a digit of the HUD numbers, in pixels.
+const DIGIT_WIDTH: usize = 3;
+/// The height of a digit of the HUD numbers, in pixels.
+const DIGIT_HEIGHT: usize = 5;
+/// The digits 0 to 9, as five rows of three pixels with the leftmost pixel in the highest bit.
+const DIGITS: [[u8; DIGIT_HEIGHT]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Convert a color of the game theme to an opaque pixel.
+fn to_pixel(color: Color) -> Rgba<u8> {
+    let [r, g, b] = [color[0], color[1], color[2]].map(|channel| (channel * 255.0).round() as u8);
+    Rgba([r, g, b, 255])
+}
+
+/// Draw a number with the tiny digit font.
+/// # Arguments
+/// * `raster: &mut Raster<Rgba<u8>>` - The raster to draw on.
+/// * `number: u32` - The number to draw.
+/// * `left: usize` - The leftmost pixel column of the number.
+/// * `top: usize` - The topmost pixel row of the number.
+/// * `pixel: Rgba<u8>` - The color of the digits.
+/// # Returns
+/// * `usize` - The first pixel column right of the number.
+fn draw_number(
+    raster: &mut Raster<Rgba<u8>>,
+    number: u32,
+    left: usize,
+    top: usize,
+    pixel: Rgba<u8>,
+) -> usize {
+    let mut x = left;
+    for digit in number
+        .to_string()
+        .bytes()
+        .map(|digit| (digit - b'0') as usize)
+    {
+        for (dy, row) in DIGITS[digit].iter().enumerate() {
+            for dx in 0..DIGIT_WIDTH {
+                if row >> (DIGIT_WIDTH - 1 - dx) & 1 == 1 {
+                    raster.set_pixel(x + dx, top + dy, pixel);
+                }
+            }
+        }
+        // Leaving a single pixel between digits.
+        x += DIGIT_WIDTH + 1;
+    }
+    x
+}
+
+/// Draw the logical state of a board, without any graphics backend.
+/// # Arguments
+/// * `size: (i32, i32)` - The width and height of the board in blocks, including the score strip.
+/// * `cell: impl Fn(Block) -> Cell` - What is shown on every cell.
+/// * `hud: &[u32]` - The numbers shown in the score strip, from left to right.
+/// * `theme: &Theme` - The colors to draw with.
+/// * `block_size: usize` - The size of a Block in pixels.
+/// # Returns
+/// * `RgbaImage` - The board, with the Snake head marked by a square in the eye color.
+pub fn render_board(
+    size: (i32, i32),
+    cell: impl Fn(Block) -> Cell,
+    hud: &[u32],
+    theme: &Theme,
+    block_size: usize,
+) -> RgbaImage {
+    let (width, height) = size;
+    let (pixel_width, pixel_height) = (width as usize * block_size, height as usize * block_size);
+    let mut raster = Raster::filled(pixel_width, pixel_height, to_pixel(theme.background));
+    for y in 0..height {
+        for x in 0..width {
+            let block = Block::new(x, y);
+            let shown = cell(block);
+            raster.fill_block(block, block_size, to_pixel(shown.color(theme)));
+            if shown == Cell::SnakeHead {
+                let (left, top) = (x as usize * block_size, y as usize * block_size);
+                for dy in block_size / 4..block_size - block_size / 4 {
+                    for dx in block_size / 4..block_size - block_size / 4 {
+                        raster.set_pixel(left + dx, top + dy, to_pixel(theme.snake_eyes));
+                    }
+                }
+            }
+        }
+    }
+    // Writing the numbers vertically centered in the bottom row, one block apart.
+    let top = pixel_height - block_size + block_size.saturating_sub(DIGIT_HEIGHT) / 2;
+    let mut left = block_size;
+    for number in hud {
+        left = draw_number(&mut raster, *number, left, top, to_pixel(theme.hud)) + block_size;
+    }
+    let pixels = raster.pixels().iter().flat_map(|pixel| pixel.0).collect();
+    RgbaImage::from_raw(pixel_width as u32, pixel_height as u32, pixels).unwrap()
+}
+
+/// Draw the logical state of a game, with the score and speed level as HUD numbers.
+/// # Arguments
+/// * `game: &Game` - The game to draw.
+/// * `block_size: usize` - The size of a Block in pixels.
+/// # Returns
+/// * `RgbaImage` - The board as the game logic sees it.
+pub fn render_game(game: &Game, block_size: usize) -> RgbaImage {
+    render_board(
+        game.size(),
+        |block| game.cell(block),
+        &[game.score().max(0) as u32, game.speed_level().max(0) as u32],
+        game.theme(),
+        block_size,
+    )
+}
+
+/// Numbered PNG images of every tick, for bug reports.
+pub struct FrameDump {
+    folder: PathBuf,
+    /// The Snake move of the last written frame, so every tick is written once.
+    last_move: Option<u32>,
+    frames: usize,
+}
+
+impl FrameDump {
+    /// Start dumping frames into a folder, creating it if needed.
+    /// # Arguments
+    /// * `folder: PathBuf` - The folder to write the images to.
+    pub fn new(folder: PathBuf) -> io::Result<FrameDump> {
+        fs::create_dir_all(&folder)?;
+        Ok(FrameDump {
+            folder,
+            last_move: None,
+            frames: 0,
+        })
+    }
+
+    /// Write the board if the game ticked since the last written frame.
+    /// # Arguments
+    /// * `game: &Game` - The game to draw.
+    pub fn update(&mut self, game: &Game) -> ImageResult<()> {
+        if self.last_move == Some(game.snake_moves()) {
+            return Ok(());
+        }
+        self.last_move = Some(game.snake_moves());
+        let path = self.folder.join(format!("frame-{:05}.png", self.frames));
+        render_game(game, GIF_BLOCK_SIZE).save(path)?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::food::FoodKind;
+
+    /// A 10x10 board with a wall, a Snake of three blocks and normal food, above a score strip.
+    fn scene(block: Block) -> Cell {
+        let snake = [Block::new(3, 5), Block::new(3, 4), Block::new(4, 4)];
+        if block.x == 0 || block.y == 0 || block.x == 9 || block.y >= 8 {
+            Cell::Border
+        } else if block == Block::new(6, 6) {
+            Cell::Wall
+        } else if block == Block::new(5, 4) {
+            Cell::SnakeHead
+        } else if snake.contains(&block) {
+            Cell::SnakeBody
+        } else if block == Block::new(7, 2) {
+            Cell::Food(FoodKind::Normal)
+        } else {
+            Cell::Empty
+        }
+    }
+
+    #[test]
+    fn boards_match_the_reference_image() {
+        let rendered = render_board((10, 10), scene, &[12, 3], &Theme::classic(), GIF_BLOCK_SIZE);
+        let reference = image::open(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/reference/board-10x10.png"
+        ))
+        .unwrap()
+        .to_rgba8();
+        assert!(rendered == reference);
+    }
+
+    #[test]
+    fn digits_are_drawn_left_to_right() {
+        let mut raster = Raster::filled(8, 5, Rgba([0, 0, 0, 0]));
+        let white = Rgba([255, 255, 255, 255]);
+        assert_eq!(draw_number(&mut raster, 17, 0, 0, white), 8);
+        // The 1 has its stem in the middle column, the 7 its top row filled.
+        assert_eq!(raster.pixel(1, 2), white);
+        assert_eq!(raster.pixel(0, 2), Rgba([0, 0, 0, 0]));
+        assert!((4..7).all(|x| raster.pixel(x, 0) == white));
+    }
+}
@@ -1,445 +1,1217 @@
 // External imports.
-use piston_window::types::Color;
-use piston_window::{Context, G2d, Glyphs, Key};
-use rand::{thread_rng, Rng};
-use std::path::PathBuf;
+#[cfg(feature = "gui")]
+use piston_window::Key;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
+use std::f64::consts::TAU;
+use std::fmt;
+use std::time::Duration;
+use std::vec::Drain;
 
 // Local imports.
+use crate::autosave::GameSnapshot;
 use crate::block::Block;
+use crate::breakdown::{ScoreBreakdown, ScoreReason};
+use crate::cell::Cell;
+use crate::config::{ConfigError, Difficulty, GameConfig};
+#[cfg(feature = "gui")]
+use crate::dateformat::DisplayFormat;
 use crate::direction::Direction;
-use crate::draw::{draw_block, draw_rectangle, draw_text, show_scores, BLOCK_SIZE};
-use crate::food;
-use crate::score::{create_empty_name, write_score, Score, MAX_NAME_LENGTH};
+use crate::event::{DeathCause, GameEvent};
+use crate::food::{self, FoodKind};
+use crate::grid::{flood_fill, generate_obstacles};
+use crate::hazard::HazardRow;
+use crate::levels::{Door, Layout, ObstacleKind, HAZARD_DAMAGE_TICKS};
+use crate::locale::Locale;
+use crate::recording::{Frame, Recording};
+use crate::score::{check_score, create_empty_name, write_score, RankKey, Score, MAX_NAME_LENGTH};
 use crate::snake::Snake;
+use crate::storage::Storage;
+#[cfg(feature = "gui")]
+use crate::textcache::TextCache;
+use crate::theme::{Palette, Theme};
+
+// Drawing the game, which only the windowed frontend does.
+#[cfg(feature = "gui")]
+mod render;
 
 // Constants.
-const FOOD_COLOR: Color = [0.80, 0.00, 0.00, 1.00];
-const BORDER_COLOR: Color = [0.00, 0.00, 0.00, 1.00];
 const BORDER_WIDTH: i32 = 1;
-const GAMEOVER_COLOR: Color = [0.90, 0.00, 0.00, 0.50];
-const GAMEOVER_TEXT_COLOR: Color = [1.0, 1.0, 1.0, 0.9];
 const SCORE_BORDER_WIDTH: i32 = 1;
-const SCORE_FONT_SIZE: u32 = 20;
-const MOVING_PERIOD: f64 = 0.5;
 const FOOD_SPEED_INCREASE: i32 = 5;
-const SPEED_FACTOR: f64 = 0.8;
-const FOODS_PER_SPEED_INCREASE: i32 = 5;
-
-struct Borders {
-    top_border: Block,
-    bottom_border: Block,
-    left_border: Block,
-    right_border: Block,
-    score_border: Block,
-    high_score_border: Block,
-    score_name_border: Block,
+/// The number of moves after which the game speeds up in survival mode, where eating does not speed it up.
+const SURVIVAL_MOVES_PER_SPEED_INCREASE: u32 = 50;
+/// The number of consecutive foods eaten on consecutive moves before every next food earns a combo bonus.
+const COMBO_STREAK: u32 = 3;
+const COMBO_BONUS_POINTS: i32 = 1;
+/// The bonus for eating food with at least `RISKY_NEIGHBORS` of its neighbors blocked.
+const RISKY_BONUS_POINTS: i32 = 1;
+/// The number of blocked neighbors that make eating food risky, not counting the segment behind the head.
+const RISKY_NEIGHBORS: usize = 2;
+/// The time the risky bonus stays shown where the food was eaten, in seconds.
+const RISKY_POPUP_DURATION: f64 = 1.0;
+/// The Snake length of which every multiple earns a bonus.
+const LENGTH_MILESTONE: i32 = 10;
+/// The bonus for every length milestone reached.
+const MILESTONE_BONUS_POINTS: i32 = 5;
+/// The time a reached length milestone stays announced on the top border, in seconds.
+const MILESTONE_BANNER_DURATION: f64 = 2.0;
+/// The factor every star multiplies the points of food by.
+const STAR_MULTIPLIER: i32 = 2;
+/// The number of foods of which the points are multiplied after picking up a star.
+const MULTIPLIED_FOODS: u32 = 10;
+/// The number of stars that stack into the multiplier, so a star picked up while one is active refreshes it only once.
+const MAX_STAR_STACKS: u32 = 2;
+/// The time the game announces reaching a new speed level, fading out, in seconds.
+const SPEED_UP_DURATION: f64 = 1.5;
+/// The time a penalty stays shown where it was taken, in seconds.
+const PENALTY_POPUP_DURATION: f64 = 1.0;
+/// The number of direction changes that can be buffered ahead of the Snake moves.
+const MAX_QUEUED_DIRECTIONS: usize = 3;
+/// The largest number of moves that can be undone in a row in practice mode.
+pub const MAX_UNDO_STEPS: usize = 5;
+/// The number of cells ahead of the starting Snake that random obstacles keep clear of.
+const OBSTACLE_FREE_RUNWAY: i32 = 4;
+/// The number of random spawn positions sampled for new food, of which the one furthest from the head is used.
+const FOOD_SPAWN_CANDIDATES: usize = 3;
+
+/// The time between two blinks of the Snake, in seconds.
+const EYE_BLINK_INTERVAL: f64 = 3.0;
+/// The time the eyes stay closed during a blink, in seconds.
+const EYE_BLINK_DURATION: f64 = 0.15;
+/// The time the cursor of the name entry is shown, and then hidden, in seconds.
+const CURSOR_BLINK_PERIOD: f64 = 0.5;
+
+/// The distance from the head up to which cells are fully visible in fog of war mode, in blocks.
+const FOG_RADIUS: f64 = 6.0;
+/// How far the visible radius grows and shrinks while pulsing, in blocks.
+const FOG_PULSE_AMPLITUDE: f64 = 0.5;
+/// The time of a single pulse of the visible radius, in seconds.
+const FOG_PULSE_PERIOD: f64 = 2.0;
+/// How much further the Snake sees right after eating, in blocks.
+const FOG_REVEAL_RADIUS: f64 = 4.0;
+/// The time the wider view lasts after eating, in seconds.
+const FOG_REVEAL_DURATION: f64 = 1.0;
+/// The distance over which cells fade from visible to fully dark, in blocks.
+const FOG_FADE_WIDTH: f64 = 2.0;
+
+/// Check whether the Snake is heading straight for the food, without anything in between.
+/// # Arguments
+/// * `snake: &Snake` - A reference to the Snake.
+/// * `food: Block` - The food Block.
+/// * `obstacles: &[Block]` - All Blocks besides the Snake itself that block the way.
+/// # Returns
+/// * `bool` - Whether (true) or not (false) the food lies ahead of the head, on a free line.
+pub fn food_is_in_line_of_sight(snake: &Snake, food: Block, obstacles: &[Block]) -> bool {
+    let head = snake.head_position();
+    let [dx, dy] = snake.head_direction().offset();
+    let distance = head.manhattan_distance(food);
+    // The food has to be ahead of the head, on the same row or column.
+    if Block::new(head.x + dx * distance, head.y + dy * distance) != food {
+        return false;
+    }
+    (1..distance)
+        .map(|step| Block::new(head.x + dx * step, head.y + dy * step))
+        .all(|block| !obstacles.contains(&block) && !snake.overlap_tail(block))
+}
+
+/// Get the highest length milestone a Snake length reached.
+/// # Arguments
+/// * `length: i32` - The length of the Snake.
+/// # Returns
+/// * `i32` - The length rounded down to a multiple of `LENGTH_MILESTONE`.
+fn milestone(length: i32) -> i32 {
+    length / LENGTH_MILESTONE * LENGTH_MILESTONE
+}
+
+/// Count down the time left of something shown for a while, such as a popup or a banner.
+/// # Arguments
+/// * `shown: Option<(T, f64)>` - What is shown and the time it is still shown for, if anything.
+/// * `delta_time: f64` - The time passed since the last update in seconds.
+/// # Returns
+/// * `Option<(T, f64)>` - What is still shown with its remaining time, or None once the time ran out.
+fn count_down<T>(shown: Option<(T, f64)>, delta_time: f64) -> Option<(T, f64)> {
+    shown
+        .map(|(value, left)| (value, left - delta_time))
+        .filter(|(_, left)| *left > 0.0)
 }
 
 pub struct Game {
+    config: GameConfig,
+    /// The options changed during the run, which apply from the next restart on.
+    pending_config: Option<GameConfig>,
+    rng: StdRng,
+    theme: Theme,
+
     snake: Snake,
     food: Option<Block>,
-    direction_queue: Vec<Option<Direction>>,
+    food_kind: FoodKind,
+    /// The Snake move the food spawned at, which its value decays from.
+    food_spawned_at: u32,
+    /// The number of times the current food escaped, reset once it is gone.
+    food_moves: u32,
+    /// Buffered direction changes, oldest first. Every Snake move takes the oldest one.
+    direction_queue: VecDeque<Direction>,
 
     width: i32,
     height: i32,
 
     game_over: bool,
-    waiting_time: f64,
+    /// What ended the game, while it is over.
+    death: Option<DeathCause>,
+    paused: bool,
+    /// Whether the controls overlay is shown. The game does not start moving until it is dismissed.
+    hints: bool,
+    /// Whether the grid coordinates of the Snake and food are drawn. Only available in debug builds.
+    #[cfg(all(debug_assertions, feature = "gui"))]
+    debug_overlay: bool,
+    /// The time since the last Snake move.
+    waiting_time: Duration,
+    snake_moves: u32,
 
-    score: i32,
+    /// Points earned by eating normal food.
+    base_score: i32,
+    /// Points earned by eating bonus food.
+    bonus_score: i32,
+    /// The points per category, adding up to the score.
+    breakdown: ScoreBreakdown,
+    /// The number of foods eaten on consecutive moves.
+    eat_streak: u32,
+    max_eat_streak: u32,
+    /// The longest the Snake got during this run.
+    peak_length: i32,
+    /// The number of foods eaten during this run.
+    foods_eaten: u32,
+    /// The number of moves that brought the head right next to the Snake body without hitting it.
+    near_miss_count: u32,
+    /// Where the last risky bonus was earned and how long it is still shown.
+    risky_popup: Option<(Block, f64)>,
+    /// Where the last penalty was taken, the points lost and how long it is still shown.
+    penalty_popup: Option<((Block, i32), f64)>,
+    /// The star multiplying the points of the next foods, if one is waiting to be picked up.
+    star: Option<Block>,
+    /// The number of foods the points are still multiplied for.
+    multiplied_foods: u32,
+    /// The number of stars stacked into the multiplier, up to `MAX_STAR_STACKS`.
+    multiplier_stacks: u32,
+    /// The highest length milestone rewarded this run, so none is rewarded twice.
+    length_milestone: i32,
+    /// The last length milestone reached and how long it is still announced.
+    milestone_banner: Option<(i32, f64)>,
+    /// The time the new speed level is still announced, in seconds.
+    speed_up_timer: f64,
     pub high_score: bool,
     pub score_written: bool,
     score_name: String,
+    /// Where the next letter of the name is typed, as the number of letters before it.
+    cursor_pos: usize,
+    /// The time since the cursor of the name entry last appeared, which it blinks with.
+    cursor_blink: f64,
 
-    borders: Borders,
+    /// The walls of the level inside the border and any random obstacles, which are as deadly as the border.
+    walls: Vec<Block>,
+    /// The doors of the level, which block the way like walls until their key is picked up.
+    doors: Vec<Door>,
+    /// The hazard blocks of the level, which shrink the Snake instead of ending the run.
+    hazard_blocks: Vec<Block>,
+    /// Whether any part of the Snake lies on a hazard block, which makes it flash.
+    hurting: bool,
+    /// The row that periodically turns lethal, when hazard rows are on.
+    hazard: HazardRow,
+    events: Vec<GameEvent>,
+    /// Every tick of the current run, to export it afterwards.
+    recording: Recording,
+    /// The time played this run, which the fog of war pulses with.
+    play_time: Duration,
+    /// The time left of the wider view after eating, in fog of war mode.
+    fog_reveal: f64,
+    /// The states before the last few moves, most recent last, to undo them in practice mode.
+    undo_history: VecDeque<GameSnapshot>,
+    /// The time until the eyes of the Snake open or close next.
+    eye_blink_timer: f64,
+    /// Whether the Snake is blinking, so no eyes are drawn.
+    eyes_closed: bool,
+    /// The score text in the score strip, built from whether the run counts survival time and is a practice run, the
+    /// score, the multiplier and the foods it lasts for.
+    #[cfg(feature = "gui")]
+    hud_score_text: TextCache<(bool, bool, i32, i32, u32)>,
+    /// The speed text in the score strip, built from the speed level and whether it is the last one.
+    #[cfg(feature = "gui")]
+    hud_speed_text: TextCache<(i32, bool)>,
+    /// The high scores shown after a game over, built from a hash of the scores, the timestamp format and the current
+    /// minute for relative timestamps.
+    #[cfg(feature = "gui")]
+    scoreboard_text: TextCache<(u64, DisplayFormat, i64)>,
 }
 
 impl Game {
-    /// Instantiate a new game.
+    /// Instantiate a new game, panicking if the options are invalid. The options are kept to rebuild the game on a
+    /// restart.
     /// # Arguments
-    /// * `width: i32` - The game window width in pixels.
-    /// * `height: i32` - The game window height in pixels.
+    /// * `config: GameConfig` - The options to create the game from.
     /// # Returns
     /// * `Game` - The new Game instance.
-    pub fn new(
-        width: i32,
-        height: i32,
-        starting_length: Option<i32>,
-        starting_direction: Option<Direction>,
-    ) -> Game {
-        Game {
-            snake: Snake::new(2, 2, starting_length, starting_direction),
-            waiting_time: 0.0,
-            food: Some(Block::new(6, 4)),
+    pub fn new(config: GameConfig) -> Game {
+        Game::try_new(config).unwrap_or_else(|error| panic!("Could not create the game: {error}"))
+    }
+
+    /// Instantiate a new game, checking the options first. The options are kept to rebuild the game on a restart.
+    /// # Arguments
+    /// * `config: GameConfig` - The options to create the game from.
+    /// # Returns
+    /// * `Result<Game, ConfigError>` - The new Game instance, or why the options cannot be played with.
+    pub fn try_new(config: GameConfig) -> Result<Game, ConfigError> {
+        config.validate()?;
+        let (width, height) = (config.width, config.height);
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut game = Game {
+            theme: config.theme(),
+            snake: Snake::new(2, 2, config.starting_length, config.starting_direction),
+            config,
+            pending_config: None,
+            rng,
+            waiting_time: Duration::ZERO,
+            food: None,
+            food_kind: FoodKind::Normal,
+            food_spawned_at: 0,
+            food_moves: 0,
             width,
             height: height - SCORE_BORDER_WIDTH,
             game_over: false,
-            direction_queue: Vec::new(),
-            score: 0,
+            death: None,
+            paused: false,
+            hints: false,
+            #[cfg(all(debug_assertions, feature = "gui"))]
+            debug_overlay: false,
+            snake_moves: 0,
+            direction_queue: VecDeque::new(),
+            base_score: 0,
+            bonus_score: 0,
+            breakdown: ScoreBreakdown::default(),
+            eat_streak: 0,
+            max_eat_streak: 0,
+            peak_length: 0,
+            foods_eaten: 0,
+            near_miss_count: 0,
+            risky_popup: None,
+            penalty_popup: None,
+            star: None,
+            multiplied_foods: 0,
+            multiplier_stacks: 0,
+            length_milestone: 0,
+            milestone_banner: None,
+            speed_up_timer: 0.0,
             high_score: false,
             score_written: false,
             score_name: create_empty_name(),
-            borders: Borders {
-                top_border: Block::new(0, 0),
-                bottom_border: Block::new(0, height - BORDER_WIDTH - SCORE_BORDER_WIDTH),
-                left_border: Block::new(0, 0),
-                right_border: Block::new(width - BORDER_WIDTH, 0),
-                score_border: Block::new(0, height - BORDER_WIDTH),
-                high_score_border: Block::new(BORDER_WIDTH, height / 2 + 1),
-                score_name_border: Block::new(BORDER_WIDTH, height / 2 - 1),
-            },
-        }
+            cursor_pos: 0,
+            cursor_blink: 0.0,
+            walls: Vec::new(),
+            doors: Vec::new(),
+            hazard_blocks: Vec::new(),
+            hurting: false,
+            hazard: HazardRow::new(),
+            events: Vec::new(),
+            recording: Recording::default(),
+            undo_history: VecDeque::new(),
+            play_time: Duration::ZERO,
+            fog_reveal: 0.0,
+            eye_blink_timer: EYE_BLINK_INTERVAL,
+            eyes_closed: false,
+            #[cfg(feature = "gui")]
+            hud_score_text: TextCache::new(),
+            #[cfg(feature = "gui")]
+            hud_speed_text: TextCache::new(),
+            #[cfg(feature = "gui")]
+            scoreboard_text: TextCache::new(),
+        };
+        game.restart();
+        Ok(game)
     }
 
     /// React to a keypress.
     /// # Arguments
     /// * `piston_window::Key` - The key being pressed.
+    #[cfg(feature = "gui")]
     pub fn key_pressed(&mut self, key: Key) {
+        // Any key dismisses the controls overlay, without doing anything else.
+        if self.dismiss_hints() {
+            return;
+        }
+        // Toggling the coordinate overlay with <F3>.
+        #[cfg(debug_assertions)]
+        if key == Key::F3 {
+            self.debug_overlay = !self.debug_overlay;
+            return;
+        }
+        // Undoing the last move with <U> in practice mode, also to come back from a game over.
+        if key == Key::U && self.config.practice {
+            self.undo();
+            return;
+        }
         if self.game_over {
-            match key {
-                Key::Space => self.restart(),
-                _ => return,
+            if key == Key::Space {
+                self.play_again();
             }
-        };
-
-        // Associating all valid keys with the Some part of the Option and invalid ones with the None part.
+            return;
+        }
+        // Pausing and resuming.
+        if key == Key::P {
+            self.toggle_pause();
+            return;
+        }
         let direction = match key {
-            Key::Up => Some(Direction::Up),
-            Key::Down => Some(Direction::Down),
-            Key::Left => Some(Direction::Left),
-            Key::Right => Some(Direction::Right),
-            _ => Some(self.snake.head_direction()),
+            Key::Up => Direction::Up,
+            Key::Down => Direction::Down,
+            Key::Left => Direction::Left,
+            Key::Right => Direction::Right,
+            _ => return,
         };
+        self.steer(direction);
+    }
+
+    /// Hide the controls overlay, which keeps the game from starting while it is shown.
+    /// # Returns
+    /// * `bool` - Whether the overlay was shown, in which case the keypress that dismissed it does nothing else.
+    pub fn dismiss_hints(&mut self) -> bool {
+        std::mem::replace(&mut self.hints, false)
+    }
+
+    /// Start a new run from the game over screen.
+    pub fn play_again(&mut self) {
+        if self.game_over {
+            self.events.push(GameEvent::MenuSelect);
+            self.restart()
+        }
+    }
+
+    /// Pause or resume the running game.
+    pub fn toggle_pause(&mut self) {
+        if !self.game_over {
+            self.paused = !self.paused;
+        }
+    }
 
-        // The snake cannot turn around.
-        if direction.unwrap() == self.snake.head_direction().opposite() {
+    /// Buffer a direction change for one of the next moves of the running game.
+    /// # Arguments
+    /// * `direction: Direction` - The direction to turn to, ignored when it would turn the Snake around.
+    pub fn steer(&mut self, direction: Direction) {
+        if self.game_over || self.paused {
+            return;
+        }
+        // The snake cannot turn around, also not relative to the direction it will have after the buffered moves.
+        let previous = self
+            .direction_queue
+            .back()
+            .copied()
+            .unwrap_or(self.snake.head_direction());
+        if direction == previous.opposite() || self.direction_queue.len() >= MAX_QUEUED_DIRECTIONS {
             return;
         }
-        self.direction_queue.push(direction);
+        self.direction_queue.push_back(direction);
+    }
+
+    /// Check whether the player is typing a name for a new high score, in which case letter keys are reserved.
+    pub fn entering_name(&self) -> bool {
+        self.game_over && self.high_score && !self.score_written
     }
 
     /// Interact with the name entry field.
-    /// * `key: piston_window::Key` - The key being pressed. Allows letter, backspace and enter.
+    /// * `key: piston_window::Key` - The key being pressed. Allows letter, backspace, delete, left, right and enter.
     /// * `scores: &mut Vec<Score>` - The vector of Score structs to push the new score to.
-    /// * `scores_file: &PathBuf` - The location of the score file to write the new scores to.
-    pub fn ask_name(&mut self, key: Key, scores: &mut Vec<Score>, scores_file: &PathBuf) {
-        if self.game_over && self.high_score && !self.score_written {
-            if let Some(letter) = match key {
-                // Valid letter.
-                Key::A => Some('A'),
-                Key::B => Some('B'),
-                Key::C => Some('C'),
-                Key::D => Some('D'),
-                Key::E => Some('E'),
-                Key::F => Some('F'),
-                Key::G => Some('G'),
-                Key::H => Some('H'),
-                Key::I => Some('I'),
-                Key::J => Some('J'),
-                Key::K => Some('K'),
-                Key::L => Some('L'),
-                Key::M => Some('M'),
-                Key::N => Some('N'),
-                Key::O => Some('O'),
-                Key::P => Some('P'),
-                Key::Q => Some('Q'),
-                Key::R => Some('R'),
-                Key::S => Some('S'),
-                Key::T => Some('T'),
-                Key::U => Some('U'),
-                Key::V => Some('V'),
-                Key::W => Some('W'),
-                Key::X => Some('X'),
-                Key::Y => Some('Y'),
-                Key::Z => Some('Z'),
-                // Removing a letter from the name.
-                Key::Backspace => {
-                    self.score_name.pop();
-                    None
-                }
-                // Accepting the name.
-                Key::Return => {
-                    write_score(scores, &self.score_name, self, scores_file);
-                    self.score_written = true;
-                    None
-                }
-                // Invalid key.
-                _ => None,
-            } {
-                // Adding a letter if there is still room.
-                if self.score_name.chars().count() < MAX_NAME_LENGTH {
-                    self.score_name.push(letter)
-                }
-            }
+    /// * `storage: &mut dyn Storage` - Where the score file is written to.
+    #[cfg(feature = "gui")]
+    pub fn ask_name(&mut self, key: Key, scores: &mut Vec<Score>, storage: &mut dyn Storage) {
+        let letter = match key {
+            Key::A => Some('A'),
+            Key::B => Some('B'),
+            Key::C => Some('C'),
+            Key::D => Some('D'),
+            Key::E => Some('E'),
+            Key::F => Some('F'),
+            Key::G => Some('G'),
+            Key::H => Some('H'),
+            Key::I => Some('I'),
+            Key::J => Some('J'),
+            Key::K => Some('K'),
+            Key::L => Some('L'),
+            Key::M => Some('M'),
+            Key::N => Some('N'),
+            Key::O => Some('O'),
+            Key::P => Some('P'),
+            Key::Q => Some('Q'),
+            Key::R => Some('R'),
+            Key::S => Some('S'),
+            Key::T => Some('T'),
+            Key::U => Some('U'),
+            Key::V => Some('V'),
+            Key::W => Some('W'),
+            Key::X => Some('X'),
+            Key::Y => Some('Y'),
+            Key::Z => Some('Z'),
+            // Removing a letter before or at the cursor.
+            Key::Backspace => return self.erase_letter(),
+            Key::Delete => return self.delete_letter(),
+            // Moving the cursor through the name.
+            Key::Left => return self.move_cursor(Direction::Left),
+            Key::Right => return self.move_cursor(Direction::Right),
+            // Accepting the name.
+            Key::Return => return self.confirm_name(scores, storage),
+            // Invalid key.
+            _ => None,
+        };
+        if let Some(letter) = letter {
+            self.type_letter(letter);
         }
     }
 
-    /// Move to the next position and ead food, stopping the game in case of a death.
-    pub fn update_snake(&mut self) {
-        let direction = match self.direction_queue.last() {
-            Some(dir) => *dir,
-            None => Some(self.snake.head_direction()),
-        };
-        if self.check_snake_alive(direction) {
-            self.snake.move_forward(direction);
-            self.check_eaten();
-        } else {
-            self.game_over = true;
+    /// Add a letter to the name of a new high score at the cursor, if there is still room.
+    /// # Arguments
+    /// * `letter: char` - The letter, ignored unless it is in the alphabet, and stored in upper case.
+    pub fn type_letter(&mut self, letter: char) {
+        if self.entering_name()
+            && letter.is_ascii_alphabetic()
+            && self.score_name.chars().count() < MAX_NAME_LENGTH
+        {
+            // Names only hold ASCII letters, so letters and bytes line up.
+            self.score_name
+                .insert(self.cursor_pos, letter.to_ascii_uppercase());
+            self.cursor_pos += 1;
+            self.cursor_blink = 0.0;
         }
-        // Resetting.
-        self.waiting_time = 0.0;
-        self.direction_queue.clear();
     }
 
-    /// Move the food if not eaten yet and the game is not over.
-    pub fn update_food(&mut self) {
-        let speed = if self.game_over {
-            0
-        } else {
-            FOOD_SPEED_INCREASE
-        };
-        if let Some(food) = self.food {
-            let offset = food::escape(food, &self.snake, [0, self.width], [0, self.height], speed);
-            self.food = Some(Block::new(food.x + offset[0], food.y + offset[1]))
+    /// Remove the letter before the cursor from the name of a new high score.
+    pub fn erase_letter(&mut self) {
+        if self.entering_name() && self.cursor_pos > 0 {
+            self.cursor_pos -= 1;
+            self.score_name.remove(self.cursor_pos);
+            self.cursor_blink = 0.0;
         }
     }
 
-    fn _draw_background(&self, con: &Context, g: &mut G2d) {
-        // Drawing the top, bottom, left and right borders of the screen.
-
-        draw_rectangle(
-            BORDER_COLOR,
-            self.borders.top_border,
-            self.width,
-            BORDER_WIDTH,
-            con,
-            g,
-        );
-        draw_rectangle(
-            BORDER_COLOR,
-            self.borders.bottom_border,
-            self.width,
-            BORDER_WIDTH,
-            con,
-            g,
-        );
-        draw_rectangle(
-            BORDER_COLOR,
-            self.borders.left_border,
-            BORDER_WIDTH,
-            self.height,
-            con,
-            g,
-        );
-        draw_rectangle(
-            BORDER_COLOR,
-            self.borders.right_border,
-            BORDER_WIDTH,
-            self.height,
-            con,
-            g,
-        );
+    /// Remove the letter at the cursor from the name of a new high score.
+    pub fn delete_letter(&mut self) {
+        if self.entering_name() && self.cursor_pos < self.score_name.len() {
+            self.score_name.remove(self.cursor_pos);
+            self.cursor_blink = 0.0;
+        }
+    }
 
-        // Drawing the score border.
-        draw_rectangle(
-            BORDER_COLOR,
-            self.borders.score_border,
-            self.width,
-            SCORE_BORDER_WIDTH,
-            con,
-            g,
-        );
+    /// Move the cursor of the name entry a letter to the left or right, staying within the name.
+    /// # Arguments
+    /// * `direction: Direction` - The way to move the cursor. Up and down are ignored.
+    pub fn move_cursor(&mut self, direction: Direction) {
+        if !self.entering_name() {
+            return;
+        }
+        self.cursor_pos = match direction {
+            Direction::Left => self.cursor_pos.saturating_sub(1),
+            Direction::Right => (self.cursor_pos + 1).min(self.score_name.len()),
+            Direction::Up | Direction::Down => return,
+        };
+        self.cursor_blink = 0.0;
     }
 
-    fn _draw_score_text(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
-        draw_text(
-            &format!("SCORE: {}", self.score.to_string().as_str()),
-            Block::new(SCORE_BORDER_WIDTH, self.height + SCORE_BORDER_WIDTH / 2),
-            FOOD_COLOR,
-            SCORE_FONT_SIZE,
-            glyphs,
-            con,
-            g,
-        );
+    /// Get where the cursor of the name entry is shown, blinking unless motion is reduced.
+    /// # Returns
+    /// * `Option<usize>` - The number of letters before the cursor, or None while it is hidden.
+    pub fn name_cursor(&self) -> Option<usize> {
+        let shown = self.config.reduce_motion || self.cursor_blink < CURSOR_BLINK_PERIOD;
+        (self.entering_name() && shown).then_some(self.cursor_pos)
     }
 
-    fn _draw_speed_text(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
-        draw_text(
-            &format!(
-                "SPEED: {}",
-                (1 + self.score / FOODS_PER_SPEED_INCREASE)
-                    .to_string()
-                    .as_str()
-            ),
-            Block::new(
-                self.width - 7 * SCORE_BORDER_WIDTH,
-                self.height + SCORE_BORDER_WIDTH / 2,
-            ),
-            FOOD_COLOR,
-            SCORE_FONT_SIZE,
-            glyphs,
-            con,
-            g,
-        );
+    /// Accept the name of a new high score and write it.
+    /// * `scores: &mut Vec<Score>` - The vector of Score structs to push the new score to.
+    /// * `storage: &mut dyn Storage` - Where the score file is written to.
+    pub fn confirm_name(&mut self, scores: &mut Vec<Score>, storage: &mut dyn Storage) {
+        if self.entering_name() {
+            self.events.push(GameEvent::MenuSelect);
+            self.write_pending_score(scores, storage);
+        }
     }
-    fn _draw_game_over_screen(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
-        draw_rectangle(
-            GAMEOVER_COLOR,
-            Block::new(SCORE_BORDER_WIDTH, BORDER_WIDTH),
-            self.width - 2 * BORDER_WIDTH,
-            self.height - BORDER_WIDTH - SCORE_BORDER_WIDTH,
-            con,
-            g,
-        );
-        let highscore = match self.high_score {
-            true => " - HIGHSCORE",
-            false => "",
-        };
-        draw_text(
-            &format!("GAME OVER\n{}{}\n<SPACE> TO PLAY", self.score, highscore),
-            Block::new(BORDER_WIDTH, BORDER_WIDTH),
-            GAMEOVER_TEXT_COLOR,
-            32,
-            glyphs,
-            con,
-            g,
-        );
+
+    /// Write the current high score if it has not been written yet, e.g. when the window closes during name entry.
+    /// Falls back to the configured default player name if no name was entered.
+    /// * `scores: &mut Vec<Score>` - The vector of Score structs to push the new score to.
+    /// * `storage: &mut dyn Storage` - Where the score file is written to.
+    pub fn write_pending_score(&mut self, scores: &mut Vec<Score>, storage: &mut dyn Storage) {
+        if self.entering_name() {
+            let name = if self.score_name.is_empty() {
+                self.config
+                    .default_player_name
+                    .chars()
+                    .take(MAX_NAME_LENGTH)
+                    .collect()
+            } else {
+                self.score_name.clone()
+            };
+            write_score(scores, &name, self, storage);
+            self.score_written = true;
+        }
     }
 
-    fn _draw_scoreboard(&self, scores: &[Score], glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
-        show_scores(
-            scores,
-            self.borders.high_score_border,
-            GAMEOVER_TEXT_COLOR,
-            15,
-            glyphs,
-            con,
-            g,
-        )
+    /// Move to the next position and eat food, stopping the game in case of a death.
+    /// # Returns
+    /// * `bool` - Whether the game continues (true) or is over (false).
+    pub fn update_snake(&mut self) -> bool {
+        let direction = Some(
+            self.direction_queue
+                .pop_front()
+                .unwrap_or(self.snake.head_direction()),
+        );
+        let mut death = self.collision(direction);
+        if death.is_none() {
+            if self.near_miss(self.snake.next_head(direction)) {
+                self.near_miss_count += 1;
+            }
+            let speed_level = self.speed_level();
+            self.snake.move_forward(direction);
+            self.snake_moves += 1;
+            // Every move survived is worth a point in survival mode.
+            if self.config.survival {
+                self.add_score(1, ScoreReason::Survival);
+            }
+            self.check_eaten();
+            self.check_star();
+            self.check_length_milestones();
+            self.check_keys();
+            death = self.check_hazard_blocks();
+            if self.speed_level() > speed_level {
+                self.events.push(GameEvent::SpeedUp);
+                self.speed_up_timer = SPEED_UP_DURATION;
+            }
+        }
+        if death.is_some() {
+            self.game_over = true;
+            self.death = death;
+            // The multiplier never carries over into the next run.
+            self.star = None;
+            self.multiplied_foods = 0;
+            self.multiplier_stacks = 0;
+        }
+        // Resetting.
+        self.waiting_time = Duration::ZERO;
+        !self.game_over
     }
 
-    fn _draw_name_querry(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
-        draw_text(
-            &format!("Name: {}", &self.score_name),
-            self.borders.score_name_border,
-            GAMEOVER_TEXT_COLOR,
-            SCORE_FONT_SIZE,
-            glyphs,
-            con,
-            g,
-        )
+    /// Move the food if not eaten yet and the game is not over.
+    /// The food stays put during the grace period at the start of every run and when motion is reduced.
+    /// # Returns
+    /// * `Option<Block>` - The new food position if the food moved, None if it stayed put.
+    pub fn update_food(&mut self) -> Option<Block> {
+        // Not even searching for an escape when the food cannot move.
+        if self.game_over
+            || self.config.reduce_motion
+            || self.snake_moves <= self.config.escape_grace_moves
+        {
+            return None;
+        }
+        let food = self.food?;
+        let offset = food::escape(
+            food,
+            &self.snake,
+            &self.obstacles(),
+            [0, self.width],
+            [0, self.height],
+            FOOD_SPEED_INCREASE,
+            self.config
+                .difficulty
+                .food_strategy(self.config.escape_lookahead),
+            &mut self.rng,
+        );
+        if offset == [0, 0] {
+            return None;
+        }
+        self.food = Some(Block::new(food.x + offset[0], food.y + offset[1]));
+        self.food_moves += 1;
+        self.food
     }
 
-    /// Draw all game elements: the snake, the borders, food, game over symbols and the score.
-    /// # Arguments
-    /// * `glyphs: &mut piston_window::Glyphs` - The characters to use for drawing.
-    /// * `con: &piston_window::Context` - The context in which to draw.
-    /// * `g: &mut G2d` - The 2d graphics driver to use.
-    pub fn draw(
-        &mut self,
-        // key: Option<Key>,
-        // scores: &HashMap<i32, Score>,
-        glyphs: &mut Glyphs,
-        con: &Context,
-        g: &mut G2d,
-        scores: &[Score],
-    ) {
-        // Drawing the snake and food.
-        self.snake.draw(con, g);
-        if let Some(food) = self.food {
-            draw_block(
-                food,
-                FOOD_COLOR,
-                [0.0, 0.0],
-                [BLOCK_SIZE, BLOCK_SIZE],
-                con,
-                g,
+    fn _score_text(&self, locale: &Locale) -> String {
+        let score = if self.config.survival {
+            locale.format("time", &[("time", &self.score())])
+        } else {
+            locale.format("score", &[("score", &self.score())])
+        };
+        let score = if self.multiplied_foods > 0 {
+            let multiplier = locale.format(
+                "multiplier",
+                &[
+                    ("factor", &self.score_multiplier()),
+                    ("left", &self.multiplied_foods),
+                ],
             );
+            format!("{} {}", score, multiplier)
+        } else {
+            score
         };
+        if self.config.practice {
+            format!("{} {}", score, locale.get("practice"))
+        } else {
+            score
+        }
+    }
 
-        self._draw_background(con, g);
-        self._draw_score_text(glyphs, con, g);
-        self._draw_speed_text(glyphs, con, g);
+    fn _speed_text(&self, locale: &Locale) -> String {
+        let key = if self.at_max_speed() {
+            "speed_max"
+        } else {
+            "speed"
+        };
+        locale.format(key, &[("speed", &self.speed_level())])
+    }
 
-        // Drawing a game over screen.
-        if self.game_over {
-            self._draw_game_over_screen(glyphs, con, g);
-            self._draw_scoreboard(scores, glyphs, con, g)
+    /// Advance the game logic by a single step.
+    ///
+    /// The order within a step is fixed: the Snake first resolves its movement and eating against the position the
+    /// food had at the start of the step, and only then does surviving food get a chance to escape. The food can
+    /// therefore never dodge out of the cell the head is entering during the same step.
+    pub fn tick(&mut self) {
+        #[cfg(feature = "logging")]
+        {
+            let head = self.snake.head_position();
+            log::debug!(
+                "Tick {}, head at ({}, {})",
+                self.snake_moves,
+                head.x,
+                head.y
+            );
         }
-
-        if self.high_score {
-            self._draw_name_querry(glyphs, con, g);
+        if self.food.is_none() {
+            self.add_food();
+        }
+        if self.config.practice {
+            let steps = self.config.undo_steps.clamp(1, MAX_UNDO_STEPS);
+            while self.undo_history.len() >= steps {
+                self.undo_history.pop_front();
+            }
+            self.undo_history.push_back(self.snapshot());
+        }
+        if self.update_snake() {
+            self.update_food();
+        } else {
+            self.events.push(GameEvent::Died);
         }
+        self.record_frame();
+    }
+
+    /// Store the current board in the recording of the run.
+    fn record_frame(&mut self) {
+        self.recording.record(Frame {
+            snake: self.snake.blocks().collect(),
+            food: self.food.map(|food| (food, self.food_kind)),
+        });
+    }
+
+    /// Get every tick of the current run, starting with the board before the first move.
+    pub fn recording(&self) -> &Recording {
+        &self.recording
     }
 
     /// Move the game one tick, checking for game over, food presence and drawing the snake.
     /// # Arguments
     /// * `delta_time: f64` - The timestep of the tick in seconds.
     pub fn update(&mut self, delta_time: f64) {
+        if self.entering_name() {
+            self.cursor_blink = (self.cursor_blink + delta_time) % (2.0 * CURSOR_BLINK_PERIOD);
+        }
         // Stop movement
-        if self.game_over {
+        if self.game_over || self.paused || self.hints {
             return;
         }
 
-        self.waiting_time += delta_time;
+        // Ignoring negative and invalid timesteps rather than moving back in time.
+        let delta = Duration::try_from_secs_f64(delta_time).unwrap_or_default();
+        self.waiting_time += delta;
+        self.play_time += delta;
+        self.fog_reveal = (self.fog_reveal - delta_time).max(0.0);
+        self.risky_popup = count_down(self.risky_popup, delta_time);
+        self.penalty_popup = count_down(self.penalty_popup, delta_time);
+        self.milestone_banner = count_down(self.milestone_banner, delta_time);
+        self.speed_up_timer = (self.speed_up_timer - delta_time).max(0.0);
+        self.update_blink(delta_time);
 
         // Drawing food if not yet food.
         match self.food {
             Some(_) => (),
             None => self.add_food(),
         }
-        // Moving after the moving period has passed.
-        if self.waiting_time
-            > MOVING_PERIOD * SPEED_FACTOR.powi(self.score / FOODS_PER_SPEED_INCREASE)
-        {
-            self.update_food();
-            self.update_snake();
+        // Moving after the moving period has passed, catching up on several buffered moves after a long frame.
+        let moving_period = self.moving_period();
+        if self.waiting_time > moving_period {
+            let steps = (self.waiting_time.div_duration_f64(moving_period) as usize)
+                .clamp(1, self.direction_queue.len().max(1));
+            for _ in 0..steps {
+                if self.game_over {
+                    break;
+                }
+                self.tick();
+            }
+        }
+        if self.hazard_rows_active() && !self.game_over {
+            let rows = BORDER_WIDTH..self.height - BORDER_WIDTH;
+            if let Some(row) = self.hazard.update(delta_time, rows, &mut self.rng) {
+                self.strike_row(row);
+            }
+        }
+    }
+
+    /// Open or close the eyes of the Snake, blinking briefly every few seconds unless motion is reduced.
+    /// # Arguments
+    /// * `delta_time: f64` - The time passed since the last update in seconds.
+    fn update_blink(&mut self, delta_time: f64) {
+        if self.config.reduce_motion {
+            self.eyes_closed = false;
+            return;
+        }
+        self.eye_blink_timer -= delta_time;
+        if self.eye_blink_timer <= 0.0 {
+            self.eyes_closed = !self.eyes_closed;
+            self.eye_blink_timer = if self.eyes_closed {
+                EYE_BLINK_DURATION
+            } else {
+                EYE_BLINK_INTERVAL
+            };
+        }
+    }
+
+    /// Check whether a random row periodically turns lethal, which only happens on hard difficulty.
+    pub fn hazard_rows_active(&self) -> bool {
+        self.config.hazard_rows && self.config.difficulty == Difficulty::Hard
+    }
+
+    /// Make a row lethal for a single moment: the game is over if any part of the Snake is on it, and food on it
+    /// moves elsewhere.
+    /// # Arguments
+    /// * `row: i32` - The y-coordinate of the row.
+    pub fn strike_row(&mut self, row: i32) {
+        if self.snake.blocks().any(|block| block.y == row) {
+            self.game_over = true;
+            self.death = Some(DeathCause::Hazard);
+            self.events.push(GameEvent::Died);
+        } else if self.food.is_some_and(|food| food.y == row) {
+            self.food = None;
+            self.food_moves = 0;
+            self.add_food();
+        }
+    }
+
+    /// Get the time between two Snake moves at the current speed level.
+    /// # Returns
+    /// * `Duration` - The moving period.
+    pub fn moving_period(&self) -> Duration {
+        let steps = if self.config.smooth_speed {
+            self.speed_steps()
+                .min((self.config.max_speed_level - 1) as f64)
+        } else {
+            (self.speed_level() - 1) as f64
+        };
+        Duration::from_secs_f64(
+            self.config.period_at(steps) * self.config.difficulty.period_factor(),
+        )
+    }
+
+    /// Get the number of speed increases earned so far, including the progress towards the next one. The game speeds up
+    /// every few foods eaten, or every few moves survived in survival mode.
+    fn speed_steps(&self) -> f64 {
+        if self.config.survival {
+            self.snake_moves as f64 / SURVIVAL_MOVES_PER_SPEED_INCREASE as f64
+        } else {
+            self.foods_eaten as f64 / self.config.foods_per_speed_increase as f64
+        }
+    }
+
+    /// Get the current speed level, starting at 1 and increasing with every step of `speed_steps`.
+    /// Food is worth its points times this level, so the speed does not follow the score.
+    pub fn speed_level(&self) -> i32 {
+        (1 + self.speed_steps() as i32).min(self.config.max_speed_level)
+    }
+
+    /// Get the speed level after which the game does not speed up any further.
+    pub fn max_speed_level(&self) -> i32 {
+        self.config.max_speed_level
+    }
+
+    /// Check whether the game cannot speed up any further.
+    pub fn at_max_speed(&self) -> bool {
+        self.speed_level() >= self.config.max_speed_level
+    }
+
+    /// Get the progress towards the next speed level.
+    /// # Returns
+    /// * `f64` - The fraction of the foods needed for the next speed level that has been eaten, 1 at max speed.
+    pub fn speed_progress(&self) -> f64 {
+        if self.at_max_speed() {
+            1.0
+        } else {
+            self.speed_steps().fract()
         }
     }
 
-    /// Reset all the games attributes.
+    /// Reset all the games attributes, rebuilding the Snake and food from the games options.
     pub fn restart(&mut self) {
-        self.snake = Snake::new(2, 2, None, None);
-        self.direction_queue = Vec::new();
-        self.waiting_time = 0.0;
-        self.food = Some(Block::new(6, 4));
+        // Applying the options changed during the last run, including a changed board size.
+        if let Some(config) = self.pending_config.take() {
+            self.config = config;
+            self.theme = self.config.theme();
+        }
+        let (width, height) = (self.config.width, self.config.height);
+        self.width = width;
+        self.height = height - SCORE_BORDER_WIDTH;
+        let mut layout = self.config.level.layout(width, self.height, &mut self.rng);
+        if self.config.arcade {
+            let obstacles = self.arcade_obstacles(&layout);
+            layout.walls.extend(obstacles);
+        }
+        self.reset_to_level(&layout);
+    }
+
+    /// Start a new run on a level: the Snake, walls and food are placed as laid out, and the score and timing reset.
+    /// # Arguments
+    /// * `layout: &Layout` - The level, which must fit the current board size.
+    pub fn reset_to_level(&mut self, layout: &Layout) {
+        self.snake = Snake::new(
+            layout.start.x,
+            layout.start.y,
+            self.config.starting_length,
+            self.config.starting_direction,
+        );
+        self.walls = layout.walls.clone();
+        self.doors = layout.doors.clone();
+        self.hazard_blocks = layout.hazards.clone();
+        self.hurting = false;
+        self.hazard = HazardRow::new();
+        self.direction_queue = VecDeque::new();
+        self.waiting_time = Duration::ZERO;
+        self.paused = false;
+        self.snake_moves = 0;
+        self.foods_eaten = 0;
+        self.near_miss_count = 0;
+        self.risky_popup = None;
+        self.penalty_popup = None;
+        self.star = None;
+        self.multiplied_foods = 0;
+        self.multiplier_stacks = 0;
+        self.length_milestone = milestone(self.snake.body_len() as i32);
+        self.milestone_banner = None;
+        self.speed_up_timer = 0.0;
+        self.undo_history.clear();
+        self.food_moves = 0;
+        // The configured food replaces the laid out one, unless it would start on the Snake, a wall or a key.
+        let obstacles = self.obstacles();
+        let food = self
+            .config
+            .initial_food
+            .filter(|food| {
+                !obstacles.contains(food)
+                    && !self.snake.blocks().any(|block| block == *food)
+                    && self.doors.iter().all(|door| door.key != *food)
+                    && !self.hazard_blocks.contains(food)
+            })
+            .or(layout.food.first().copied());
+        match food {
+            Some(food) => {
+                self.food = Some(food);
+                self.food_kind = FoodKind::Normal;
+                self.food_spawned_at = 0;
+            }
+            None => self.add_food(),
+        }
         self.game_over = false;
-        self.score = 0;
+        self.death = None;
+        self.base_score = 0;
+        self.bonus_score = 0;
+        self.breakdown = ScoreBreakdown::default();
+        self.eat_streak = 0;
+        self.max_eat_streak = 0;
+        self.peak_length = self.snake.body_len() as i32;
+        self.play_time = Duration::ZERO;
+        self.fog_reveal = 0.0;
         self.high_score = false;
         self.score_written = false;
         self.score_name = create_empty_name();
+        self.cursor_pos = 0;
+        self.cursor_blink = 0.0;
+        self.recording = Recording::new(self.width, self.height, self.walls.clone());
+        self.record_frame();
+    }
+
+    /// Place random obstacles for arcade mode, keeping the area around the head and the first moves of the Snake clear.
+    /// # Arguments
+    /// * `layout: &Layout` - The level to place the obstacles on.
+    /// # Returns
+    /// * `Vec<Block>` - The obstacles, leaving every free Block reachable.
+    fn arcade_obstacles(&mut self, layout: &Layout) -> Vec<Block> {
+        let snake = Snake::new(
+            layout.start.x,
+            layout.start.y,
+            self.config.starting_length,
+            self.config.starting_direction,
+        );
+        let head = snake.head_position();
+        let [dx, dy] = snake.head_direction().offset();
+        let reserved: Vec<Block> = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |y| Block::new(head.x + x, head.y + y)))
+            .chain(
+                (1..=OBSTACLE_FREE_RUNWAY)
+                    .map(|step| Block::new(head.x + dx * step, head.y + dy * step)),
+            )
+            .chain(layout.food.iter().copied())
+            // Keeping the cells next to the food open as well, so it never spawns boxed in.
+            .chain(
+                (0..self.width)
+                    .flat_map(|x| (0..self.height).map(move |y| Block::new(x, y)))
+                    .filter(|block| layout.food.iter().any(|food| food.is_adjacent(*block))),
+            )
+            .chain(
+                layout
+                    .doors
+                    .iter()
+                    .flat_map(|door| [door.position, door.key]),
+            )
+            .collect();
+        let count = (self.playable_cell_count() as f64 * self.config.difficulty.obstacle_density())
+            as usize;
+        generate_obstacles(
+            &mut self.rng,
+            count,
+            head,
+            &reserved,
+            &layout.walls,
+            self.width,
+            self.height,
+        )
     }
 
     /// Respawn food at a random location after a previous one has been eaten.
     pub fn add_food(&mut self) {
-        // Spawn food at a random location.
-        let mut rng = thread_rng();
-        let mut food = Block::new(
-            rng.gen_range(1..self.width - 1),
-            rng.gen_range(1..self.height - 1),
-        );
-        // Food cannot spawn on the snake.
-        while self.snake.overlap_tail(food) {
-            food = Block::new(
-                rng.gen_range(1..self.width - 1),
-                rng.gen_range(1..self.height - 1),
-            );
+        // Leaving the board empty for good once the food supply of the run is used up.
+        if self.food_supply_exhausted() {
+            return;
         }
+        // Food cannot spawn on the snake or a key, nor anywhere the walls and closed doors cut off from the head.
+        let walls: HashSet<Block> = self.obstacles().into_iter().collect();
+        let keys: Vec<Block> = self.doors.iter().map(|door| door.key).collect();
+        let (width, height) = (self.width, self.height);
+        let reachable = flood_fill(self.snake.head_position(), |block| {
+            !block.out_of_bounds([0, width], [0, height]) && !walls.contains(&block)
+        });
+        let (playable_width, playable_height) = self.playable_area();
+        let free: Vec<Block> = (BORDER_WIDTH..BORDER_WIDTH + playable_width)
+            .flat_map(|x| {
+                (BORDER_WIDTH..BORDER_WIDTH + playable_height).map(move |y| Block::new(x, y))
+            })
+            .filter(|block| {
+                !self.snake.overlap_tail(*block)
+                    && reachable.contains(block)
+                    && !keys.contains(block)
+                    && !self.hazard_blocks.contains(block)
+                    && self.star != Some(*block)
+            })
+            .collect();
+        // Keeping food away from the head to leave time to react, at half the distance on a board too full for it.
+        let head = self.snake.head_position();
+        let min_distance = self.config.food_spawn_min_distance as f64;
+        let free = [min_distance, min_distance / 2.0]
+            .into_iter()
+            .map(|distance| {
+                free.iter()
+                    .copied()
+                    .filter(|block| food::get_distance(*block, head) >= distance)
+                    .collect::<Vec<Block>>()
+            })
+            // At the very least not right in front of the head, where it would be eaten on the next move.
+            .chain(std::iter::once(
+                free.iter()
+                    .copied()
+                    .filter(|block| !block.is_adjacent(head))
+                    .collect::<Vec<Block>>(),
+            ))
+            .find(|blocks| !blocks.is_empty())
+            .unwrap_or(free);
+        // Food preferably does not spawn right next to the snake either, unless there is no room left for that.
+        // Diagonal neighbors are fine, as the snake cannot reach those in a single move.
+        let spaced: Vec<Block> = free
+            .iter()
+            .copied()
+            .filter(|block| food::get_closest_snake_segment_distance(*block, &self.snake) > 1.0)
+            .collect();
+        let candidates = if spaced.is_empty() { free } else { spaced };
+        // Spawn food at the random location furthest away from the head.
         // Updating the food attribute, hence the mutable reference to self.
-        self.food = Some(food);
+        self.food = candidates
+            .choose_multiple(&mut self.rng, FOOD_SPAWN_CANDIDATES)
+            .copied()
+            .max_by(|a, b| food::get_distance(*a, head).total_cmp(&food::get_distance(*b, head)));
+        self.food_kind = food::weighted_random_food_kind(&self.config.food_weights, &mut self.rng);
+        self.food_spawned_at = self.snake_moves;
+        // Rarely placing a star next to the food, as long as there is none yet and it can still add to the multiplier.
+        if self.star.is_none()
+            && !self.config.survival
+            && self.multiplier_stacks < MAX_STAR_STACKS
+            && self.rng.gen_bool(food::STAR_CHANCE)
+        {
+            let spots: Vec<Block> = candidates
+                .into_iter()
+                .filter(|block| self.food != Some(*block))
+                .collect();
+            self.star = spots.choose(&mut self.rng).copied();
+        }
+    }
+
+    /// Pick up the star when the head reaches it, multiplying the points of the next `MULTIPLIED_FOODS` foods.
+    /// A star picked up while the multiplier is already stacked `MAX_STAR_STACKS` times is used up without effect.
+    fn check_star(&mut self) {
+        if self.star != Some(self.snake.head_position()) {
+            return;
+        }
+        self.star = None;
+        if self.multiplier_stacks < MAX_STAR_STACKS {
+            self.multiplier_stacks += 1;
+            self.multiplied_foods = MULTIPLIED_FOODS;
+        }
+    }
+
+    /// Get the factor the points of food are multiplied by, which is 1 without any star picked up.
+    pub fn score_multiplier(&self) -> i32 {
+        if self.multiplied_foods > 0 {
+            STAR_MULTIPLIER.pow(self.multiplier_stacks)
+        } else {
+            1
+        }
+    }
+
+    /// Get the multiplier of the current food, which decays the longer it sits uneaten.
+    fn food_freshness(&self) -> i32 {
+        food::freshness(self.snake_moves.saturating_sub(self.food_spawned_at))
+    }
+
+    /// Get the number of points the current food is worth before the speed level multiplier.
+    pub fn food_value(&self) -> i32 {
+        self.food_kind.points() * self.food_freshness()
     }
 
-    /// Check if the snake has eaten food.
+    /// Check if the snake has eaten food, keeping track of the streak of foods eaten on consecutive moves.
     pub fn check_eaten(&mut self) {
+        if self.food != Some(self.snake.head_position()) {
+            self.eat_streak = 0;
+        }
         // The head position coincides with the food.
-        if self.snake.head_position() == self.food.unwrap() {
-            self.snake
-                .digesting
-                .insert(self.food.unwrap(), self.snake.len());
+        if self.food == Some(self.snake.head_position()) {
+            self.snake.start_digesting(self.config.max_digesting);
             self.food = None;
+            let food_moves = std::mem::take(&mut self.food_moves);
             self.snake.restore_tail();
-            self.score += 1;
+            self.peak_length = self.peak_length.max(self.snake.body_len() as i32);
+            let speed_level = self.speed_level();
+            self.eat_streak += 1;
+            self.foods_eaten += 1;
+            self.max_eat_streak = self.max_eat_streak.max(self.eat_streak);
+            // Survival runs score the moves survived instead, so food only makes the Snake grow.
+            if !self.config.survival {
+                let earned = self.score();
+                // Multiplying the decayed value of the food with the speed level it was eaten at.
+                let value = self.food_value();
+                self.add_score(value, ScoreReason::Food(self.food_kind));
+                self.add_score(
+                    value * (speed_level - 1),
+                    ScoreReason::Speed(self.food_kind),
+                );
+                if self.combo_active() {
+                    self.add_score(COMBO_BONUS_POINTS, ScoreReason::Combo);
+                }
+                let head = self.snake.head_position();
+                if self.occupied_neighbors(head) >= RISKY_NEIGHBORS {
+                    self.add_score(RISKY_BONUS_POINTS, ScoreReason::Risky);
+                    self.risky_popup = Some((head, RISKY_POPUP_DURATION));
+                }
+                // Multiplying everything this food earned, bonuses included, while a star is active.
+                if self.multiplied_foods > 0 {
+                    let earned = self.score() - earned;
+                    self.add_score(
+                        earned * (self.score_multiplier() - 1),
+                        ScoreReason::Multiplier,
+                    );
+                    self.multiplied_foods -= 1;
+                    if self.multiplied_foods == 0 {
+                        self.multiplier_stacks = 0;
+                    }
+                }
+            }
+            self.fog_reveal = FOG_REVEAL_DURATION;
+            self.events.push(GameEvent::AteFood { food_moves });
+            #[cfg(feature = "logging")]
+            log::info!("Ate food {}, score {}", self.foods_eaten, self.score());
+        }
+    }
+
+    /// Add points to the score, or take them away, which is the only way the score changes.
+    /// The score never drops below zero, but the breakdown still shows the full penalty next to the points given
+    /// back, and every penalty pops up at the head.
+    /// # Arguments
+    /// * `delta: i32` - The points, negative for a penalty.
+    /// * `reason: ScoreReason` - Why the points were earned or lost, which decides where they are counted.
+    pub fn add_score(&mut self, delta: i32, reason: ScoreReason) {
+        let applied = delta.max(-self.score());
+        self.breakdown.record(delta, reason);
+        self.breakdown.floor += applied - delta;
+        if reason.is_base() {
+            self.base_score += applied;
+        } else {
+            self.bonus_score += applied;
         }
+        if delta < 0 {
+            self.penalty_popup =
+                Some(((self.snake.head_position(), -delta), PENALTY_POPUP_DURATION));
+        }
+    }
+
+    /// Count the neighbors of a Block that are blocked by the border, a wall, a closed door or the Snake body, not
+    /// counting the head nor the segment right behind it, through which the head reaches the Block.
+    /// # Arguments
+    /// * `block: Block` - The Block to count the neighbors of.
+    /// # Returns
+    /// * `usize` - The number of blocked neighbors, from 0 to 4.
+    pub fn occupied_neighbors(&self, block: Block) -> usize {
+        let obstacles = self.obstacles();
+        let mut segments = self.snake.blocks();
+        let head = segments.next();
+        let neck = segments.next();
+        // Segments still stacked behind the head, while the Snake unfolds at the start, are skipped too.
+        let body: Vec<Block> = segments
+            .filter(|segment| Some(*segment) != head && Some(*segment) != neck)
+            .collect();
+        Direction::offsets()
+            .into_iter()
+            .map(|(_, offset)| Block::new(block.x + offset[0], block.y + offset[1]))
+            .filter(|neighbor| {
+                neighbor.out_of_bounds([0, self.width], [0, self.height])
+                    || obstacles.contains(neighbor)
+                    || body.contains(neighbor)
+            })
+            .count()
+    }
+
+    /// Check whether moving the head to a position brings it right next to the Snake body, not counting the current
+    /// head position, which the new head always borders, nor the tail, which moves away.
+    /// # Arguments
+    /// * `destination: Block` - The next position of the head, which should not collide with the body.
+    /// # Returns
+    /// * `bool` - Whether (true) or not (false) the move is a close call.
+    pub fn near_miss(&self, destination: Block) -> bool {
+        let head = self.snake.head_position();
+        let len = self.snake.body_len();
+        // Segments still stacked on the head, while the Snake unfolds at the start, are skipped too.
+        self.snake
+            .blocks()
+            .take(len.saturating_sub(1))
+            .any(|segment| segment != head && segment.is_adjacent(destination))
     }
 
     /// Check if the movement direction does not kill the snake.
@@ -448,16 +1220,1896 @@ impl Game {
     /// # Returns
     /// * `bool` - Whether (true) or not (false) the snake survives the selected move.
     pub fn check_snake_alive(&self, direction: Option<Direction>) -> bool {
+        self.collision(direction).is_none()
+    }
+
+    /// Find what the snake runs into when moving in a direction.
+    /// # Arguments
+    /// * `direction: Option<Direction>` - The selected movement direction.
+    /// # Returns
+    /// * `Option<DeathCause>` - What kills the snake, or None if it survives the selected move.
+    pub fn collision(&self, direction: Option<Direction>) -> Option<DeathCause> {
         let destination = self.snake.next_head(direction);
-        !self.snake.overlap_tail(destination)
-            && !destination.out_of_bounds([0, self.width], [0, self.height])
+        if destination.out_of_bounds([0, self.width], [0, self.height]) {
+            Some(DeathCause::Border)
+        } else if self.snake.overlap_tail(destination) {
+            Some(DeathCause::Body)
+        } else if self.obstacles().contains(&destination) {
+            Some(DeathCause::Obstacle)
+        } else {
+            None
+        }
     }
 
-    pub fn game_over(&self) -> bool {
-        self.game_over
+    /// Get what ended the game.
+    /// # Returns
+    /// * `Option<DeathCause>` - What the Snake ran into, or None while the game is not over.
+    pub fn death_cause(&self) -> Option<DeathCause> {
+        self.death
     }
 
-    pub fn score(&self) -> i32 {
-        self.score
+    /// Get what an obstacle inside the border does to the Snake.
+    /// # Arguments
+    /// * `block: Block` - The block to check.
+    /// # Returns
+    /// * `Option<ObstacleKind>` - The kind of obstacle on the block, or None if there is none.
+    pub fn obstacle_kind(&self, block: Block) -> Option<ObstacleKind> {
+        if self.obstacles().contains(&block) {
+            Some(ObstacleKind::Wall)
+        } else if self.hazard_blocks.contains(&block) {
+            Some(ObstacleKind::Hazard {
+                damage_ticks: HAZARD_DAMAGE_TICKS,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Get everything besides the border and the Snake itself that blocks the way: the walls and closed doors.
+    pub fn obstacles(&self) -> Vec<Block> {
+        self.walls
+            .iter()
+            .copied()
+            .chain(
+                self.doors
+                    .iter()
+                    .filter(|door| !door.open)
+                    .map(|door| door.position),
+            )
+            .collect()
+    }
+
+    /// Reward every length milestone the Snake grew past since the last check, once per run, and announce the
+    /// highest one. Survival runs announce the milestones without scoring them.
+    fn check_length_milestones(&mut self) {
+        let reached = milestone(self.snake.body_len() as i32);
+        if reached <= self.length_milestone {
+            return;
+        }
+        if !self.config.survival {
+            let crossed = (reached - self.length_milestone) / LENGTH_MILESTONE;
+            self.add_score(crossed * MILESTONE_BONUS_POINTS, ScoreReason::Milestone);
+        }
+        self.length_milestone = reached;
+        self.milestone_banner = Some((reached, MILESTONE_BANNER_DURATION));
+    }
+
+    /// Open the door of a key the head moved onto. Unlike food, keys do not make the Snake grow nor score points.
+    pub fn check_keys(&mut self) {
+        let head = self.snake.head_position();
+        for door in &mut self.doors {
+            if !door.open && door.key == head {
+                door.open = true;
+                self.events.push(GameEvent::OpenedDoor);
+            }
+        }
+    }
+
+    /// Shrink the Snake when the head moved onto a hazard block.
+    /// # Returns
+    /// * `Option<DeathCause>` - The damage, if it left nothing but the head, or None if the Snake survives.
+    pub fn check_hazard_blocks(&mut self) -> Option<DeathCause> {
+        let head = self.snake.head_position();
+        let mut death = None;
+        if let Some(ObstacleKind::Hazard { damage_ticks }) = self.obstacle_kind(head) {
+            self.snake.shed_tail(damage_ticks as usize);
+            if self.snake.body_len() <= 1 {
+                death = Some(DeathCause::Damage);
+            }
+        }
+        self.hurting = self.touches_hazard_blocks();
+        death
+    }
+
+    /// Check whether the Snake is hurt by a hazard block it lies on, which frontends show by flashing it.
+    pub fn is_hurting(&self) -> bool {
+        self.hurting
+    }
+
+    /// Check whether any part of the Snake lies on a hazard block.
+    fn touches_hazard_blocks(&self) -> bool {
+        self.snake
+            .blocks()
+            .any(|block| self.hazard_blocks.contains(&block))
+    }
+
+    /// Check whether the final score beats any score on the scoreboard, once the game is over.
+    /// # Arguments
+    /// * `scores: &[Score]` - The reverse sorted slice of Score structs.
+    pub fn update_high_score(&mut self, scores: &[Score]) {
+        // Practice runs never make it onto the leaderboard. Practice mode is fixed for the whole run, as it only changes
+        // at a restart, so turning it off before dying does not count the run either.
+        if self.game_over && !self.high_score && !self.score_written && !self.config.practice {
+            self.high_score = check_score(self.rank_key(), scores).is_some();
+            if self.high_score {
+                self.events.push(GameEvent::NewHighScore);
+            }
+        }
+    }
+
+    /// Take all events that happened since the previous call.
+    pub fn drain_events(&mut self) -> Drain<'_, GameEvent> {
+        self.events.drain(..)
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Get the options the game was created with.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Switch to new options, keeping the current run going.
+    /// Only how the game looks and the accessibility options apply immediately, see `GameConfig::with_live_options`.
+    /// All other options, like the board size, the difficulty and the game modes, apply at the next restart.
+    /// # Arguments
+    /// * `config: GameConfig` - The new options.
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.config = self.config.with_live_options(&config);
+        self.theme = self.config.theme();
+        self.pending_config = Some(config);
+    }
+
+    /// Capture everything needed to continue the current run later.
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            config: self.config.clone(),
+            snake: self.snake.blocks().collect(),
+            direction: self.snake.head_direction(),
+            digesting: self
+                .snake
+                .digesting
+                .iter()
+                .map(|(block, moves)| (*block, *moves))
+                .collect(),
+            food: self.food,
+            food_kind: self.food_kind,
+            food_spawned_at: self.food_spawned_at,
+            food_moves: self.food_moves,
+            snake_moves: self.snake_moves,
+            base_score: self.base_score,
+            bonus_score: self.bonus_score,
+            breakdown: self.breakdown,
+            eat_streak: self.eat_streak,
+            max_eat_streak: self.max_eat_streak,
+            peak_length: self.peak_length,
+            foods_eaten: self.foods_eaten,
+            near_miss_count: self.near_miss_count,
+            star: self.star,
+            multiplied_foods: self.multiplied_foods,
+            multiplier_stacks: self.multiplier_stacks,
+            walls: self.walls.clone(),
+            doors: self.doors.clone(),
+        }
+    }
+
+    /// Continue a saved run, paused so the player can get ready.
+    /// # Arguments
+    /// * `snapshot: GameSnapshot` - The run to continue, including the options it was started with.
+    pub fn restore(&mut self, snapshot: GameSnapshot) {
+        self.set_config(snapshot.config.clone());
+        self.restart();
+        self.load_snapshot(snapshot);
+        self.paused = true;
+        self.recording = Recording::new(self.width, self.height, self.walls.clone());
+        self.record_frame();
+    }
+
+    /// Check whether there is a move to undo, which is only the case in practice mode.
+    pub fn can_undo(&self) -> bool {
+        self.config.practice && !self.undo_history.is_empty()
+    }
+
+    /// Take back the last move in practice mode, also the one that ended the game.
+    /// Buffered direction changes are dropped, as they would lead straight back into the undone move.
+    /// # Returns
+    /// * `bool` - Whether (true) or not (false) there was a move to undo.
+    pub fn undo(&mut self) -> bool {
+        if !self.config.practice {
+            return false;
+        }
+        let Some(snapshot) = self.undo_history.pop_back() else {
+            return false;
+        };
+        self.load_snapshot(snapshot);
+        self.game_over = false;
+        self.death = None;
+        self.direction_queue.clear();
+        self.waiting_time = Duration::ZERO;
+        true
+    }
+
+    /// Put the Snake, food, scores and walls back as they were in a snapshot, keeping the current options.
+    /// # Arguments
+    /// * `snapshot: GameSnapshot` - The state to go back to.
+    fn load_snapshot(&mut self, snapshot: GameSnapshot) {
+        // Random obstacles differ per start, so the saved ones replace the freshly placed ones.
+        self.walls = snapshot.walls;
+        self.doors = snapshot.doors;
+        self.snake = Snake::from_blocks(snapshot.snake, snapshot.direction);
+        self.snake.digesting = snapshot.digesting.into_iter().collect();
+        self.food = snapshot.food;
+        self.food_kind = snapshot.food_kind;
+        self.food_spawned_at = snapshot.food_spawned_at;
+        self.food_moves = snapshot.food_moves;
+        self.snake_moves = snapshot.snake_moves;
+        self.foods_eaten = snapshot.foods_eaten;
+        self.near_miss_count = snapshot.near_miss_count;
+        self.star = snapshot.star;
+        self.multiplied_foods = snapshot.multiplied_foods;
+        self.multiplier_stacks = snapshot.multiplier_stacks;
+        self.base_score = snapshot.base_score;
+        self.bonus_score = snapshot.bonus_score;
+        self.breakdown = snapshot.breakdown;
+        self.eat_streak = snapshot.eat_streak;
+        self.max_eat_streak = snapshot.max_eat_streak;
+        self.peak_length = snapshot.peak_length.max(self.snake.body_len() as i32);
+        self.length_milestone = milestone(self.peak_length);
+        self.hurting = self.touches_hazard_blocks();
+    }
+
+    /// Get the size of the playing field, inside the borders.
+    /// # Returns
+    /// * `(i32, i32)` - The width and height in blocks.
+    pub fn playable_area(&self) -> (i32, i32) {
+        (
+            self.width - 2 * BORDER_WIDTH,
+            self.height - 2 * BORDER_WIDTH,
+        )
+    }
+
+    /// Get the part of the playing field the Snake fills.
+    /// # Returns
+    /// * `f64` - The Snake length relative to the number of playable cells, from 0 to 1.
+    pub fn length_fraction(&self) -> f64 {
+        (self.snake.body_len() as f64 / self.playable_cell_count() as f64).min(1.0)
+    }
+
+    /// Get the number of cells of the playing field, inside the borders.
+    pub fn playable_cell_count(&self) -> i32 {
+        let (width, height) = self.playable_area();
+        width * height
+    }
+
+    /// Get the size of the current board in blocks, including the score strip.
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height + SCORE_BORDER_WIDTH)
+    }
+
+    /// Get what is shown on a cell of the board, for frontends that draw the game cell by cell.
+    /// # Arguments
+    /// * `block: Block` - The cell, anywhere on the board including the score strip.
+    /// # Returns
+    /// * `Cell` - The topmost thing on the cell.
+    pub fn cell(&self, block: Block) -> Cell {
+        let inside_field = block.x >= BORDER_WIDTH
+            && block.x < self.width - BORDER_WIDTH
+            && block.y >= BORDER_WIDTH
+            && block.y < self.height - BORDER_WIDTH;
+        if !inside_field {
+            return Cell::Border;
+        }
+        // Hiding everything far from the head, until the run is over.
+        if self.config.fog_of_war && !self.game_over && self.fog_darkness(block) >= 0.5 {
+            return Cell::Fog;
+        }
+        if self.walls.contains(&block) {
+            return Cell::Wall;
+        }
+        if let Some(door) = self.doors.iter().find(|door| door.position == block) {
+            return Cell::Door { open: door.open };
+        }
+        if self
+            .doors
+            .iter()
+            .any(|door| !door.open && door.key == block)
+        {
+            return Cell::Key;
+        }
+        if self.snake.head_position() == block {
+            return Cell::SnakeHead;
+        }
+        if self.snake.blocks().any(|body| body == block) {
+            return Cell::SnakeBody;
+        }
+        if self.star == Some(block) {
+            return Cell::Star;
+        }
+        if self.food == Some(block) {
+            return Cell::Food(self.food_kind);
+        }
+        if self.hazard_blocks.contains(&block) {
+            return Cell::HazardBlock;
+        }
+        let hazard_row = self
+            .hazard
+            .struck()
+            .or(self.hazard.warning().map(|(row, _)| row));
+        if hazard_row == Some(block.y) {
+            return Cell::Hazard;
+        }
+        Cell::Empty
+    }
+
+    /// Get the text of the score strip and any message on top of the playing field, for frontends without graphics.
+    /// # Arguments
+    /// * `locale: &Locale` - The strings of the language to write all text in.
+    /// # Returns
+    /// * `Vec<String>` - The score and speed, followed by the lines of the current message, if any.
+    pub fn hud_lines(&self, locale: &Locale) -> Vec<String> {
+        let mut lines = vec![self._score_text(locale), self._speed_text(locale)];
+        if self.hints {
+            lines.extend(locale.get("hints").lines().map(String::from));
+        } else if self.game_over {
+            let final_score = match self.high_score {
+                true => "final_score_high",
+                false => "final_score",
+            };
+            lines.push(locale.get("game_over").to_string());
+            lines.push(locale.format(final_score, &[("score", &self.score())]));
+            if self.entering_name() {
+                lines.push(locale.format("name", &[("name", &self.score_name)]));
+            } else {
+                lines.push(locale.get("play_again").to_string());
+            }
+        } else if self.paused {
+            lines.push(locale.get("paused").to_string());
+            lines.push(locale.get("resume").to_string());
+        }
+        lines
+    }
+
+    /// Switch the colors the game is drawn with, keeping the current run going.
+    /// # Arguments
+    /// * `palette: Palette` - The palette to draw with from now on.
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.config.palette = palette;
+        if let Some(pending) = &mut self.pending_config {
+            pending.palette = palette;
+        }
+        self.theme = self.config.theme();
+    }
+
+    /// Switch high contrast mode on or off, keeping the current run going.
+    /// # Arguments
+    /// * `high_contrast: bool` - Whether (true) or not (false) to draw larger elements with more contrast.
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.config.high_contrast = high_contrast;
+        if let Some(pending) = &mut self.pending_config {
+            pending.high_contrast = high_contrast;
+        }
+        self.theme = self.config.theme();
+    }
+
+    /// Get the factor the game is scaled up by when drawn.
+    pub fn render_scale(&self) -> f64 {
+        self.config.render_scale()
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Show the controls overlay until the next key press.
+    pub fn show_hints(&mut self) {
+        self.hints = true;
+    }
+
+    pub fn hints_shown(&self) -> bool {
+        self.hints
+    }
+
+    pub fn game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Get the total score, combining the base and bonus points.
+    pub fn score(&self) -> i32 {
+        self.base_score + self.bonus_score
+    }
+
+    /// Get the keys the final score is ranked by on the scoreboard.
+    pub fn rank_key(&self) -> RankKey {
+        (self.score(), self.base_score, self.max_eat_streak)
+    }
+
+    /// Check whether the current eating streak is long enough to earn combo bonus points.
+    pub fn combo_active(&self) -> bool {
+        self.eat_streak >= COMBO_STREAK
+    }
+
+    pub fn max_eat_streak(&self) -> u32 {
+        self.max_eat_streak
+    }
+
+    pub fn peak_length(&self) -> i32 {
+        self.peak_length
+    }
+
+    /// Get the current length of the Snake, including any blocks still stacked at the start.
+    pub fn snake_length(&self) -> i32 {
+        self.snake.body_len() as i32
+    }
+
+    /// Get the number of moves the Snake made this run, which goes up by one every tick.
+    pub fn snake_moves(&self) -> u32 {
+        self.snake_moves
+    }
+
+    /// Get the number of foods eaten during this run, of any kind. Unlike the score, every food counts once.
+    pub fn foods_eaten(&self) -> u32 {
+        self.foods_eaten
+    }
+
+    /// Get the time played this run, without the time spent paused.
+    pub fn play_time(&self) -> Duration {
+        self.play_time
+    }
+
+    pub fn fog_of_war(&self) -> bool {
+        self.config.fog_of_war
+    }
+
+    /// Check whether the score counts the moves survived instead of the food eaten.
+    /// The mode is fixed for the whole run, as it only changes at a restart, so the speed and the score file always
+    /// match how the run was scored.
+    pub fn survival(&self) -> bool {
+        self.config.survival
+    }
+
+    /// Get the distance from the head up to which everything is visible in fog of war mode. The radius pulses
+    /// slightly, unless motion is reduced, and briefly grows after eating.
+    /// # Returns
+    /// * `f64` - The radius, in blocks.
+    pub fn fog_radius(&self) -> f64 {
+        let pulse = if self.config.reduce_motion {
+            0.0
+        } else {
+            FOG_PULSE_AMPLITUDE * (TAU * self.play_time.as_secs_f64() / FOG_PULSE_PERIOD).sin()
+        };
+        FOG_RADIUS + pulse + FOG_REVEAL_RADIUS * self.fog_reveal / FOG_REVEAL_DURATION
+    }
+
+    /// Get how dark the fog of war is on a cell, fading in with the distance from the head.
+    /// # Arguments
+    /// * `block: Block` - The cell to cover.
+    /// # Returns
+    /// * `f64` - The opacity of the fog, from 0 (visible) to 1 (hidden).
+    pub fn fog_darkness(&self, block: Block) -> f64 {
+        let distance = food::get_distance(block, self.snake.head_position());
+        ((distance - self.fog_radius()) / FOG_FADE_WIDTH).clamp(0.0, 1.0)
+    }
+
+    /// Check whether no more food will spawn this run, because `max_foods_per_game` foods have been eaten.
+    pub fn food_supply_exhausted(&self) -> bool {
+        self.config
+            .max_foods_per_game
+            .is_some_and(|max_foods| self.foods_eaten >= max_foods)
+    }
+
+    pub fn base_score(&self) -> i32 {
+        self.base_score
+    }
+
+    pub fn bonus_score(&self) -> i32 {
+        self.bonus_score
+    }
+
+    pub fn breakdown(&self) -> ScoreBreakdown {
+        self.breakdown
+    }
+}
+
+/// Write the board as a grid of characters, see `Cell::symbol`, followed by a status line. Makes failing tests
+/// readable and allows comparing the board against expected snapshots.
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (width, height) = self.size();
+        for y in 0..height {
+            let row: String = (0..width)
+                .map(|x| self.cell(Block::new(x, y)).symbol())
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+        write!(
+            f,
+            "score: {}  speed: {}  tick: {}",
+            self.score(),
+            self.speed_level(),
+            self.snake_moves
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{MIN_HEIGHT, MIN_WIDTH};
+    use crate::food::{FOOD_DECAY_MOVES, FOOD_FRESH_VALUE};
+    use crate::levels::Level;
+    use crate::score::{parse_scores, scores_file_name};
+    use crate::storage::MemoryStorage;
+
+    fn custom_config() -> GameConfig {
+        GameConfig {
+            width: 15,
+            height: 12,
+            starting_length: Some(6),
+            starting_direction: Some(Direction::Down),
+            seed: Some(42),
+            ..GameConfig::default()
+        }
+    }
+
+    #[test]
+    fn restart_preserves_options() {
+        let mut game = Game::new(custom_config());
+        game.base_score = 7;
+        game.game_over = true;
+        game.restart();
+
+        assert_eq!(game.config, custom_config());
+        assert_eq!(game.snake.body_len(), 6);
+        assert!(game.snake.head_direction() == Direction::Down);
+        assert_eq!(game.score(), 0);
+        assert!(!game.game_over());
+    }
+
+    #[test]
+    fn snake_eats_adjacent_food_before_it_escapes() {
+        for seed in 0..20 {
+            let mut game = Game::new(GameConfig {
+                seed: Some(seed),
+                ..custom_config()
+            });
+            // The head is right next to the food, which has plenty of room to flee to the right.
+            game.snake = Snake::from_blocks(
+                [Block::new(4, 5), Block::new(3, 5), Block::new(2, 5)],
+                Direction::Right,
+            );
+            game.food = Some(Block::new(5, 5));
+            game.food_kind = FoodKind::Normal;
+            game.tick();
+
+            assert_eq!(game.score(), FOOD_FRESH_VALUE);
+            assert!(game.food.is_none());
+            assert!(game.snake.head_position() == Block::new(5, 5));
+            assert_eq!(
+                game.to_string(),
+                [
+                    "###############",
+                    "#             #",
+                    "#             #",
+                    "#             #",
+                    "#             #",
+                    "# oooO        #",
+                    "#             #",
+                    "#             #",
+                    "#             #",
+                    "#             #",
+                    "###############",
+                    "###############",
+                    "score: 3  speed: 1  tick: 1",
+                ]
+                .join("\n")
+            );
+        }
+    }
+
+    #[test]
+    fn snake_turns_on_non_square_boards() {
+        let mut game = Game::new(GameConfig {
+            width: 12,
+            height: 7,
+            escape_grace_moves: 100,
+            ..custom_config()
+        });
+        game.snake = Snake::from_blocks(
+            [Block::new(4, 2), Block::new(3, 2), Block::new(2, 2)],
+            Direction::Right,
+        );
+        game.food = Some(Block::new(9, 4));
+        game.food_kind = FoodKind::Normal;
+        game.tick();
+        game.steer(Direction::Down);
+        game.tick();
+        assert_eq!(
+            game.to_string(),
+            [
+                "############",
+                "#          #",
+                "#   oo     #",
+                "#    O     #",
+                "#        * #",
+                "############",
+                "############",
+                "score: 0  speed: 1  tick: 2",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn food_does_not_escape_during_grace_period() {
+        let grace = 5;
+        for seed in 0..200 {
+            let mut game = Game::new(GameConfig {
+                width: 30,
+                height: 11,
+                // A Snake this long on this board makes the food escape on every move.
+                starting_length: Some(60),
+                starting_direction: Some(Direction::Right),
+                escape_grace_moves: grace,
+                seed: Some(seed),
+                ..custom_config()
+            });
+            for _ in 0..2 {
+                let food = Block::new(20, 5);
+                game.food = Some(food);
+                for _ in 0..grace {
+                    game.tick();
+                    assert!(game.food == Some(food), "seed {seed}");
+                }
+                game.tick();
+                assert!(game.food != Some(food), "seed {seed}");
+                // The grace period starts over with every run.
+                game.restart();
+            }
+        }
+    }
+
+    #[test]
+    fn update_snake_reports_a_death() {
+        let mut game = Game::new(custom_config());
+        game.snake = Snake::from_blocks([Block::new(2, 1), Block::new(2, 2)], Direction::Up);
+        game.food = Some(Block::new(5, 5));
+        assert_eq!(game.death_cause(), None);
+        assert!(!game.update_snake());
+        assert!(game.game_over());
+        assert_eq!(game.death_cause(), Some(DeathCause::Border));
+        game.restart();
+        assert_eq!(game.death_cause(), None);
+    }
+
+    #[test]
+    fn update_food_reports_a_move() {
+        let mut game = Game::new(GameConfig {
+            width: 30,
+            height: 11,
+            // A Snake this long on this board makes the food escape on every move.
+            starting_length: Some(60),
+            escape_grace_moves: 0,
+            ..custom_config()
+        });
+        let food = Block::new(20, 5);
+        game.food = Some(food);
+        game.snake_moves = 1;
+        let moved = game.update_food();
+        assert!(moved.is_some() && moved == game.food && moved != Some(food));
+        assert_eq!(game.food_moves, 1);
+        // The whole Snake starts out on a single cell, from which the food flees one cell further away.
+        assert_eq!(
+            game.to_string(),
+            [
+                "##############################",
+                "#                            #",
+                "#                            #",
+                "# O                          #",
+                "#                            #",
+                "#                    *       #",
+                "#                            #",
+                "#                            #",
+                "#                            #",
+                "##############################",
+                "##############################",
+                "score: 0  speed: 1  tick: 1",
+            ]
+            .join("\n")
+        );
+
+        game.config.reduce_motion = true;
+        assert!(game.update_food().is_none());
+    }
+
+    #[test]
+    fn eating_reports_how_often_the_food_escaped() {
+        let mut game = Game::new(custom_config());
+        game.snake = Snake::from_blocks(
+            [Block::new(4, 5), Block::new(3, 5), Block::new(2, 5)],
+            Direction::Right,
+        );
+        game.food = Some(Block::new(5, 5));
+        game.food_moves = 3;
+        game.tick();
+        assert!(game
+            .drain_events()
+            .any(|event| event == GameEvent::AteFood { food_moves: 3 }));
+        assert_eq!(game.food_moves, 0);
+    }
+
+    #[test]
+    fn events_are_emitted_and_drained() {
+        let mut game = Game::new(custom_config());
+        game.snake = Snake::from_blocks(
+            [Block::new(4, 5), Block::new(3, 5), Block::new(2, 5)],
+            Direction::Right,
+        );
+        game.food = Some(Block::new(5, 5));
+        game.tick();
+        assert_eq!(
+            game.drain_events().collect::<Vec<_>>(),
+            [GameEvent::AteFood { food_moves: 0 }]
+        );
+
+        // Steer into the right border.
+        while !game.game_over() {
+            game.tick();
+        }
+        assert!(game.drain_events().any(|event| event == GameEvent::Died));
+        assert_eq!(game.drain_events().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn paused_game_does_not_move() {
+        let mut game = Game::new(custom_config());
+        let head = game.snake.head_position();
+        game.key_pressed(Key::P);
+        assert!(game.paused());
+        game.update(10.0);
+        assert!(game.snake.head_position() == head);
+        game.key_pressed(Key::P);
+        game.update(10.0);
+        assert!(game.snake.head_position() != head);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn hints_hold_the_game_until_any_key() {
+        let mut game = Game::new(custom_config());
+        let head = game.snake.head_position();
+        game.show_hints();
+        game.update(10.0);
+        assert!(game.snake.head_position() == head);
+        // The key dismissing the hints does not pause the game.
+        game.key_pressed(Key::P);
+        assert!(!game.hints_shown());
+        assert!(!game.paused());
+        game.update(10.0);
+        assert!(game.snake.head_position() != head);
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, feature = "gui"))]
+    fn debug_overlay_toggles() {
+        let mut game = Game::new(custom_config());
+        game.key_pressed(Key::F3);
+        assert!(game.debug_overlay);
+        game.key_pressed(Key::F3);
+        assert!(!game.debug_overlay);
+    }
+
+    #[test]
+    fn smooth_speed_lies_between_steps() {
+        let mut stepped = Game::new(custom_config());
+        let mut smooth = Game::new(GameConfig {
+            smooth_speed: true,
+            ..custom_config()
+        });
+
+        stepped.foods_eaten = 5;
+        let step_1 = stepped.moving_period();
+        stepped.foods_eaten = 10;
+        let step_2 = stepped.moving_period();
+        stepped.foods_eaten = 7;
+        assert_eq!(stepped.moving_period(), step_1);
+        smooth.foods_eaten = 7;
+        assert!(smooth.moving_period() < step_1 && smooth.moving_period() > step_2);
+
+        // Both modes agree on the speed at every step.
+        stepped.foods_eaten = 15;
+        smooth.foods_eaten = 15;
+        assert!(
+            stepped.moving_period().abs_diff(smooth.moving_period()) <= Duration::from_nanos(1)
+        );
+    }
+
+    #[test]
+    fn speed_stops_at_the_max_level() {
+        let mut game = Game::new(GameConfig {
+            max_speed_level: 3,
+            ..custom_config()
+        });
+        game.foods_eaten = 7;
+        assert!((game.speed_progress() - 0.4).abs() < 1e-12);
+        game.foods_eaten = 10;
+        assert!(game.at_max_speed());
+        let period = game.moving_period();
+        game.foods_eaten = 40;
+        assert_eq!(game.speed_level(), 3);
+        assert_eq!(game.moving_period(), period);
+        assert_eq!(game.speed_progress(), 1.0);
+        assert_eq!(game._speed_text(&Locale::default()), "SPEED: 3 (MAX)");
+
+        // Food still scores at the capped speed level.
+        let head = game.snake.head_position();
+        game.food = Some(Block::new(head.x, head.y + 1));
+        game.food_kind = FoodKind::Normal;
+        game.tick();
+        assert_eq!(game.score(), 3 * FOOD_FRESH_VALUE);
+        assert_eq!(game.moving_period(), period);
+    }
+
+    #[test]
+    fn speeding_up_is_announced_and_fades() {
+        let mut game = Game::new(custom_config());
+        game.foods_eaten = 3;
+        eat_in_a_row(&mut game, 1);
+        assert_eq!(game.speed_up_timer, 0.0);
+        eat_in_a_row(&mut game, 1);
+        assert_eq!(game.speed_level(), 2);
+        assert_eq!(game.speed_up_timer, SPEED_UP_DURATION);
+        game.update(1.0);
+        assert!((game.speed_up_timer - (SPEED_UP_DURATION - 1.0)).abs() < 1e-12);
+        game.update(1.0);
+        assert_eq!(game.speed_up_timer, 0.0);
+    }
+
+    #[test]
+    fn breakdown_adds_up_to_the_score_until_restarting() {
+        let mut game = Game::new(custom_config());
+        // Eating at the second speed level, with a combo, a bonus food and a star.
+        game.foods_eaten = 4;
+        game.multiplier_stacks = 1;
+        game.multiplied_foods = 1;
+        eat_in_a_row(&mut game, 2);
+        let head = game.snake.head_position();
+        game.food = Some(Block::new(head.x, head.y + 1));
+        game.food_kind = FoodKind::Bonus;
+        game.food_spawned_at = game.snake_moves;
+        game.tick();
+        let breakdown = game.breakdown();
+        assert_eq!(breakdown.food, 3 + 3 + 9);
+        assert_eq!(breakdown.speed, 3 + 9);
+        assert_eq!(breakdown.combo, COMBO_BONUS_POINTS);
+        assert_eq!(breakdown.multiplier, 3);
+        assert_eq!(breakdown.total(), game.score());
+        assert_eq!(game.score(), 3 + 3 + 3 + 18 + 1 + 3);
+
+        game.restart();
+        assert_eq!(game.breakdown(), ScoreBreakdown::default());
+    }
+
+    #[test]
+    fn penalties_never_take_the_score_below_zero() {
+        let mut game = Game::new(custom_config());
+        game.add_score(5, ScoreReason::Food(FoodKind::Normal));
+        game.add_score(-2, ScoreReason::Penalty);
+        assert_eq!(game.score(), 3);
+        assert_eq!(game.breakdown().floor, 0);
+
+        game.add_score(-8, ScoreReason::Penalty);
+        assert_eq!(game.score(), 0);
+        let breakdown = game.breakdown();
+        assert_eq!(breakdown.penalties, -10);
+        assert_eq!(breakdown.floor, 5);
+        assert_eq!(breakdown.total(), game.score());
+        let head = game.snake.head_position();
+        assert!(game
+            .penalty_popup
+            .is_some_and(|((block, points), _)| block == head && points == 8));
+        game.update(PENALTY_POPUP_DURATION);
+        assert!(game.penalty_popup.is_none());
+
+        // Points earned after hitting the floor count in full again.
+        game.add_score(4, ScoreReason::Combo);
+        assert_eq!(game.score(), 4);
+        assert_eq!(game.breakdown().total(), game.score());
+    }
+
+    #[test]
+    fn length_fraction_counts_the_playable_cells() {
+        let mut game = Game::new(custom_config());
+        // The 13x9 playing field holds 117 cells.
+        assert!((game.length_fraction() - 6.0 / 117.0).abs() < 1e-12);
+        game.snake = Snake::new(2, 2, Some(117), Some(Direction::Down));
+        assert_eq!(game.length_fraction(), 1.0);
+    }
+
+    #[test]
+    fn cells_show_the_topmost_thing_on_the_board() {
+        let mut game = Game::new(custom_config());
+        game.food = Some(Block::new(10, 3));
+        game.food_kind = FoodKind::Bonus;
+        game.star = Some(Block::new(10, 4));
+        let head = game.snake.head_position();
+        assert_eq!(game.cell(head), Cell::SnakeHead);
+        assert!(game
+            .snake
+            .blocks()
+            .filter(|body| *body != head)
+            .all(|body| game.cell(body) == Cell::SnakeBody));
+        assert_eq!(game.cell(Block::new(10, 3)), Cell::Food(FoodKind::Bonus));
+        assert_eq!(game.cell(Block::new(10, 4)), Cell::Star);
+        assert_eq!(game.cell(Block::new(0, 5)), Cell::Border);
+        // The score strip below the bottom border counts as border too.
+        let (width, height) = game.size();
+        assert_eq!(game.cell(Block::new(width / 2, height - 1)), Cell::Border);
+        assert_eq!(game.cell(Block::new(12, 9)), Cell::Empty);
+    }
+
+    #[test]
+    fn hud_lines_follow_the_state_of_the_game() {
+        let locale = Locale::default();
+        let mut game = Game::new(custom_config());
+        assert_eq!(
+            game.hud_lines(&locale),
+            vec![game._score_text(&locale), game._speed_text(&locale)]
+        );
+        game.toggle_pause();
+        assert_eq!(game.hud_lines(&locale)[2], locale.get("paused"));
+        game.toggle_pause();
+        game.game_over = true;
+        assert_eq!(game.hud_lines(&locale)[2], locale.get("game_over"));
+        assert_eq!(
+            game.hud_lines(&locale).last().unwrap(),
+            locale.get("play_again")
+        );
+    }
+
+    #[test]
+    fn speed_follows_the_configured_curve() {
+        let mut game = Game::new(GameConfig {
+            foods_per_speed_increase: 2,
+            speed_periods: Some(vec![0.4, 0.2]),
+            ..custom_config()
+        });
+        let factor = game.config.difficulty.period_factor();
+        assert_eq!(game.moving_period(), Duration::from_secs_f64(0.4 * factor));
+        game.foods_eaten = 2;
+        assert_eq!(game.speed_level(), 2);
+        assert_eq!(game.moving_period(), Duration::from_secs_f64(0.2 * factor));
+        game.foods_eaten = 8;
+        assert_eq!(game.moving_period(), Duration::from_secs_f64(0.2 * factor));
+    }
+
+    /// Eat a fresh normal food on each of the next moves.
+    fn eat_in_a_row(game: &mut Game, foods: u32) {
+        for _ in 0..foods {
+            let head = game.snake.head_position();
+            game.food = Some(Block::new(head.x, head.y + 1));
+            game.food_kind = FoodKind::Normal;
+            game.food_spawned_at = game.snake_moves;
+            game.tick();
+        }
+    }
+
+    #[test]
+    fn stars_multiply_the_points_of_the_next_foods() {
+        let mut plain = Game::new(custom_config());
+        let mut starred = Game::new(custom_config());
+        for game in [&mut plain, &mut starred] {
+            let head = game.snake.head_position();
+            game.star = Some(Block::new(head.x, head.y + 1));
+            game.food = Some(Block::new(head.x + 5, head.y + 1));
+        }
+        plain.star = None;
+        plain.tick();
+        starred.tick();
+        assert!(starred.star.is_none());
+        assert_eq!(starred.score_multiplier(), 2);
+
+        // Three fresh foods, the last of which earns the combo bonus.
+        eat_in_a_row(&mut plain, 3);
+        eat_in_a_row(&mut starred, 3);
+        assert_eq!(plain.score(), 3 + 3 + 4);
+        assert_eq!(starred.score(), 2 * (3 + 3 + 4));
+        for game in [&plain, &starred] {
+            assert_eq!(game.breakdown().food, 9);
+            assert_eq!(game.breakdown().combo, COMBO_BONUS_POINTS);
+            assert_eq!(game.breakdown().total(), game.score());
+        }
+        assert_eq!(starred.breakdown().multiplier, 10);
+        assert_eq!(
+            starred._score_text(&Locale::default()),
+            "SCORE: 20 x2 (7 left)"
+        );
+
+        // A second star stacks and refreshes the multiplier, a third one no longer does.
+        starred.snake = Snake::from_blocks([Block::new(5, 2), Block::new(5, 1)], Direction::Down);
+        for left in [MULTIPLIED_FOODS, MULTIPLIED_FOODS - 1] {
+            let head = starred.snake.head_position();
+            starred.star = Some(Block::new(head.x, head.y + 1));
+            starred.tick();
+            assert_eq!(starred.score_multiplier(), 4);
+            assert_eq!(starred.multiplied_foods, left);
+            eat_in_a_row(&mut starred, 1);
+        }
+        // The multiplier ends at game over.
+        while !starred.game_over() {
+            starred.tick();
+        }
+        assert_eq!(starred.score_multiplier(), 1);
+        starred.restart();
+        assert_eq!(starred.score_multiplier(), 1);
+    }
+
+    #[test]
+    fn multiplied_foods_run_out() {
+        let mut game = Game::new(custom_config());
+        game.multiplier_stacks = 1;
+        game.multiplied_foods = 1;
+        eat_in_a_row(&mut game, 2);
+        assert_eq!(game.score(), 2 * 3 + 3);
+        assert_eq!(game.score_multiplier(), 1);
+        assert_eq!(game._score_text(&Locale::default()), "SCORE: 9");
+    }
+
+    #[test]
+    fn bonus_food_counts_separately() {
+        let mut game = Game::new(custom_config());
+        for kind in [FoodKind::Normal, FoodKind::Bonus, FoodKind::Normal] {
+            let head = game.snake.head_position();
+            game.food = Some(Block::new(head.x, head.y + 1));
+            game.food_kind = kind;
+            game.tick();
+            // Moving without eating in between, to not earn any combo bonus.
+            game.food = Some(Block::new(1, 1));
+            game.tick();
+        }
+        assert_eq!(game.foods_eaten(), 3);
+        assert_eq!(game.base_score(), 2 * FOOD_FRESH_VALUE);
+        assert_eq!(
+            game.bonus_score(),
+            FoodKind::Bonus.points() * FOOD_FRESH_VALUE
+        );
+        assert_eq!(
+            game.score(),
+            (2 + FoodKind::Bonus.points()) * FOOD_FRESH_VALUE
+        );
+    }
+
+    #[test]
+    fn food_value_decays_while_uneaten() {
+        let mut game = Game::new(custom_config());
+        for (age, value) in [
+            (FOOD_DECAY_MOVES - 1, 3),
+            (FOOD_DECAY_MOVES, 2),
+            (2 * FOOD_DECAY_MOVES - 1, 2),
+            (2 * FOOD_DECAY_MOVES, 1),
+            (10 * FOOD_DECAY_MOVES, 1),
+        ] {
+            game.restart();
+            game.snake_moves = 200;
+            let head = game.snake.head_position();
+            game.food = Some(Block::new(head.x, head.y + 1));
+            game.food_kind = FoodKind::Normal;
+            // Counting the age at the move that eats the food.
+            game.food_spawned_at = game.snake_moves + 1 - age;
+            game.tick();
+            assert_eq!(game.score(), value);
+        }
+    }
+
+    #[test]
+    fn food_points_scale_with_the_speed_level() {
+        let mut game = Game::new(custom_config());
+        for (foods_eaten, speed_level) in [(0, 1), (10, 3), (20, 5)] {
+            game.foods_eaten = foods_eaten;
+            assert_eq!(game.speed_level(), speed_level);
+            let head = game.snake.head_position();
+            game.food = Some(Block::new(head.x, head.y + 1));
+            game.food_kind = FoodKind::Normal;
+            let score = game.score();
+            game.tick();
+            assert_eq!(game.score() - score, FOOD_FRESH_VALUE * speed_level);
+            // Moving without eating in between, to not earn any combo bonus.
+            game.food = Some(Block::new(1, 1));
+            game.tick();
+        }
+    }
+
+    #[test]
+    fn food_does_not_move_with_reduced_motion() {
+        for seed in 0..200 {
+            let mut game = Game::new(GameConfig {
+                width: 30,
+                height: 11,
+                starting_length: Some(60),
+                starting_direction: Some(Direction::Right),
+                escape_grace_moves: 0,
+                reduce_motion: true,
+                seed: Some(seed),
+                ..custom_config()
+            });
+            game.snake_moves = 1;
+            let food = Block::new(20, 5);
+            game.food = Some(food);
+            for _ in 0..500 {
+                assert!(game.update_food().is_none());
+                assert!(game.food == Some(food));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn speed_up_does_not_fade_with_reduced_motion() {
+        let mut game = Game::new(custom_config());
+        let opaque = game.theme.max_speed[3];
+        game.speed_up_timer = SPEED_UP_DURATION / 2.0;
+        assert!(game._speed_up_alpha() < opaque);
+        game.config.reduce_motion = true;
+        for timer in [SPEED_UP_DURATION, SPEED_UP_DURATION / 2.0, 0.01] {
+            game.speed_up_timer = timer;
+            assert_eq!(game._speed_up_alpha(), opaque);
+        }
+    }
+
+    #[test]
+    fn eat_streak_tracks_consecutive_foods() {
+        let mut game = Game::new(custom_config());
+        let eat_next = |game: &mut Game| {
+            let head = game.snake.head_position();
+            game.food = Some(Block::new(head.x, head.y + 1));
+            game.food_kind = FoodKind::Normal;
+            game.tick();
+        };
+        eat_next(&mut game);
+        eat_next(&mut game);
+        assert_eq!(game.eat_streak, 2);
+        assert!(!game.combo_active());
+        eat_next(&mut game);
+        assert!(game.combo_active());
+        assert_eq!(game.base_score(), 3 * FOOD_FRESH_VALUE);
+        assert_eq!(game.bonus_score(), COMBO_BONUS_POINTS);
+
+        // Moving without eating breaks the streak, but not the maximum.
+        game.food = Some(Block::new(1, 1));
+        game.tick();
+        assert_eq!(game.eat_streak, 0);
+        assert_eq!(game.max_eat_streak(), 3);
+        eat_next(&mut game);
+        assert_eq!(game.max_eat_streak(), 3);
+    }
+
+    #[test]
+    fn food_spawns_next_to_the_snake_when_nothing_else_is_free() {
+        let mut game = Game::new(GameConfig {
+            width: MIN_WIDTH,
+            height: MIN_HEIGHT,
+            starting_length: Some(1),
+            ..custom_config()
+        });
+        // The playing field is 3 by 4 blocks, split by a Snake down its middle column.
+        game.snake = Snake::from_blocks((1..5).map(|y| Block::new(2, y)), Direction::Up);
+        game.add_food();
+        assert!(game.food.is_some());
+    }
+
+    #[test]
+    fn food_spawns_away_from_the_head() {
+        let mut game = Game::new(GameConfig {
+            food_spawn_min_distance: 5,
+            ..custom_config()
+        });
+        let head = game.snake.head_position();
+        for _ in 0..50 {
+            game.add_food();
+            assert!(food::get_distance(game.food.unwrap(), head) >= 5.0);
+        }
+        // No block is that far away, so the food spawns anywhere rather than not at all.
+        game.config.food_spawn_min_distance = 100;
+        game.add_food();
+        assert!(game.food.is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "the board is 5x5 but should be at least 5x7")]
+    fn games_on_tiny_boards_panic() {
+        Game::new(GameConfig {
+            width: 5,
+            height: 5,
+            ..custom_config()
+        });
+    }
+
+    #[test]
+    fn food_in_line_of_sight_only_straight_ahead() {
+        let snake = Snake::from_blocks(
+            [Block::new(4, 5), Block::new(3, 5), Block::new(2, 5)],
+            Direction::Right,
+        );
+        assert!(food_is_in_line_of_sight(&snake, Block::new(9, 5), &[]));
+        assert!(!food_is_in_line_of_sight(
+            &snake,
+            Block::new(9, 5),
+            &[Block::new(7, 5)]
+        ));
+        // Behind the head or off to the side.
+        assert!(!food_is_in_line_of_sight(&snake, Block::new(1, 5), &[]));
+        assert!(!food_is_in_line_of_sight(&snake, Block::new(9, 6), &[]));
+    }
+
+    #[test]
+    fn gameplay_options_change_at_restart() {
+        let mut game = Game::new(custom_config());
+        game.set_config(GameConfig {
+            width: 25,
+            height: 30,
+            difficulty: Difficulty::Hard,
+            fog_of_war: true,
+            reduce_motion: true,
+            palette: Palette::Colorblind,
+            ..custom_config()
+        });
+        // Only the look and the accessibility options apply to the running game.
+        assert_eq!(game.size(), (15, 12));
+        assert_eq!(game.config.difficulty, Difficulty::Normal);
+        assert!(!game.config.fog_of_war);
+        assert!(game.config.reduce_motion);
+        assert_eq!(game.config.palette, Palette::Colorblind);
+        game.restart();
+        assert_eq!(game.size(), (25, 30));
+        assert_eq!(game.config.difficulty, Difficulty::Hard);
+        assert!(game.config.fog_of_war);
+    }
+
+    #[test]
+    fn restart_places_food_off_the_snake() {
+        let mut game = Game::new(custom_config());
+        for _ in 0..50 {
+            game.restart();
+            let food = game.food.unwrap();
+            assert!(!game.snake.overlap_tail(food));
+            assert!(!game.snake.blocks().any(|segment| segment.is_adjacent(food)));
+            assert!(!food.out_of_bounds([0, game.width], [0, game.height]));
+        }
+    }
+
+    #[test]
+    fn restart_places_the_configured_initial_food() {
+        let food = Block::new(10, 3);
+        let mut game = Game::new(GameConfig {
+            initial_food: Some(food),
+            ..custom_config()
+        });
+        for _ in 0..3 {
+            assert_eq!(game.food, Some(food));
+            assert_eq!(game.food_kind, FoodKind::Normal);
+            game.restart();
+        }
+        // Food on the Snake is placed randomly instead.
+        let head = game.snake.head_position();
+        let mut game = Game::new(GameConfig {
+            initial_food: Some(head),
+            ..custom_config()
+        });
+        assert!(game.food.is_some_and(|food| !game.snake.overlap_tail(food)));
+        assert!(game.food != Some(head));
+        game.restart();
+        assert!(game.food != Some(head));
+    }
+
+    #[test]
+    fn names_are_edited_at_the_cursor() {
+        let mut game = Game::new(custom_config());
+        game.game_over = true;
+        game.high_score = true;
+        "BD".chars().for_each(|letter| game.type_letter(letter));
+        game.move_cursor(Direction::Left);
+        game.type_letter('c');
+        assert_eq!(game.score_name, "BCD");
+        assert_eq!(game.name_cursor(), Some(2));
+        // Deleting forward at the cursor, and backward before it.
+        game.delete_letter();
+        assert_eq!(game.score_name, "BC");
+        game.delete_letter();
+        assert_eq!(game.score_name, "BC");
+        game.move_cursor(Direction::Left);
+        game.erase_letter();
+        assert_eq!(game.score_name, "C");
+        assert_eq!(game.name_cursor(), Some(0));
+        game.erase_letter();
+        game.move_cursor(Direction::Left);
+        game.move_cursor(Direction::Right);
+        game.move_cursor(Direction::Right);
+        assert_eq!(game.name_cursor(), Some(1));
+
+        // The cursor blinks while waiting for input.
+        game.update(CURSOR_BLINK_PERIOD * 1.5);
+        assert_eq!(game.name_cursor(), None);
+        game.update(CURSOR_BLINK_PERIOD);
+        assert_eq!(game.name_cursor(), Some(1));
+    }
+
+    #[test]
+    fn every_tick_is_recorded() {
+        let mut game = Game::new(custom_config());
+        assert_eq!(game.recording().len(), 1);
+        game.tick();
+        game.tick();
+        assert_eq!(game.recording().len(), 3);
+        game.restart();
+        assert_eq!(game.recording().len(), 1);
+    }
+
+    #[test]
+    fn restoring_a_snapshot_continues_the_run() {
+        let mut game = Game::new(custom_config());
+        for _ in 0..3 {
+            game.tick();
+        }
+        game.base_score = 8;
+        game.max_eat_streak = 2;
+        let snapshot = game.snapshot();
+        let data = serde_json::to_string(&snapshot).unwrap();
+
+        let mut restored = Game::new(GameConfig::default());
+        restored.restore(serde_json::from_str(&data).unwrap());
+        assert!(restored.snapshot() == snapshot);
+        assert_eq!(restored.size(), game.size());
+        assert!(restored.paused());
+    }
+
+    #[test]
+    fn peak_length_tracks_the_longest_snake() {
+        let mut game = Game::new(custom_config());
+        assert_eq!(game.peak_length(), 6);
+        let head = game.snake.head_position();
+        game.food = Some(Block::new(head.x, head.y + 1));
+        game.update_snake();
+        assert_eq!(game.peak_length(), 7);
+        game.restart();
+        assert_eq!(game.peak_length(), 6);
+    }
+
+    #[test]
+    fn food_stops_spawning_after_the_limit() {
+        let mut game = Game::new(GameConfig {
+            max_foods_per_game: Some(1),
+            ..custom_config()
+        });
+        assert!(game.food.is_some());
+        let head = game.snake.head_position();
+        game.food = Some(Block::new(head.x, head.y + 1));
+        game.update_snake();
+        assert!(game.food_supply_exhausted());
+        game.tick();
+        game.update(0.0);
+        assert!(game.food.is_none());
+        // A new run has a full food supply again.
+        game.restart();
+        assert!(!game.food_supply_exhausted());
+        assert!(game.food.is_some());
+    }
+
+    #[test]
+    fn level_walls_are_deadly_and_food_free() {
+        let mut game = Game::new(GameConfig {
+            level: Level::Rooms,
+            ..custom_config()
+        });
+        assert!(!game.walls.is_empty());
+        assert!(!game.walls.contains(&game.food.unwrap()));
+        let wall = game.walls[0];
+        game.snake = Snake::from_blocks(
+            [
+                Block::new(wall.x - 1, wall.y),
+                Block::new(wall.x - 2, wall.y),
+            ],
+            Direction::Right,
+        );
+        assert!(!game.update_snake());
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn buffered_directions_are_taken_oldest_first() {
+        let mut game = Game::new(GameConfig {
+            starting_direction: Some(Direction::Right),
+            ..custom_config()
+        });
+        game.key_pressed(Key::Down);
+        game.key_pressed(Key::Left);
+        // Turning around relative to the buffered direction is not possible either.
+        game.key_pressed(Key::Right);
+        game.key_pressed(Key::A);
+        assert_eq!(game.direction_queue, [Direction::Down, Direction::Left]);
+
+        let head = game.snake.head_position();
+        game.update_snake();
+        assert!(game.snake.head_position() == Block::new(head.x, head.y + 1));
+        game.update_snake();
+        assert!(game.snake.head_position() == Block::new(head.x - 1, head.y + 1));
+        assert!(game.direction_queue.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn long_frames_catch_up_on_buffered_moves() {
+        let mut game = Game::new(GameConfig {
+            starting_direction: Some(Direction::Right),
+            ..custom_config()
+        });
+        game.key_pressed(Key::Down);
+        game.key_pressed(Key::Right);
+        game.update(2.5 * game.moving_period().as_secs_f64());
+        assert_eq!(game.snake_moves, 2);
+        // Without buffered moves, a long frame moves the Snake only once.
+        game.update(2.5 * game.moving_period().as_secs_f64());
+        assert_eq!(game.snake_moves, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn close_calls_count_moves_next_to_the_body() {
+        let mut game = Game::new(custom_config());
+        for _ in 0..3 {
+            game.tick();
+        }
+        assert_eq!(game.near_miss_count, 0);
+
+        // Turning back along the body, right next to it.
+        game.snake = Snake::from_blocks(
+            [
+                Block::new(6, 4),
+                Block::new(6, 5),
+                Block::new(5, 5),
+                Block::new(4, 5),
+                Block::new(4, 4),
+                Block::new(4, 3),
+                Block::new(4, 2),
+            ],
+            Direction::Up,
+        );
+        game.food = Some(Block::new(10, 8));
+        game.key_pressed(Key::Left);
+        game.tick();
+        assert!(!game.game_over());
+        assert_eq!(game.near_miss_count, 1);
+    }
+
+    #[test]
+    fn occupied_neighbors_count_borders_walls_and_body() {
+        let mut game = Game::new(custom_config());
+        game.snake = Snake::from_blocks(
+            [Block::new(10, 8), Block::new(10, 9), Block::new(9, 9)],
+            Direction::Up,
+        );
+        assert_eq!(game.occupied_neighbors(Block::new(4, 4)), 0);
+        assert_eq!(game.occupied_neighbors(Block::new(1, 4)), 1);
+        assert_eq!(game.occupied_neighbors(Block::new(1, 1)), 2);
+        // The head and the segment behind it do not count, unlike the rest of the body.
+        assert_eq!(game.occupied_neighbors(Block::new(11, 8)), 0);
+        assert_eq!(game.occupied_neighbors(Block::new(9, 8)), 1);
+        game.walls = vec![
+            Block::new(5, 4),
+            Block::new(4, 5),
+            Block::new(6, 5),
+            Block::new(5, 6),
+        ];
+        assert_eq!(game.occupied_neighbors(Block::new(5, 5)), 4);
+    }
+
+    #[test]
+    fn eating_in_a_corner_earns_the_risky_bonus() {
+        let mut game = Game::new(custom_config());
+        game.snake = Snake::from_blocks(
+            [Block::new(2, 1), Block::new(3, 1), Block::new(4, 1)],
+            Direction::Left,
+        );
+        game.food = Some(Block::new(1, 1));
+        game.food_kind = FoodKind::Normal;
+        game.tick();
+        assert_eq!(game.bonus_score(), RISKY_BONUS_POINTS);
+        assert_eq!(game.breakdown().risky, RISKY_BONUS_POINTS);
+        assert_eq!(game.breakdown().total(), game.score());
+        assert!(game
+            .risky_popup
+            .is_some_and(|(block, _)| block == Block::new(1, 1)));
+        game.update(RISKY_POPUP_DURATION);
+        assert!(game.risky_popup.is_none());
+    }
+
+    #[test]
+    fn survival_mode_scores_moves_instead_of_food() {
+        let mut game = Game::new(GameConfig {
+            survival: true,
+            ..custom_config()
+        });
+        let head = game.snake.head_position();
+        game.food = Some(Block::new(head.x, head.y + 1));
+        game.tick();
+        game.tick();
+        assert_eq!(game.foods_eaten, 1);
+        assert_eq!(game.score(), 2);
+        assert_eq!(game.breakdown().survival, 2);
+        assert_eq!(game.breakdown().total(), game.score());
+        assert_eq!(game.speed_level(), 1);
+
+        // The game speeds up with the moves survived rather than the food eaten.
+        game.snake_moves = SURVIVAL_MOVES_PER_SPEED_INCREASE - 1;
+        game.foods_eaten = 0;
+        game.drain_events().for_each(drop);
+        game.tick();
+        assert_eq!(game.speed_level(), 2);
+        assert!(game.drain_events().any(|event| event == GameEvent::SpeedUp));
+    }
+
+    #[test]
+    fn length_milestones_are_rewarded_once() {
+        let mut game = Game::new(custom_config());
+        assert_eq!(game.length_milestone, 0);
+        let snake_of = |length: i32| {
+            Snake::from_blocks(
+                (0..length).map(|i| Block::new(1 + i % 13, 1 + i / 13)),
+                Direction::Right,
+            )
+        };
+        game.snake = snake_of(9);
+        game.check_length_milestones();
+        assert_eq!(game.bonus_score(), 0);
+        // Reaching a milestone exactly.
+        game.snake = snake_of(10);
+        game.check_length_milestones();
+        assert_eq!(game.bonus_score(), MILESTONE_BONUS_POINTS);
+        assert!(game
+            .milestone_banner
+            .is_some_and(|(length, _)| length == 10));
+        game.check_length_milestones();
+        assert_eq!(game.bonus_score(), MILESTONE_BONUS_POINTS);
+        // Growing past two milestones at once rewards both.
+        game.snake = snake_of(31);
+        game.check_length_milestones();
+        assert_eq!(game.bonus_score(), 3 * MILESTONE_BONUS_POINTS);
+        assert_eq!(game.breakdown().milestones, 3 * MILESTONE_BONUS_POINTS);
+        assert!(game
+            .milestone_banner
+            .is_some_and(|(length, _)| length == 30));
+        game.update(MILESTONE_BANNER_DURATION);
+        assert!(game.milestone_banner.is_none());
+
+        game.restart();
+        assert_eq!(game.length_milestone, 0);
+    }
+
+    #[test]
+    fn saved_scores_break_ties_on_the_eating_streak() {
+        let mut storage = MemoryStorage::default();
+        let name = scores_file_name((custom_config().width, custom_config().height), false);
+        let mut scores = parse_scores(&storage, &name);
+        let played = |streak| {
+            let mut game = Game::new(custom_config());
+            game.base_score = 10;
+            game.max_eat_streak = streak;
+            game.game_over = true;
+            game
+        };
+        write_score(&mut scores, "SHORT", &played(1), &mut storage);
+        // An equal score with a longer streak ranks above the saved one, one with the same streak below it.
+        let longer = played(3);
+        assert_eq!(check_score(longer.rank_key(), &scores), Some(0));
+        write_score(&mut scores, "LONG", &longer, &mut storage);
+        let same = played(1);
+        assert_eq!(check_score(same.rank_key(), &scores), Some(2));
+        write_score(&mut scores, "SAME", &same, &mut storage);
+        let players: Vec<String> = parse_scores(&storage, &name)
+            .iter()
+            .take(3)
+            .map(|score| score.player().to_string())
+            .collect();
+        assert_eq!(players, ["LONG", "SHORT", "SAME"]);
+    }
+
+    #[test]
+    fn survival_mode_only_changes_at_the_restart() {
+        let mut game = Game::new(custom_config());
+        game.foods_eaten = 4;
+        game.snake_moves = 200;
+        let speed_level = game.speed_level();
+        game.set_config(GameConfig {
+            survival: true,
+            ..custom_config()
+        });
+        // The food scored run keeps its speed and is saved with the other food scored runs.
+        assert!(!game.survival());
+        assert_eq!(game.speed_level(), speed_level);
+        let mut storage = MemoryStorage::default();
+        let mut scores = parse_scores(&storage, "scores.json");
+        game.base_score = 5;
+        game.game_over = true;
+        write_score(&mut scores, "FOOD", &game, &mut storage);
+        let food_scores = scores_file_name(game.size(), false);
+        assert_eq!(parse_scores(&storage, &food_scores)[0].player(), "FOOD");
+        assert!(storage.load(&scores_file_name(game.size(), true)).is_none());
+        game.restart();
+        assert!(game.survival());
+    }
+
+    #[test]
+    fn practice_runs_stay_practice_runs_until_the_restart() {
+        let mut game = Game::new(GameConfig {
+            practice: true,
+            ..custom_config()
+        });
+        let scores = parse_scores(&MemoryStorage::default(), "scores.json");
+        // Turning practice mode off in the middle of the run, before dying with a score worth saving.
+        game.paused = true;
+        game.set_config(custom_config());
+        game.base_score = 50;
+        game.game_over = true;
+        game.update_high_score(&scores);
+        assert!(!game.high_score);
+        game.restart();
+        assert!(!game.config().practice);
+        game.base_score = 50;
+        game.game_over = true;
+        game.update_high_score(&scores);
+        assert!(game.high_score);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn practice_mode_undoes_the_fatal_move() {
+        let mut game = Game::new(GameConfig {
+            practice: true,
+            ..custom_config()
+        });
+        while !game.game_over() {
+            game.tick();
+        }
+        game.update_high_score(&[]);
+        assert!(!game.high_score);
+        let fatal = game.snapshot();
+        game.key_pressed(Key::U);
+        assert!(!game.game_over());
+        assert!(game.snapshot() == fatal);
+        // A single move can be undone by default, after which the Snake can steer clear.
+        assert!(!game.can_undo());
+        game.key_pressed(Key::Right);
+        game.tick();
+        assert!(!game.game_over());
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn undo_needs_practice_mode() {
+        let mut game = Game::new(custom_config());
+        game.tick();
+        let snapshot = game.snapshot();
+        game.key_pressed(Key::U);
+        assert!(!game.can_undo());
+        assert!(game.snapshot() == snapshot);
+    }
+
+    #[test]
+    fn arcade_mode_places_seeded_obstacles() {
+        let config = GameConfig {
+            arcade: true,
+            ..custom_config()
+        };
+        let game = Game::new(config.clone());
+        assert!(!game.walls.is_empty());
+        assert!(game.walls == Game::new(config).walls);
+        // The first moves are always possible.
+        let mut game = game;
+        for _ in 0..OBSTACLE_FREE_RUNWAY {
+            assert!(game.update_snake());
+        }
+    }
+
+    #[test]
+    fn arcade_obstacles_never_touch_the_food() {
+        let layout = Layout {
+            walls: Vec::new(),
+            start: Block::new(4, 4),
+            food: vec![Block::new(10, 8)],
+            doors: Vec::new(),
+            hazards: Vec::new(),
+        };
+        for seed in 0..200 {
+            let mut game = Game::new(GameConfig {
+                arcade: true,
+                seed: Some(seed),
+                ..custom_config()
+            });
+            let obstacles = game.arcade_obstacles(&layout);
+            assert!(!obstacles.is_empty());
+            assert!(!obstacles
+                .iter()
+                .any(|block| *block == layout.food[0] || block.is_adjacent(layout.food[0])));
+        }
+    }
+
+    #[test]
+    fn reset_to_level_places_everything_as_laid_out() {
+        let mut game = Game::new(custom_config());
+        game.base_score = 5;
+        let layout = Layout {
+            walls: vec![Block::new(7, 7)],
+            start: Block::new(4, 4),
+            food: vec![Block::new(9, 3), Block::new(9, 4)],
+            doors: Vec::new(),
+            hazards: Vec::new(),
+        };
+        game.reset_to_level(&layout);
+        assert!(game.walls == layout.walls);
+        assert!(game.food == Some(Block::new(9, 3)));
+        assert!(game.snake.head_position() == Block::new(4, 5));
+        assert_eq!(game.score(), 0);
+    }
+
+    #[test]
+    fn fog_of_war_clears_around_the_head() {
+        let mut game = Game::new(GameConfig {
+            width: 30,
+            height: 30,
+            fog_of_war: true,
+            reduce_motion: true,
+            ..custom_config()
+        });
+        let head = game.snake.head_position();
+        assert_eq!(game.fog_darkness(head), 0.0);
+        assert_eq!(game.fog_darkness(Block::new(head.x + 6, head.y)), 0.0);
+        assert_eq!(game.fog_darkness(Block::new(head.x + 8, head.y)), 1.0);
+        // Eating widens the view for a moment.
+        game.food = Some(Block::new(head.x, head.y + 1));
+        game.tick();
+        let head = game.snake.head_position();
+        assert!(game.fog_darkness(Block::new(head.x + 8, head.y)) < 1.0);
+        game.update(FOG_REVEAL_DURATION);
+        assert!(game.fog_radius() <= FOG_RADIUS);
+    }
+
+    #[test]
+    fn playable_area_excludes_the_borders() {
+        let game = Game::new(custom_config());
+        assert_eq!(game.playable_area(), (13, 9));
+        assert_eq!(game.playable_cell_count(), 117);
+        let cells = (0..game.width)
+            .flat_map(|x| (0..game.height).map(move |y| Block::new(x, y)))
+            .filter(|block| !block.out_of_bounds([0, game.width], [0, game.height]))
+            .count();
+        assert_eq!(cells as i32, game.playable_cell_count());
+    }
+
+    #[test]
+    fn snake_blinks_while_playing() {
+        let mut game = Game::new(custom_config());
+        game.update_blink(EYE_BLINK_INTERVAL);
+        assert!(game.eyes_closed);
+        game.update_blink(EYE_BLINK_DURATION);
+        assert!(!game.eyes_closed);
+        // The blink cycle stops with the game.
+        game.paused = true;
+        game.update(EYE_BLINK_INTERVAL);
+        assert!(!game.eyes_closed);
+    }
+
+    #[test]
+    fn keys_open_their_doors() {
+        let mut game = Game::new(custom_config());
+        game.snake = Snake::from_blocks([Block::new(5, 5), Block::new(5, 4)], Direction::Down);
+        game.doors = vec![Door {
+            position: Block::new(5, 7),
+            key: Block::new(5, 6),
+            open: false,
+        }];
+        // Moving down onto the key, right in front of the closed door.
+        assert!(game.check_snake_alive(Some(Direction::Down)));
+        game.snake.move_forward(Some(Direction::Down));
+        assert!(!game.check_snake_alive(Some(Direction::Down)));
+        assert_eq!(
+            game.collision(Some(Direction::Down)),
+            Some(DeathCause::Obstacle)
+        );
+        let length = game.snake.body_len();
+        game.check_keys();
+        assert!(game.doors[0].open);
+        assert!(game.check_snake_alive(Some(Direction::Down)));
+        // Keys neither make the Snake grow nor score points.
+        assert_eq!(game.snake.body_len(), length);
+        assert_eq!(game.score(), 0);
+    }
+
+    #[test]
+    fn hazard_blocks_shrink_the_snake_until_it_dies() {
+        let mut game = Game::new(custom_config());
+        game.food = Some(Block::new(10, 10));
+        game.hazard_blocks = vec![Block::new(5, 6)];
+        game.snake = Snake::from_blocks(
+            [
+                Block::new(5, 5),
+                Block::new(5, 4),
+                Block::new(5, 3),
+                Block::new(5, 2),
+            ],
+            Direction::Down,
+        );
+        // Moving onto a hazard block is not a collision, but costs two blocks.
+        assert!(game.check_snake_alive(Some(Direction::Down)));
+        assert!(game.update_snake());
+        assert_eq!(game.snake.body_len(), 2);
+        assert!(game.is_hurting());
+        // Still flashing while the tail passes over it, without further damage.
+        assert!(game.update_snake());
+        assert_eq!(game.snake.body_len(), 2);
+        assert!(game.is_hurting());
+        // Walls stay lethal, and a hazard block leaving only the head ends the run.
+        assert_eq!(
+            game.obstacle_kind(Block::new(5, 6)),
+            Some(ObstacleKind::Hazard {
+                damage_ticks: HAZARD_DAMAGE_TICKS
+            })
+        );
+        game.walls = vec![Block::new(5, 8)];
+        assert_eq!(
+            game.obstacle_kind(Block::new(5, 8)),
+            Some(ObstacleKind::Wall)
+        );
+        assert!(!game.check_snake_alive(Some(Direction::Down)));
+        game.walls.clear();
+        game.hazard_blocks = vec![Block::new(5, 8)];
+        assert!(!game.update_snake());
+        assert_eq!(game.death_cause(), Some(DeathCause::Damage));
+    }
+
+    #[test]
+    fn hazard_rows_hit_any_part_of_the_snake() {
+        let mut game = Game::new(custom_config());
+        // Straddling row 5 with a single body segment, head and tail on other rows.
+        let snake = [
+            Block::new(4, 4),
+            Block::new(4, 5),
+            Block::new(5, 5),
+            Block::new(5, 6),
+        ];
+        game.snake = Snake::from_blocks(snake, Direction::Up);
+        game.food = Some(Block::new(9, 3));
+        game.strike_row(3);
+        assert!(!game.game_over);
+        assert!(game.food.is_some_and(|food| food.y != 3));
+        game.strike_row(5);
+        assert!(game.game_over);
+        assert_eq!(game.death_cause(), Some(DeathCause::Hazard));
+        assert!(game.drain_events().any(|event| event == GameEvent::Died));
+    }
+
+    #[test]
+    fn hazard_rows_need_hard_difficulty() {
+        let mut game = Game::new(GameConfig {
+            hazard_rows: true,
+            ..custom_config()
+        });
+        assert!(!game.hazard_rows_active());
+        game.config.difficulty = Difficulty::Hard;
+        assert!(game.hazard_rows_active());
+        // The warning starts from the game update, never on the borders or the score strip.
+        game.update(crate::hazard::HAZARD_INTERVAL - 1.0);
+        let (row, _) = game.hazard.warning().unwrap();
+        assert!(row >= BORDER_WIDTH && row < game.height - BORDER_WIDTH);
     }
 }
@@ -0,0 +1,958 @@
+// External imports.
+use piston_window::{CharacterCache, Context, G2d, Glyphs};
+use std::collections::hash_map::DefaultHasher;
+use std::f64::consts::TAU;
+use std::hash::{Hash, Hasher};
+
+// Local imports.
+use super::{
+    food_is_in_line_of_sight, Game, BORDER_WIDTH, RISKY_BONUS_POINTS, SCORE_BORDER_WIDTH,
+    SPEED_UP_DURATION,
+};
+use crate::block::Block;
+use crate::config::HudLayout;
+use crate::dateformat::DisplayFormat;
+use crate::draw::{
+    draw_block, draw_gradient_background, draw_marker, draw_outlined_block, draw_progress_bar,
+    draw_rectangle, draw_text, draw_text_at_pixels, draw_text_centered, fit_font_size,
+    scoreboard_text, text_width, to_pixels, BLOCK_SIZE, LINE_SPACING, MIN_FONT_SIZE,
+};
+use crate::food;
+use crate::hazard::HAZARD_WARNING;
+use crate::locale::Locale;
+use crate::score::{Score, NUMBER_HIGH_SCORES};
+
+// Constants.
+/// The top left corner of the window, where the top and left border start.
+const WINDOW_CORNER: Block = Block::new(0, 0);
+/// The top left corner of the playing field, inside the borders.
+const FIELD_CORNER: Block = Block::new(BORDER_WIDTH, BORDER_WIDTH);
+// The text sizes, relative to the block size.
+const SCORE_FONT_RATIO: f64 = 0.8;
+const GAMEOVER_FONT_RATIO: f64 = 1.28;
+const SCOREBOARD_FONT_RATIO: f64 = 0.6;
+const FOOD_GLYPH_FONT_RATIO: f64 = 0.48;
+const POPUP_FONT_RATIO: f64 = 0.48;
+const SPEED_UP_FONT_RATIO: f64 = 0.96;
+#[cfg(debug_assertions)]
+const DEBUG_FONT_SIZE: u32 = 8;
+const SPEED_BAR_HEIGHT: f64 = 3.0;
+const SPEED_BAR_MARGIN: f64 = 2.0;
+/// The number of times a hazard row flashes during its warning.
+const HAZARD_FLASHES: f64 = 4.0;
+/// The opacity of a hazard row at the brightest point of a warning flash.
+const HAZARD_WARNING_ALPHA: f32 = 0.5;
+/// The time between two flashes of the Snake while it touches a hazard block, in seconds.
+const HURT_FLASH_PERIOD: f64 = 0.2;
+
+/// The width of the outline around food the Snake is heading straight for, in pixels.
+const FOOD_HIGHLIGHT_WIDTH: f64 = 2.0;
+
+/// The positions the borders are drawn at.
+struct Borders {
+    top_border: Block,
+    bottom_border: Block,
+    left_border: Block,
+    right_border: Block,
+    score_border: Block,
+    high_score_border: Block,
+    score_name_border: Block,
+}
+
+impl Borders {
+    /// Place all borders on a board of a given size, including the score strip.
+    fn new(width: i32, height: i32) -> Borders {
+        Borders {
+            top_border: WINDOW_CORNER,
+            bottom_border: Block::new(0, height - BORDER_WIDTH - SCORE_BORDER_WIDTH),
+            left_border: WINDOW_CORNER,
+            right_border: Block::new(width - BORDER_WIDTH, 0),
+            score_border: Block::new(0, height - BORDER_WIDTH),
+            high_score_border: Block::new(BORDER_WIDTH, height / 2 + 1),
+            score_name_border: Block::new(BORDER_WIDTH, height / 2 - 1),
+        }
+    }
+}
+
+impl Game {
+    /// Place the borders around the current board.
+    fn borders(&self) -> Borders {
+        Borders::new(self.width, self.height + SCORE_BORDER_WIDTH)
+    }
+
+    fn _draw_background(&self, con: &Context, g: &mut G2d) {
+        // Drawing the top, bottom, left and right borders of the screen.
+
+        draw_rectangle(
+            self.theme.border,
+            self.borders().top_border,
+            self.width,
+            BORDER_WIDTH,
+            con,
+            g,
+        );
+        draw_rectangle(
+            self.theme.border,
+            self.borders().bottom_border,
+            self.width,
+            BORDER_WIDTH,
+            con,
+            g,
+        );
+        draw_rectangle(
+            self.theme.border,
+            self.borders().left_border,
+            BORDER_WIDTH,
+            self.height,
+            con,
+            g,
+        );
+        draw_rectangle(
+            self.theme.border,
+            self.borders().right_border,
+            BORDER_WIDTH,
+            self.height,
+            con,
+            g,
+        );
+
+        // Drawing the score border.
+        draw_rectangle(
+            self.theme.border,
+            self.borders().score_border,
+            self.width,
+            SCORE_BORDER_WIDTH,
+            con,
+            g,
+        );
+
+        // Drawing the walls of the level like the borders.
+        for wall in &self.walls {
+            draw_rectangle(self.theme.border, *wall, 1, 1, con, g);
+        }
+        // Drawing the doors like walls, and the keys of closed doors as markers.
+        for door in &self.doors {
+            let color = if door.open {
+                self.theme.door_open
+            } else {
+                self.theme.door
+            };
+            draw_rectangle(color, door.position, 1, 1, con, g);
+            if !door.open {
+                draw_marker(door.key, self.theme.key, self.theme.marker_ratio, con, g);
+            }
+        }
+        // Drawing the hazard blocks as markers, so they do not read as walls.
+        for hazard in &self.hazard_blocks {
+            draw_marker(
+                *hazard,
+                self.theme.hazard,
+                self.theme.snake_block_ratio,
+                con,
+                g,
+            );
+        }
+    }
+
+    /// Get the pixel height of a line of text vertically centered in a row of blocks.
+    fn _hud_text_y(&self, row: i32, font_size: u32) -> f64 {
+        to_pixels(row) + (to_pixels(SCORE_BORDER_WIDTH) - font_size as f64 * LINE_SPACING) / 2.0
+    }
+
+    /// Get the pixel height of a bar along the bottom of a row of blocks.
+    fn _hud_bar_y(&self, row: i32) -> f64 {
+        to_pixels(row + 1) - SPEED_BAR_HEIGHT - SPEED_BAR_MARGIN
+    }
+
+    /// Find the top left pixel of a HUD element, which ends at its position if that is aligned to the right.
+    /// # Arguments
+    /// * `position: Block` - The position of the element in the HUD layout.
+    /// * `width: f64` - The width of the element in pixels.
+    /// # Returns
+    /// * `(f64, i32)` - The pixel column the element starts at, kept inside the window, and its row of blocks.
+    fn _hud_place(&self, position: Block, width: f64) -> (f64, i32) {
+        let (block, right_aligned) = HudLayout::resolve(position, self.size());
+        let x = if right_aligned {
+            to_pixels(block.x) - width
+        } else {
+            to_pixels(block.x)
+        };
+        (x.min(to_pixels(self.width) - width).max(0.0), block.y)
+    }
+
+    /// Lay out a line of HUD text with the metrics of the current font, which differ between fonts.
+    /// # Arguments
+    /// * `position: Block` - The position of the text in the HUD layout.
+    /// * `text: &str` - The text to lay out.
+    /// * `glyphs: &mut impl CharacterCache` - The font to measure the text with.
+    /// # Returns
+    /// * `(f64, f64, u32)` - The top left pixel of the text, and its font size.
+    fn _hud_text_layout(
+        &self,
+        position: Block,
+        text: &str,
+        glyphs: &mut impl CharacterCache,
+    ) -> (f64, f64, u32) {
+        let font_size = self._score_font_size(glyphs);
+        let (x, row) = self._hud_place(position, text_width(text, font_size, glyphs));
+        (x, self._hud_text_y(row, font_size), font_size)
+    }
+
+    /// Get the font size of the score strip, small enough for the score and speed text to fit side by side.
+    fn _score_font_size(&self, glyphs: &mut impl CharacterCache) -> u32 {
+        // Both texts get half of the strip, minus the speaker icon and the margin on the right.
+        let max_width = to_pixels(self.width / 2 - SCORE_BORDER_WIDTH);
+        fit_font_size(
+            self.hud_score_text.text(),
+            self.config.font_size(SCORE_FONT_RATIO),
+            max_width,
+            glyphs,
+        )
+        .min(fit_font_size(
+            self.hud_speed_text.text(),
+            self.config.font_size(SCORE_FONT_RATIO),
+            max_width,
+            glyphs,
+        ))
+    }
+
+    /// Draw the Snake, flashing in the hazard color while it touches a hazard block, or tinted when motion is reduced.
+    fn _draw_snake(&mut self, con: &Context, g: &mut G2d) {
+        let flash_on = self.config.reduce_motion
+            || ((self.play_time.as_secs_f64() / HURT_FLASH_PERIOD) as u64).is_multiple_of(2);
+        if self.hurting && flash_on {
+            let mut theme = self.theme.clone();
+            theme.snake_head = theme.hazard;
+            theme.snake_body = theme.hazard;
+            self.snake.draw(&theme, !self.eyes_closed, con, g);
+        } else {
+            self.snake.draw(&self.theme, !self.eyes_closed, con, g);
+        }
+    }
+
+    fn _draw_hazard(&self, con: &Context, g: &mut G2d) {
+        let (row, alpha) = if let Some(row) = self.hazard.struck() {
+            (row, 1.0)
+        } else if let Some((row, left)) = self.hazard.warning() {
+            // Flashing while the warning runs, or a steady tint when motion is reduced.
+            let flash = if self.config.reduce_motion {
+                1.0
+            } else {
+                (0.5 - 0.5 * (TAU * HAZARD_FLASHES * left / HAZARD_WARNING).cos()) as f32
+            };
+            (row, HAZARD_WARNING_ALPHA * flash)
+        } else {
+            return;
+        };
+        let [r, g_, b, _] = self.theme.hazard;
+        draw_rectangle(
+            [r, g_, b, alpha],
+            Block::new(BORDER_WIDTH, row),
+            self.width - 2 * BORDER_WIDTH,
+            1,
+            con,
+            g,
+        );
+    }
+
+    fn _draw_fog(&self, con: &Context, g: &mut G2d) {
+        for x in BORDER_WIDTH..self.width - BORDER_WIDTH {
+            for y in BORDER_WIDTH..self.height - BORDER_WIDTH {
+                let block = Block::new(x, y);
+                let darkness = self.fog_darkness(block);
+                if darkness > 0.0 {
+                    draw_rectangle([0.0, 0.0, 0.0, darkness as f32], block, 1, 1, con, g);
+                }
+            }
+        }
+    }
+
+    /// Rebuild the texts of the score strip and the scoreboard, if the values they show changed since the last frame.
+    /// # Arguments
+    /// * `scores: &[Score]` - The high scores shown on the game over screen.
+    /// * `locale: &Locale` - The strings of the language to build the texts in.
+    fn _update_texts(&mut self, scores: &[Score], locale: &Locale) {
+        let key = (
+            self.config.survival,
+            self.config.practice,
+            self.score(),
+            self.score_multiplier(),
+            self.multiplied_foods,
+        );
+        if self.hud_score_text.is_stale(&key) {
+            let text = self._score_text(locale);
+            self.hud_score_text.store(key, text);
+        }
+        let key = (self.speed_level(), self.at_max_speed());
+        if self.hud_speed_text.is_stale(&key) {
+            let text = self._speed_text(locale);
+            self.hud_speed_text.store(key, text);
+        }
+        if self.game_over {
+            let key = scoreboard_key(scores, self.config.timestamp_format);
+            if self.scoreboard_text.is_stale(&key) {
+                let text = scoreboard_text(
+                    scores,
+                    self.config.timestamp_format,
+                    self.config.utc_timestamps,
+                    locale,
+                );
+                self.scoreboard_text.store(key, text);
+            }
+        }
+    }
+
+    fn _draw_score_text(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        let text = self.hud_score_text.text();
+        let (x, y, font_size) =
+            self._hud_text_layout(self.config.hud_layout.score_pos, text, glyphs);
+        draw_text_at_pixels(text, x, y, self.theme.hud, font_size, glyphs, con, g);
+    }
+
+    fn _draw_speed_text(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        let text = self.hud_speed_text.text();
+        let (x, y, font_size) =
+            self._hud_text_layout(self.config.hud_layout.speed_pos, text, glyphs);
+        draw_text_at_pixels(text, x, y, self.theme.hud, font_size, glyphs, con, g);
+        // Showing the progress towards the next speed level below the text, along half of the strip.
+        let color = if self.at_max_speed() {
+            self.theme.max_speed
+        } else {
+            self.theme.hud
+        };
+        let bar_width = to_pixels(self.width - self.width / 2 - SCORE_BORDER_WIDTH);
+        let (x, row) = self._hud_place(self.config.hud_layout.speed_pos, bar_width);
+        draw_progress_bar(
+            x,
+            self._hud_bar_y(row),
+            [bar_width, SPEED_BAR_HEIGHT],
+            self.speed_progress(),
+            [color, self.theme.hud_accent],
+            con,
+            g,
+        );
+    }
+
+    /// Draw how much of the playing field the Snake fills along half of the strip, changing color every quarter.
+    fn _draw_length_bar(&self, con: &Context, g: &mut G2d) {
+        let fraction = self.length_fraction();
+        let quarter = ((fraction * 4.0) as usize).min(3);
+        let bar_width = to_pixels(self.width / 2 - 2 * SCORE_BORDER_WIDTH);
+        let (x, row) = self._hud_place(self.config.hud_layout.length_pos, bar_width);
+        draw_progress_bar(
+            x,
+            self._hud_bar_y(row),
+            [bar_width, SPEED_BAR_HEIGHT],
+            fraction,
+            [self.theme.length_bar[quarter], self.theme.hud_accent],
+            con,
+            g,
+        );
+    }
+
+    /// Draw the length of the current eating streak while it earns combo bonus points.
+    fn _draw_combo(&self, locale: &Locale, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        let text = locale.format("combo", &[("streak", &self.eat_streak)]);
+        let (x, y, font_size) =
+            self._hud_text_layout(self.config.hud_layout.combo_pos, &text, glyphs);
+        draw_text_at_pixels(&text, x, y, self.theme.hud, font_size, glyphs, con, g);
+    }
+
+    /// Draw a single line of text centered on the top border, to announce the length milestones and the end of the
+    /// food supply.
+    fn _draw_banner(&self, text: &str, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        let max_width = to_pixels(self.width - 2 * BORDER_WIDTH);
+        let font_size = fit_font_size(
+            text,
+            self.config.font_size(SCOREBOARD_FONT_RATIO),
+            max_width,
+            glyphs,
+        );
+        draw_text_at_pixels(
+            text,
+            (to_pixels(self.width) - text_width(text, font_size, glyphs)) / 2.0,
+            (to_pixels(BORDER_WIDTH) - font_size as f64 * LINE_SPACING) / 2.0,
+            self.theme.hud,
+            font_size,
+            glyphs,
+            con,
+            g,
+        );
+    }
+
+    /// Get the opacity of the speed up announcement, which fades out as it runs out, or stays constant when motion is
+    /// reduced.
+    pub(super) fn _speed_up_alpha(&self) -> f32 {
+        let a = self.theme.max_speed[3];
+        if self.config.reduce_motion {
+            a
+        } else {
+            a * (self.speed_up_timer / SPEED_UP_DURATION) as f32
+        }
+    }
+
+    fn _draw_speed_up(&self, locale: &Locale, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        let [r, g_, b, _] = self.theme.max_speed;
+        draw_text_centered(
+            locale.get("speed_up"),
+            Block::new(self.width / 2, self.height / 2),
+            [r, g_, b, self._speed_up_alpha()],
+            self.config.font_size(SPEED_UP_FONT_RATIO),
+            glyphs,
+            con,
+            g,
+        );
+    }
+
+    fn _draw_game_over_screen(
+        &self,
+        locale: &Locale,
+        glyphs: &mut Glyphs,
+        con: &Context,
+        g: &mut G2d,
+    ) {
+        draw_rectangle(
+            self.theme.game_over_overlay,
+            Block::new(SCORE_BORDER_WIDTH, BORDER_WIDTH),
+            self.width - 2 * BORDER_WIDTH,
+            self.height - BORDER_WIDTH - SCORE_BORDER_WIDTH,
+            con,
+            g,
+        );
+        let final_score = match self.high_score {
+            true => "final_score_high",
+            false => "final_score",
+        };
+        let play_again = if self.can_undo() {
+            format!("{} {}", locale.get("play_again"), locale.get("undo"))
+        } else {
+            locale.get("play_again").to_string()
+        };
+        let text = format!(
+            "{}\n{}\n{}",
+            locale.get("game_over"),
+            locale.format(final_score, &[("score", &self.score())]),
+            play_again
+        );
+        let max_width = to_pixels(self.width - 2 * BORDER_WIDTH);
+        let font_size = fit_font_size(
+            &text,
+            self.config.font_size(GAMEOVER_FONT_RATIO),
+            max_width,
+            glyphs,
+        );
+        draw_text(
+            &text,
+            FIELD_CORNER,
+            self.theme.overlay_text,
+            font_size,
+            glyphs,
+            con,
+            g,
+        );
+        // Showing the run statistics right below the three lines above.
+        let statistics = format!(
+            "{} - {} - {}",
+            locale.format("best_streak", &[("streak", &self.max_eat_streak)]),
+            locale.format("peak_length", &[("length", &self.peak_length)]),
+            locale.format("close_calls", &[("count", &self.near_miss_count)])
+        );
+        let statistics_y = to_pixels(BORDER_WIDTH) + 3.0 * font_size as f64 * LINE_SPACING;
+        let statistics_size = fit_font_size(
+            &statistics,
+            self.config.font_size(SCOREBOARD_FONT_RATIO),
+            max_width,
+            glyphs,
+        );
+        draw_text_at_pixels(
+            &statistics,
+            to_pixels(BORDER_WIDTH),
+            statistics_y,
+            self.theme.overlay_text,
+            statistics_size,
+            glyphs,
+            con,
+            g,
+        );
+        self._draw_breakdown(
+            locale,
+            statistics_y + statistics_size as f64 * LINE_SPACING,
+            glyphs,
+            con,
+            g,
+        );
+    }
+
+    /// Draw where the points of the run came from as a table against the right border, skipping empty categories.
+    /// # Arguments
+    /// * `top: f64` - The pixel height of the first row.
+    fn _draw_breakdown(
+        &self,
+        locale: &Locale,
+        top: f64,
+        glyphs: &mut Glyphs,
+        con: &Context,
+        g: &mut G2d,
+    ) {
+        let rows: Vec<(&str, String)> = self
+            .breakdown
+            .rows()
+            .into_iter()
+            .filter(|(_, points)| *points != 0)
+            .map(|(key, points)| (locale.get(key), points.to_string()))
+            .collect();
+        let labels = rows
+            .iter()
+            .map(|(label, _)| *label)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let values = rows
+            .iter()
+            .map(|(_, value)| value.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let font_size = self.config.font_size(SCOREBOARD_FONT_RATIO);
+        let right = to_pixels(self.width - BORDER_WIDTH) - font_size as f64;
+        let values_width = text_width(&values, font_size, glyphs);
+        let labels_x =
+            right - values_width - font_size as f64 - text_width(&labels, font_size, glyphs);
+        draw_text_at_pixels(
+            &labels,
+            labels_x,
+            top,
+            self.theme.overlay_text,
+            font_size,
+            glyphs,
+            con,
+            g,
+        );
+        // Aligning every value to the right edge on its own.
+        for (i, (_, value)) in rows.iter().enumerate() {
+            draw_text_at_pixels(
+                value,
+                right - text_width(value, font_size, glyphs),
+                top + i as f64 * font_size as f64 * LINE_SPACING,
+                self.theme.overlay_text,
+                font_size,
+                glyphs,
+                con,
+                g,
+            );
+        }
+    }
+
+    /// Annotate every Snake body block and obstacle with its grid coordinates, and the food with its coordinates until
+    /// it escapes and with the number of escapes afterwards.
+    #[cfg(debug_assertions)]
+    fn _draw_debug_overlay(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        for block in self.snake.blocks().chain(self.food).chain(self.obstacles()) {
+            let text = if Some(block) == self.food && self.food_moves > 0 {
+                format!("x{}", self.food_moves)
+            } else {
+                format!("{},{}", block.x, block.y)
+            };
+            draw_text_centered(
+                &text,
+                block,
+                self.theme.overlay_text,
+                DEBUG_FONT_SIZE,
+                glyphs,
+                con,
+                g,
+            );
+        }
+    }
+
+    fn _draw_hints(&self, locale: &Locale, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        draw_rectangle(
+            self.theme.game_over_overlay,
+            FIELD_CORNER,
+            self.width - 2 * BORDER_WIDTH,
+            self.height - BORDER_WIDTH - SCORE_BORDER_WIDTH,
+            con,
+            g,
+        );
+        let text = locale.get("hints");
+        let max_width = to_pixels(self.width - 2 * BORDER_WIDTH);
+        draw_text(
+            text,
+            FIELD_CORNER,
+            self.theme.overlay_text,
+            fit_font_size(
+                text,
+                self.config.font_size(SCORE_FONT_RATIO),
+                max_width,
+                glyphs,
+            ),
+            glyphs,
+            con,
+            g,
+        );
+    }
+
+    /// Get the text size of the high scores, shrunk until all of them fit between the top of the scoreboard and the
+    /// bottom border.
+    fn _scoreboard_font_size(&self) -> u32 {
+        let borders = self.borders();
+        let region = to_pixels(borders.bottom_border.y - borders.high_score_border.y);
+        let fitting = (region / (NUMBER_HIGH_SCORES as f64 * LINE_SPACING)).floor() as u32;
+        self.config
+            .font_size(SCOREBOARD_FONT_RATIO)
+            .min(fitting)
+            .max(MIN_FONT_SIZE)
+    }
+
+    fn _draw_scoreboard(&self, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        draw_text(
+            self.scoreboard_text.text(),
+            self.borders().high_score_border,
+            self.theme.overlay_text,
+            self._scoreboard_font_size(),
+            glyphs,
+            con,
+            g,
+        )
+    }
+
+    fn _draw_name_querry(&self, locale: &Locale, glyphs: &mut Glyphs, con: &Context, g: &mut G2d) {
+        let position = self.borders().score_name_border;
+        let font_size = self.config.font_size(SCORE_FONT_RATIO);
+        draw_text(
+            &locale.format("name", &[("name", &self.score_name)]),
+            position,
+            self.theme.overlay_text,
+            font_size,
+            glyphs,
+            con,
+            g,
+        );
+        // Underlining the letter at the cursor, placed after the text in front of it.
+        if let Some(cursor) = self.name_cursor() {
+            let before = locale.format("name", &[("name", &&self.score_name[..cursor])]);
+            draw_text_at_pixels(
+                "_",
+                to_pixels(position.x) + text_width(&before, font_size, glyphs),
+                to_pixels(position.y),
+                self.theme.overlay_text,
+                font_size,
+                glyphs,
+                con,
+                g,
+            );
+        }
+    }
+
+    /// Draw all game elements: the snake, the borders, food, game over symbols and the score.
+    /// # Arguments
+    /// * `glyphs: &mut piston_window::Glyphs` - The characters to use for drawing.
+    /// * `con: &piston_window::Context` - The context in which to draw.
+    /// * `g: &mut G2d` - The 2d graphics driver to use.
+    /// * `scores: &[Score]` - The high scores shown on the game over screen.
+    /// * `locale: &Locale` - The strings of the language to draw all text in.
+    pub fn draw(
+        &mut self,
+        // key: Option<Key>,
+        // scores: &HashMap<i32, Score>,
+        glyphs: &mut Glyphs,
+        con: &Context,
+        g: &mut G2d,
+        scores: &[Score],
+        locale: &Locale,
+    ) {
+        self._update_texts(scores, locale);
+        if self.config.gradient_background {
+            draw_gradient_background(
+                self.theme.background,
+                self.theme.background_bottom,
+                self.width,
+                self.height,
+                con,
+                g,
+            );
+        }
+        // Drawing the snake and food.
+        self._draw_snake(con, g);
+        if let Some(star) = self.star {
+            draw_outlined_block(
+                star,
+                self.theme.star,
+                self.theme.food_highlight,
+                FOOD_HIGHLIGHT_WIDTH,
+                con,
+                g,
+            );
+            draw_text_centered(
+                "x2",
+                star,
+                self.theme.food_glyph,
+                self.config.font_size(FOOD_GLYPH_FONT_RATIO),
+                glyphs,
+                con,
+                g,
+            );
+        }
+        if let Some(food) = self.food {
+            let color =
+                food::decayed_color(self.food_kind.color(&self.theme), self.food_freshness());
+            if food_is_in_line_of_sight(&self.snake, food, &self.obstacles()) {
+                draw_outlined_block(
+                    food,
+                    color,
+                    self.theme.food_highlight,
+                    FOOD_HIGHLIGHT_WIDTH,
+                    con,
+                    g,
+                );
+            } else {
+                draw_block(food, color, [0.0, 0.0], [BLOCK_SIZE, BLOCK_SIZE], con, g);
+            }
+            if let Some(marker) = self.theme.food_marker {
+                draw_marker(food, marker, self.theme.marker_ratio, con, g);
+            }
+            if let Some(glyph) = self.food_kind.glyph() {
+                draw_text_centered(
+                    &glyph.to_string(),
+                    food,
+                    self.theme.food_glyph,
+                    self.config.font_size(FOOD_GLYPH_FONT_RATIO),
+                    glyphs,
+                    con,
+                    g,
+                );
+            }
+        };
+
+        self._draw_hazard(con, g);
+
+        self._draw_background(con, g);
+        // Covering the playing field but not the score strip, and lifting the fog once the run is over.
+        if self.config.fog_of_war && !self.game_over {
+            self._draw_fog(con, g);
+        }
+        // Drawing on top of the fog, so the coordinates stay readable with fog of war on.
+        #[cfg(debug_assertions)]
+        if self.debug_overlay {
+            self._draw_debug_overlay(glyphs, con, g);
+        }
+        if let Some((block, _)) = self.risky_popup {
+            draw_text_centered(
+                &locale.format("risky", &[("points", &RISKY_BONUS_POINTS)]),
+                block,
+                self.theme.overlay_text,
+                self.config.font_size(POPUP_FONT_RATIO),
+                glyphs,
+                con,
+                g,
+            );
+        }
+        if self.speed_up_timer > 0.0 && !self.game_over {
+            self._draw_speed_up(locale, glyphs, con, g);
+        }
+        if let Some(((block, points), _)) = self.penalty_popup {
+            draw_text_centered(
+                &locale.format("penalty", &[("points", &points)]),
+                block,
+                self.theme.penalty,
+                self.config.font_size(POPUP_FONT_RATIO),
+                glyphs,
+                con,
+                g,
+            );
+        }
+        self._draw_score_text(glyphs, con, g);
+        self._draw_speed_text(glyphs, con, g);
+        self._draw_length_bar(con, g);
+        if self.combo_active() && !self.game_over {
+            self._draw_combo(locale, glyphs, con, g);
+        }
+        if let Some((length, _)) = self.milestone_banner {
+            let text = locale.format("length_milestone", &[("length", &length)]);
+            self._draw_banner(&text, glyphs, con, g);
+        } else if self.food_supply_exhausted() && !self.game_over {
+            self._draw_banner(locale.get("no_more_food"), glyphs, con, g);
+        }
+
+        // Drawing a game over screen.
+        if self.game_over {
+            self._draw_game_over_screen(locale, glyphs, con, g);
+            self._draw_scoreboard(glyphs, con, g)
+        }
+
+        if self.high_score {
+            self._draw_name_querry(locale, glyphs, con, g);
+        }
+
+        if self.hints {
+            self._draw_hints(locale, glyphs, con, g);
+        } else if self.paused {
+            let text = format!(
+                "{}\n{}\n{}",
+                locale.get("paused"),
+                locale.get("resume"),
+                locale.get("open_settings")
+            );
+            let max_width = to_pixels(self.width - 2 * BORDER_WIDTH);
+            let font_size = fit_font_size(
+                &text,
+                self.config.font_size(GAMEOVER_FONT_RATIO),
+                max_width,
+                glyphs,
+            );
+            draw_text(
+                &text,
+                Block::new(BORDER_WIDTH, self.height / 2 - 1),
+                self.theme.overlay_text,
+                font_size,
+                glyphs,
+                con,
+                g,
+            );
+        }
+    }
+}
+
+/// Get what the scoreboard text is built from, without building it.
+/// # Arguments
+/// * `scores: &[Score]` - The high scores shown on the game over screen.
+/// * `timestamp_format: DisplayFormat` - How the scoreboard shows when each score was set.
+/// # Returns
+/// * `(u64, DisplayFormat, i64)` - A hash of the scores, the format and the current minute, as relative timestamps
+///   change over time.
+fn scoreboard_key(scores: &[Score], timestamp_format: DisplayFormat) -> (u64, DisplayFormat, i64) {
+    let mut hasher = DefaultHasher::new();
+    for score in scores.iter().take(NUMBER_HIGH_SCORES) {
+        score.score().hash(&mut hasher);
+        score.player().hash(&mut hasher);
+        score.timestamp().hash(&mut hasher);
+    }
+    let minute = match timestamp_format {
+        DisplayFormat::Relative => chrono::Utc::now().timestamp() / 60,
+        DisplayFormat::Absolute => 0,
+    };
+    (hasher.finish(), timestamp_format, minute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GameConfig, BOARD_SIZES, MAX_FONT_SCALE};
+    use piston_window::character::Character;
+    use piston_window::types::FontSize;
+    use piston_window::ImageSize;
+
+    /// A texture without pixels, as measuring text never draws it.
+    struct NoTexture;
+
+    impl ImageSize for NoTexture {
+        fn get_size(&self) -> (u32, u32) {
+            (0, 0)
+        }
+    }
+
+    /// A font whose glyphs are one and a half times as wide as they are high, far wider than the bundled font.
+    struct WideGlyphs(NoTexture);
+
+    impl CharacterCache for WideGlyphs {
+        type Texture = NoTexture;
+        type Error = ();
+
+        fn character(
+            &mut self,
+            font_size: FontSize,
+            _ch: char,
+        ) -> Result<Character<'_, NoTexture>, ()> {
+            Ok(Character {
+                offset: [0.0, 0.0],
+                advance_size: [1.5 * font_size as f64, font_size as f64],
+                atlas_offset: [0.0, 0.0],
+                atlas_size: [0.0, 0.0],
+                texture: &self.0,
+                is_invalid: false,
+            })
+        }
+    }
+
+    #[test]
+    fn wide_fonts_keep_the_hud_on_screen() {
+        let locale = Locale::default();
+        let mut glyphs = WideGlyphs(NoTexture);
+        let right = Block::new(-1, -1);
+        for hud_layout in [
+            HudLayout::default(),
+            HudLayout {
+                score_pos: right,
+                speed_pos: right,
+                length_pos: right,
+                combo_pos: right,
+            },
+        ] {
+            let mut game = Game::new(GameConfig {
+                hud_layout,
+                font_scale: 2.0,
+                ..GameConfig::default()
+            });
+            game._update_texts(&[], &locale);
+            let layout = game.config.hud_layout;
+            for (position, text) in [
+                (layout.score_pos, game._score_text(&locale)),
+                (layout.speed_pos, game._speed_text(&locale)),
+                (layout.combo_pos, locale.format("combo", &[("streak", &12)])),
+            ] {
+                let (x, _, font_size) = game._hud_text_layout(position, &text, &mut glyphs);
+                assert!(font_size < game.config.font_size(SCORE_FONT_RATIO));
+                assert!(x >= 0.0);
+                assert!(x + text_width(&text, font_size, &mut glyphs) <= to_pixels(game.width));
+            }
+        }
+    }
+
+    #[test]
+    fn scoreboards_fit_above_the_bottom_border() {
+        for (width, height) in BOARD_SIZES {
+            for font_scale in [1.0, MAX_FONT_SCALE] {
+                let game = Game::new(GameConfig {
+                    width,
+                    height,
+                    font_scale,
+                    ..GameConfig::default()
+                });
+                let borders = game.borders();
+                let region = to_pixels(borders.bottom_border.y - borders.high_score_border.y);
+                let text_height =
+                    game._scoreboard_font_size() as f64 * NUMBER_HIGH_SCORES as f64 * LINE_SPACING;
+                assert!(text_height <= region, "{width}x{height} at {font_scale}");
+            }
+        }
+    }
+
+    #[test]
+    fn texts_are_built_once_per_change_rather_than_per_frame() {
+        let locale = Locale::default();
+        let mut game = Game::new(GameConfig {
+            timestamp_format: DisplayFormat::Absolute,
+            ..GameConfig::default()
+        });
+        game.game_over = true;
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let score = |points: i32| {
+            Score::builder()
+                .score(points)
+                .player("ABC")
+                .timestamp(timestamp)
+                .build()
+        };
+        let mut scores: Vec<Score> = (0..NUMBER_HIGH_SCORES as i32).map(score).collect();
+        for _ in 0..5 {
+            game._update_texts(&scores, &locale);
+        }
+        assert_eq!(game.scoreboard_text.builds(), 1);
+        assert_eq!(game.hud_score_text.builds(), 1);
+        scores[0] = score(99);
+        game._update_texts(&scores, &locale);
+        assert_eq!(game.scoreboard_text.builds(), 2);
+        assert!(game.scoreboard_text.text().contains("99"));
+        assert_eq!(game.hud_speed_text.builds(), 1);
+    }
+}
@@ -0,0 +1,253 @@
+// External imports.
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+
+// Local imports.
+use crate::block::Block;
+use crate::direction::Direction;
+use crate::snake::Snake;
+
+// Constants.
+/// The number of free cells the Snake must still be able to reach beyond its own length after placing an obstacle.
+pub const MIN_REACHABLE_MARGIN: usize = 10;
+/// The minimum number of free cells next to the head after placing an obstacle.
+pub const MIN_HEAD_EXITS: usize = 2;
+/// The number of random placements tried per obstacle count before falling back to fewer obstacles.
+const MAX_GENERATION_ATTEMPTS: usize = 10;
+
+/// Get the four Blocks a single move away.
+/// # Arguments
+/// * `block: Block` - The Block to start from.
+/// # Returns
+/// * `impl Iterator<Item = Block>` - The Blocks above, below, left and right of it.
+pub fn neighbours(block: Block) -> impl Iterator<Item = Block> {
+    Direction::offsets()
+        .into_iter()
+        .map(move |(_, [dx, dy])| Block::new(block.x + dx, block.y + dy))
+}
+
+/// Find every Block that can be reached from a start over free Blocks.
+/// # Arguments
+/// * `start: Block` - The Block to start from, which is always reached, free or not.
+/// * `is_free: impl Fn(Block) -> bool` - Whether a Block can be moved onto. Must be false outside the board.
+/// # Returns
+/// * `HashSet<Block>` - The start and all reachable free Blocks.
+pub fn flood_fill(start: Block, is_free: impl Fn(Block) -> bool) -> HashSet<Block> {
+    let mut reached = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(block) = queue.pop_front() {
+        for next in neighbours(block) {
+            if is_free(next) && reached.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    reached
+}
+
+/// Check whether placing an obstacle leaves the Snake a fair way out.
+/// The obstacle must stay clear of the ring around the head, leave at least `MIN_HEAD_EXITS` free cells next to
+/// the head and leave at least `MIN_REACHABLE_MARGIN` more reachable free cells than the Snake is long.
+/// # Arguments
+/// * `candidate: Block` - Where the obstacle would be placed.
+/// * `snake: &Snake` - A reference to the Snake.
+/// * `obstacles: &[Block]` - The obstacles already on the board.
+/// * `width: i32` - The board width in blocks, including the borders.
+/// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+/// # Returns
+/// * `bool` - Whether (true) or not (false) the obstacle can be placed.
+pub fn obstacle_is_fair(
+    candidate: Block,
+    snake: &Snake,
+    obstacles: &[Block],
+    width: i32,
+    height: i32,
+) -> bool {
+    let head = snake.head_position();
+    if (candidate.x - head.x).abs() <= 1 && (candidate.y - head.y).abs() <= 1 {
+        return false;
+    }
+    let is_free = |block: Block| {
+        !block.out_of_bounds([0, width], [0, height])
+            && block != candidate
+            && !obstacles.contains(&block)
+            && !snake.overlap_tail(block)
+    };
+    let exits = neighbours(head).filter(|block| is_free(*block)).count();
+    // The head itself is reached but not free.
+    let reachable = flood_fill(head, is_free).len() - 1;
+    exits >= MIN_HEAD_EXITS && reachable >= snake.body_len() + MIN_REACHABLE_MARGIN
+}
+
+/// Check whether every free Block on the board can be reached from a start.
+/// # Arguments
+/// * `start: Block` - The Block to start from.
+/// * `is_free: impl Fn(Block) -> bool` - Whether a Block can be moved onto. Must be false outside the board.
+/// * `width: i32` - The board width in blocks, including the borders.
+/// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+/// # Returns
+/// * `bool` - Whether (true) or not (false) the free Blocks form a single region around the start.
+pub fn is_connected(
+    start: Block,
+    is_free: impl Fn(Block) -> bool,
+    width: i32,
+    height: i32,
+) -> bool {
+    let free = (0..width)
+        .flat_map(|x| (0..height).map(move |y| Block::new(x, y)))
+        .filter(|block| is_free(*block) || *block == start)
+        .count();
+    flood_fill(start, is_free).len() == free
+}
+
+/// Place random obstacles on a board, keeping every free Block reachable from the start.
+/// Every count is tried `MAX_GENERATION_ATTEMPTS` times before halving it, so this always finishes quickly, at
+/// worst without any obstacles.
+/// # Arguments
+/// * `rng: &mut R` - The random number generator to place the obstacles with.
+/// * `count: usize` - The number of obstacles to place.
+/// * `start: Block` - Where the Snake starts, from which everything must stay reachable.
+/// * `reserved: &[Block]` - Blocks to keep free besides the start, like the first moves of the Snake.
+/// * `walls: &[Block]` - The walls already on the board.
+/// * `width: i32` - The board width in blocks, including the borders.
+/// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+/// # Returns
+/// * `Vec<Block>` - The obstacles, at most `count` of them.
+pub fn generate_obstacles<R: Rng>(
+    rng: &mut R,
+    count: usize,
+    start: Block,
+    reserved: &[Block],
+    walls: &[Block],
+    width: i32,
+    height: i32,
+) -> Vec<Block> {
+    let candidates: Vec<Block> = (0..width)
+        .flat_map(|x| (0..height).map(move |y| Block::new(x, y)))
+        .filter(|block| {
+            !block.out_of_bounds([0, width], [0, height])
+                && *block != start
+                && !reserved.contains(block)
+                && !walls.contains(block)
+        })
+        .collect();
+    let mut count = count.min(candidates.len());
+    while count > 0 {
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let obstacles: HashSet<Block> =
+                candidates.choose_multiple(rng, count).copied().collect();
+            let is_free = |block: Block| {
+                !block.out_of_bounds([0, width], [0, height])
+                    && !walls.contains(&block)
+                    && !obstacles.contains(&block)
+            };
+            if is_connected(start, is_free, width, height) {
+                return candidates
+                    .iter()
+                    .filter(|block| obstacles.contains(block))
+                    .copied()
+                    .collect();
+            }
+        }
+        #[cfg(feature = "logging")]
+        log::warn!(
+            "Skipping {} of {} obstacles, which kept cutting off part of the board",
+            count - count / 2,
+            count
+        );
+        count /= 2;
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flood_fill_stays_inside_walls() {
+        // A 3x3 room, walled off from the rest of the board by a ring of blocked cells.
+        let reached = flood_fill(Block::new(1, 1), |block| {
+            (0..3).contains(&block.x) && (0..3).contains(&block.y)
+        });
+        assert_eq!(reached.len(), 9);
+        assert!(!reached.contains(&Block::new(3, 1)));
+        // A start without free neighbours only reaches itself.
+        assert_eq!(flood_fill(Block::new(0, 0), |_| false).len(), 1);
+    }
+
+    #[test]
+    fn obstacles_keep_clear_of_the_head() {
+        let snake = Snake::from_blocks([Block::new(5, 5), Block::new(4, 5)], Direction::Right);
+        assert!(!obstacle_is_fair(Block::new(6, 6), &snake, &[], 20, 19));
+        assert!(obstacle_is_fair(Block::new(7, 5), &snake, &[], 20, 19));
+    }
+
+    #[test]
+    fn obstacles_cannot_seal_off_the_snake() {
+        // The Snake is in a pocket of 2 by 5 cells along the left border, whose only way out is at (3, 1).
+        let snake = Snake::from_blocks([Block::new(1, 3), Block::new(1, 4)], Direction::Up);
+        let wall: Vec<Block> = (2..6)
+            .map(|y| Block::new(3, y))
+            .chain((1..4).map(|x| Block::new(x, 6)))
+            .collect();
+        assert!(obstacle_is_fair(Block::new(10, 10), &snake, &wall, 20, 19));
+        assert!(!obstacle_is_fair(Block::new(3, 1), &snake, &wall, 20, 19));
+    }
+
+    #[test]
+    fn obstacles_need_two_exits_next_to_the_head() {
+        // Cornered in the top left, with the body blocking the only other way.
+        let snake = Snake::from_blocks(
+            [Block::new(1, 1), Block::new(2, 1), Block::new(3, 1)],
+            Direction::Left,
+        );
+        assert!(!obstacle_is_fair(Block::new(10, 10), &snake, &[], 20, 19));
+    }
+
+    #[test]
+    fn generated_obstacles_keep_the_board_connected() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let (width, height) = (20, 19);
+        let start = Block::new(2, 2);
+        let reserved = [Block::new(3, 2), Block::new(4, 2)];
+        let interior = ((width - 2) * (height - 2)) as f64;
+        for density in [0.05, 0.15, 0.3] {
+            let count = (interior * density) as usize;
+            for seed in 0..100 {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let obstacles =
+                    generate_obstacles(&mut rng, count, start, &reserved, &[], width, height);
+                assert!(obstacles.len() <= count);
+                assert!(!obstacles.contains(&start));
+                assert!(!obstacles.iter().any(|block| reserved.contains(block)));
+                let is_free = |block: Block| {
+                    !block.out_of_bounds([0, width], [0, height]) && !obstacles.contains(&block)
+                };
+                assert!(is_connected(start, is_free, width, height));
+            }
+        }
+        // The same seed places the same obstacles.
+        let generate = || {
+            let mut rng = StdRng::seed_from_u64(7);
+            generate_obstacles(&mut rng, 20, start, &reserved, &[], width, height)
+        };
+        assert!(generate() == generate());
+    }
+
+    #[test]
+    fn crowded_boards_fall_back_to_fewer_obstacles() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let obstacles = generate_obstacles(&mut rng, 1000, Block::new(2, 2), &[], &[], 10, 10);
+        assert!(obstacles.len() < 64);
+        let is_free =
+            |block: Block| !block.out_of_bounds([0, 10], [0, 10]) && !obstacles.contains(&block);
+        assert!(is_connected(Block::new(2, 2), is_free, 10, 10));
+    }
+}
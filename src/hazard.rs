@@ -0,0 +1,107 @@
+// External imports.
+use rand::Rng;
+use std::ops::Range;
+
+// Constants.
+/// The time between two strikes of the hazard row, in seconds of play.
+pub const HAZARD_INTERVAL: f64 = 20.0;
+/// The time the row is highlighted before it strikes, in seconds.
+pub const HAZARD_WARNING: f64 = 2.0;
+/// The time the row stays lit up after it struck, in seconds. Only the strike itself is lethal.
+pub const HAZARD_STRIKE_FLASH: f64 = 0.2;
+
+/// A row of the board that periodically becomes lethal, after a warning.
+pub struct HazardRow {
+    /// The time until the next strike.
+    until_strike: f64,
+    /// The row that strikes next, picked once the warning starts.
+    warning: Option<i32>,
+    /// The row that struck last and the time it stays lit up.
+    struck: Option<(i32, f64)>,
+}
+
+impl Default for HazardRow {
+    fn default() -> Self {
+        HazardRow::new()
+    }
+}
+
+impl HazardRow {
+    /// Schedule the first strike a full interval from now.
+    pub fn new() -> HazardRow {
+        HazardRow {
+            until_strike: HAZARD_INTERVAL,
+            warning: None,
+            struck: None,
+        }
+    }
+
+    /// Advance the schedule, picking the row as the warning starts.
+    /// # Arguments
+    /// * `delta_time: f64` - The time passed since the last update in seconds.
+    /// * `rows: Range<i32>` - The rows that can strike, which should exclude the borders and the score strip.
+    /// * `rng: &mut R` - The random number generator to pick the row with.
+    /// # Returns
+    /// * `Option<i32>` - The row that strikes during this update, if any.
+    pub fn update<R: Rng>(
+        &mut self,
+        delta_time: f64,
+        rows: Range<i32>,
+        rng: &mut R,
+    ) -> Option<i32> {
+        self.struck = self
+            .struck
+            .map(|(row, flash)| (row, flash - delta_time))
+            .filter(|(_, flash)| *flash > 0.0);
+        self.until_strike -= delta_time;
+        if self.until_strike <= HAZARD_WARNING && self.warning.is_none() && !rows.is_empty() {
+            self.warning = Some(rng.gen_range(rows));
+        }
+        if self.until_strike > 0.0 {
+            return None;
+        }
+        self.until_strike = HAZARD_INTERVAL;
+        let row = self.warning.take()?;
+        self.struck = Some((row, HAZARD_STRIKE_FLASH));
+        Some(row)
+    }
+
+    /// Get the row that is about to strike.
+    /// # Returns
+    /// * `Option<(i32, f64)>` - The row and the time left until it strikes, during the warning.
+    pub fn warning(&self) -> Option<(i32, f64)> {
+        self.warning.map(|row| (row, self.until_strike))
+    }
+
+    /// Get the row that just struck, while it is still lit up.
+    pub fn struck(&self) -> Option<i32> {
+        self.struck.map(|(row, _)| row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn rows_strike_after_a_warning() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut hazard = HazardRow::new();
+        assert_eq!(
+            hazard.update(HAZARD_INTERVAL - HAZARD_WARNING - 0.5, 1..10, &mut rng),
+            None
+        );
+        assert!(hazard.warning().is_none());
+        assert_eq!(hazard.update(1.0, 1..10, &mut rng), None);
+        let (row, left) = hazard.warning().unwrap();
+        assert!((1..10).contains(&row));
+        assert!(left <= HAZARD_WARNING);
+        assert_eq!(hazard.update(left, 1..10, &mut rng), Some(row));
+        assert_eq!(hazard.struck(), Some(row));
+        // The strike lasts a single update, and the next one is a full interval away.
+        assert_eq!(hazard.update(HAZARD_STRIKE_FLASH, 1..10, &mut rng), None);
+        assert!(hazard.struck().is_none() && hazard.warning().is_none());
+    }
+}
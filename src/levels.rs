@@ -0,0 +1,506 @@
+// External imports.
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+// Local imports.
+use crate::block::Block;
+
+// Constants.
+const WALL: char = '#';
+const START: char = 'S';
+const FOOD: char = 'F';
+const DOOR: char = 'D';
+const KEY: char = 'K';
+const HAZARD: char = '^';
+const EMPTY: char = '.';
+/// The cell the Snake unfolds from when a layout does not mark one, the same as on an open board.
+pub const DEFAULT_START: Block = Block::new(2, 2);
+/// The distance between the border and the walls of the preset layouts, keeping the start area clear.
+const PRESET_INSET: i32 = 3;
+/// The width of the corridors of a maze, in blocks. The last row and column of rooms absorb any leftover blocks.
+const MAZE_CORRIDOR_WIDTH: i32 = 2;
+/// The distance from the start within which a maze has no walls, so the Snake has room to get going.
+const MAZE_START_CLEARANCE: i32 = 3;
+
+/// The blocks the Snake shrinks by every time its head enters a hazard block.
+pub const HAZARD_DAMAGE_TICKS: u32 = 2;
+
+/// What an obstacle inside the border does to the Snake.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ObstacleKind {
+    /// Ends the run on contact, like the border.
+    Wall,
+    /// Takes `damage_ticks` blocks off the tail whenever the head enters it, ending the run once only the head is
+    /// left.
+    Hazard { damage_ticks: u32 },
+}
+
+/// A wall that opens once the Snake picks up its key.
+#[derive(Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Door {
+    pub position: Block,
+    pub key: Block,
+    pub open: bool,
+}
+
+/// The walls of a board and where the Snake starts.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Layout {
+    /// The walls inside the border.
+    pub walls: Vec<Block>,
+    pub start: Block,
+    /// Where food is placed at the start of a run, in order. Food spawns randomly when empty.
+    pub food: Vec<Block>,
+    /// The doors, all closed, each with its key.
+    pub doors: Vec<Door>,
+    /// The blocks that shrink the Snake instead of ending the run, see `ObstacleKind::Hazard`.
+    pub hazards: Vec<Block>,
+}
+
+/// Everything that can be wrong with an ASCII layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// A character other than `#`, `S`, `F`, `D`, `K`, `^` or `.`, with its 1-based line and column.
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    /// The layout does not match the board, with both sizes as (width, height).
+    WrongSize {
+        expected: (i32, i32),
+        found: (i32, i32),
+    },
+    /// The number of doors and keys differ.
+    UnpairedDoors { doors: usize, keys: usize },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::InvalidCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "invalid character '{}' at line {}, column {}",
+                character, line, column
+            ),
+            LayoutError::WrongSize { expected, found } => write!(
+                f,
+                "the layout is {}x{} but the board is {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            LayoutError::UnpairedDoors { doors, keys } => {
+                write!(f, "the layout has {} doors but {} keys", doors, keys)
+            }
+        }
+    }
+}
+
+impl Layout {
+    /// Parse an ASCII map of the board, border included, where `#` is a wall, `S` the Snake start, `F` a starting
+    /// food position, `D` a door, `K` a key, `^` a hazard block and `.` empty. Doors and keys are paired up in reading
+    /// order.
+    /// # Arguments
+    /// * `ascii: &str` - The map, one line per row.
+    /// * `width: i32` - The board width in blocks, including the borders.
+    /// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+    /// # Returns
+    /// * `Result<Layout, LayoutError>` - The layout, or where the map is invalid.
+    pub fn parse(ascii: &str, width: i32, height: i32) -> Result<Layout, LayoutError> {
+        let lines: Vec<&str> = ascii.lines().map(str::trim_end).collect();
+        let mut layout = Layout {
+            walls: Vec::new(),
+            start: DEFAULT_START,
+            food: Vec::new(),
+            doors: Vec::new(),
+            hazards: Vec::new(),
+        };
+        let (mut doors, mut keys) = (Vec::new(), Vec::new());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, character) in line.chars().enumerate() {
+                let block = Block::new(x as i32, y as i32);
+                match character {
+                    WALL if !block.out_of_bounds([0, width], [0, height]) => {
+                        layout.walls.push(block)
+                    }
+                    WALL | EMPTY => (),
+                    START => layout.start = block,
+                    FOOD => layout.food.push(block),
+                    DOOR => doors.push(block),
+                    KEY => keys.push(block),
+                    HAZARD => layout.hazards.push(block),
+                    _ => {
+                        return Err(LayoutError::InvalidCharacter {
+                            line: y + 1,
+                            column: x + 1,
+                            character,
+                        })
+                    }
+                }
+            }
+        }
+        let found_width = lines.iter().map(|line| line.chars().count()).max();
+        let found = (found_width.unwrap_or(0) as i32, lines.len() as i32);
+        let ragged = lines
+            .iter()
+            .any(|line| line.chars().count() as i32 != width);
+        if found != (width, height) || ragged {
+            return Err(LayoutError::WrongSize {
+                expected: (width, height),
+                found,
+            });
+        }
+        if doors.len() != keys.len() {
+            return Err(LayoutError::UnpairedDoors {
+                doors: doors.len(),
+                keys: keys.len(),
+            });
+        }
+        layout.doors = doors
+            .into_iter()
+            .zip(keys)
+            .map(|(position, key)| Door {
+                position,
+                key,
+                open: false,
+            })
+            .collect();
+        Ok(layout)
+    }
+}
+
+/// The layouts bundled with the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Level {
+    /// No walls besides the border.
+    #[default]
+    Open,
+    /// A ring of walls with an opening in every side.
+    Box,
+    /// A horizontal and a vertical wall through the center, which is left open around a hazard block.
+    Cross,
+    /// Nested rings, each open on the left.
+    Spiral,
+    /// Four rooms connected by doorways.
+    Rooms,
+    /// A random maze of corridors, generated anew for every run.
+    Maze,
+    /// A closed room in the center holding the first food, with a door that opens with a key outside.
+    Vault,
+}
+
+impl Level {
+    pub const ALL: [Level; 7] = [
+        Level::Open,
+        Level::Box,
+        Level::Cross,
+        Level::Spiral,
+        Level::Rooms,
+        Level::Maze,
+        Level::Vault,
+    ];
+
+    /// The locale key of the name of the level.
+    pub fn label_key(&self) -> &'static str {
+        match *self {
+            Level::Open => "level_open",
+            Level::Box => "level_box",
+            Level::Cross => "level_cross",
+            Level::Spiral => "level_spiral",
+            Level::Rooms => "level_rooms",
+            Level::Maze => "level_maze",
+            Level::Vault => "level_vault",
+        }
+    }
+
+    /// Draw the level as an ASCII map, scaled to the board.
+    /// # Arguments
+    /// * `width: i32` - The board width in blocks, including the borders.
+    /// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+    /// * `rng: &mut R` - The random number generator a maze is generated with. The presets do not use it.
+    /// # Returns
+    /// * `String` - The map, in the format `Layout::parse` reads.
+    pub fn ascii<R: Rng>(&self, width: i32, height: i32, rng: &mut R) -> String {
+        let maze = match *self {
+            Level::Maze => maze_walls(rng, width, height),
+            _ => HashSet::new(),
+        };
+        let (left, top, right, bottom) = (
+            PRESET_INSET,
+            PRESET_INSET,
+            width - 1 - PRESET_INSET,
+            height - 1 - PRESET_INSET,
+        );
+        let (center_x, center_y) = (width / 2, height / 2);
+        let is_wall = |x: i32, y: i32| -> bool {
+            let on_ring = |inset: i32| {
+                let (l, t, r, b) = (left + inset, top + inset, right - inset, bottom - inset);
+                ((y == t || y == b) && (l..=r).contains(&x))
+                    || ((x == l || x == r) && (t..=b).contains(&y))
+            };
+            match *self {
+                Level::Open => false,
+                Level::Maze => maze.contains(&Block::new(x, y)),
+                Level::Box => on_ring(1) && x != center_x && y != center_y,
+                Level::Vault => on_ring(1) && (x, y) != (center_x, top + 1),
+                Level::Cross => {
+                    let near_center = (x - center_x).abs() <= 1 && (y - center_y).abs() <= 1;
+                    !near_center
+                        && ((y == center_y && (left + 1..=right - 1).contains(&x))
+                            || (x == center_x && (top + 1..=bottom - 1).contains(&y)))
+                }
+                Level::Spiral => (0..)
+                    .map(|ring| ring * PRESET_INSET)
+                    .take_while(|inset| {
+                        right - left - 2 * inset >= PRESET_INSET
+                            && bottom - top - 2 * inset >= PRESET_INSET
+                    })
+                    .any(|inset| {
+                        on_ring(inset)
+                            && !(x == left + inset
+                                && y > top + inset
+                                && y < top + inset + PRESET_INSET)
+                    }),
+                Level::Rooms => {
+                    // The doorways line up with the start, so the Snake does not run into a wall straight away.
+                    let doorway_x = x == DEFAULT_START.x || x == width - 1 - DEFAULT_START.x;
+                    let doorway_y = y == DEFAULT_START.y || y == height - 1 - DEFAULT_START.y;
+                    (x == center_x && !doorway_y) || (y == center_y && !doorway_x)
+                }
+            }
+        };
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let vault = *self == Level::Vault;
+                        if x == 0 || y == 0 || x == width - 1 || y == height - 1 || is_wall(x, y) {
+                            WALL
+                        } else if Block::new(x, y) == DEFAULT_START {
+                            START
+                        } else if *self == Level::Cross && (x, y) == (center_x, center_y) {
+                            HAZARD
+                        } else if vault && (x, y) == (center_x, top + 1) {
+                            DOOR
+                        } else if vault && (x, y) == (center_x, center_y) {
+                            FOOD
+                        } else if vault
+                            && (x, y) == (width - 1 - DEFAULT_START.x, height - 1 - DEFAULT_START.y)
+                        {
+                            KEY
+                        } else {
+                            EMPTY
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Build the layout of the level on a board.
+    /// # Arguments
+    /// * `width: i32` - The board width in blocks, including the borders.
+    /// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+    /// * `rng: &mut R` - The random number generator a maze is generated with. The presets do not use it.
+    /// # Returns
+    /// * `Layout` - The walls of the level and the Snake start.
+    pub fn layout<R: Rng>(&self, width: i32, height: i32, rng: &mut R) -> Layout {
+        Layout::parse(&self.ascii(width, height, rng), width, height)
+            .expect("The preset layouts always match the board they are drawn for.")
+    }
+}
+
+/// Generate the walls of a maze with randomized Prim over a grid of rooms, each `MAZE_CORRIDOR_WIDTH` blocks wide
+/// and separated by single walls. Every pair of neighbouring rooms in the spanning tree is joined by removing the wall
+/// between them, so every free Block stays reachable and every corridor is wide enough to turn in.
+/// # Arguments
+/// * `rng: &mut R` - The random number generator to generate the maze with.
+/// * `width: i32` - The board width in blocks, including the borders.
+/// * `height: i32` - The board height in blocks, including the borders but excluding the score strip.
+/// # Returns
+/// * `HashSet<Block>` - The walls inside the border.
+fn maze_walls<R: Rng>(rng: &mut R, width: i32, height: i32) -> HashSet<Block> {
+    let pitch = MAZE_CORRIDOR_WIDTH + 1;
+    let (inner_width, inner_height) = (width - 2, height - 2);
+    let rooms = (
+        ((inner_width + 1) / pitch).max(1),
+        ((inner_height + 1) / pitch).max(1),
+    );
+    let neighbours = |(x, y): (i32, i32)| {
+        [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+            .into_iter()
+            .filter(move |(nx, ny)| (0..rooms.0).contains(nx) && (0..rooms.1).contains(ny))
+            .map(move |next| ((x, y), next))
+    };
+
+    // Growing the spanning tree from the top left room, which holds the start, through a random frontier room.
+    let mut visited = HashSet::from([(0, 0)]);
+    let mut frontier: Vec<((i32, i32), (i32, i32))> = neighbours((0, 0)).collect();
+    let mut passages = HashSet::new();
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        if visited.insert(to) {
+            passages.insert((from.min(to), from.max(to)));
+            frontier.extend(neighbours(to).filter(|(_, next)| !visited.contains(next)));
+        }
+    }
+
+    let mut walls = HashSet::new();
+    for x in 0..inner_width {
+        for y in 0..inner_height {
+            let room = ((x / pitch).min(rooms.0 - 1), (y / pitch).min(rooms.1 - 1));
+            let wall_x = x - room.0 * pitch == MAZE_CORRIDOR_WIDTH && room.0 < rooms.0 - 1;
+            let wall_y = y - room.1 * pitch == MAZE_CORRIDOR_WIDTH && room.1 < rooms.1 - 1;
+            let is_wall = match (wall_x, wall_y) {
+                // The corners between four rooms are never opened up.
+                (true, true) => true,
+                (true, false) => !passages.contains(&(room, (room.0 + 1, room.1))),
+                (false, true) => !passages.contains(&(room, (room.0, room.1 + 1))),
+                (false, false) => false,
+            };
+            let block = Block::new(x + 1, y + 1);
+            let near_start = (block.x - DEFAULT_START.x).abs() <= MAZE_START_CLEARANCE
+                && (block.y - DEFAULT_START.y).abs() <= MAZE_START_CLEARANCE;
+            if is_wall && !near_start {
+                walls.insert(block);
+            }
+        }
+    }
+    walls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BOARD_SIZES;
+    use crate::grid::is_connected;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn ascii_maps_are_parsed() {
+        let layout = Layout::parse("#####\n#S.F#\n#.#.#\n#####", 5, 4).unwrap();
+        assert!(layout.start == Block::new(1, 1));
+        assert!(layout.food == vec![Block::new(3, 1)]);
+        assert!(layout.walls == vec![Block::new(2, 2)]);
+    }
+
+    #[test]
+    fn invalid_maps_are_reported() {
+        assert_eq!(
+            Layout::parse("###\n#x#\n###", 3, 3).err(),
+            Some(LayoutError::InvalidCharacter {
+                line: 2,
+                column: 2,
+                character: 'x'
+            })
+        );
+        assert_eq!(
+            Layout::parse("###\n#.#\n###", 4, 3).err(),
+            Some(LayoutError::WrongSize {
+                expected: (4, 3),
+                found: (3, 3)
+            })
+        );
+        assert!(Layout::parse("###\n#.\n###", 3, 3).is_err());
+    }
+
+    #[test]
+    fn presets_fit_every_board_size() {
+        for level in Level::ALL {
+            for (width, height) in BOARD_SIZES {
+                // The score strip is not part of the layout.
+                let layout = level.layout(width, height - 1, &mut StdRng::seed_from_u64(0));
+                assert!(!layout.walls.contains(&layout.start));
+                // The Snake starts moving right along a free row.
+                assert!(!layout.walls.contains(&Block::new(3, 2)));
+                assert_eq!(layout.walls.is_empty(), level == Level::Open);
+            }
+        }
+    }
+
+    #[test]
+    fn mazes_have_wide_connected_corridors() {
+        for (width, height) in BOARD_SIZES {
+            let height = height - 1;
+            for seed in 0..20 {
+                let walls = maze_walls(&mut StdRng::seed_from_u64(seed), width, height);
+                let is_free = |block: Block| {
+                    !block.out_of_bounds([0, width], [0, height]) && !walls.contains(&block)
+                };
+                assert!(is_connected(DEFAULT_START, is_free, width, height));
+                // Every free Block is part of a free 2x2 square, so the Snake can turn anywhere.
+                for x in 1..width - 1 {
+                    for y in 1..height - 1 {
+                        if is_free(Block::new(x, y)) {
+                            assert!([(-1, -1), (-1, 0), (0, -1), (0, 0)].iter().any(|(dx, dy)| {
+                                (0..2).all(|i| {
+                                    (0..2).all(|j| is_free(Block::new(x + dx + i, y + dy + j)))
+                                })
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mazes_are_seeded() {
+        let generate = |seed| Level::Maze.layout(20, 19, &mut StdRng::seed_from_u64(seed));
+        assert!(generate(3) == generate(3));
+        assert!(generate(3) != generate(4));
+    }
+
+    #[test]
+    fn doors_are_paired_with_keys() {
+        let layout = Layout::parse("######\n#SD.K#\n######", 6, 3).unwrap();
+        assert!(!layout.walls.contains(&Block::new(2, 1)));
+        assert!(
+            layout.doors
+                == vec![Door {
+                    position: Block::new(2, 1),
+                    key: Block::new(4, 1),
+                    open: false
+                }]
+        );
+        assert_eq!(
+            Layout::parse("######\n#SD.D#\n######", 6, 3).err(),
+            Some(LayoutError::UnpairedDoors { doors: 2, keys: 0 })
+        );
+        // The vault keeps its food behind the door, for every board size.
+        for (width, height) in BOARD_SIZES {
+            let layout = Level::Vault.layout(width, height - 1, &mut StdRng::seed_from_u64(0));
+            assert_eq!(layout.doors.len(), 1);
+            assert_eq!(layout.food.len(), 1);
+            let door = layout.doors[0];
+            let outside = |block: Block| {
+                !block.out_of_bounds([0, width], [0, height - 1])
+                    && !layout.walls.contains(&block)
+                    && block != door.position
+            };
+            let reachable = crate::grid::flood_fill(layout.start, outside);
+            assert!(reachable.contains(&door.key));
+            assert!(!reachable.contains(&layout.food[0]));
+        }
+    }
+
+    #[test]
+    fn hazard_blocks_are_not_walls() {
+        let layout = Layout::parse("#####\n#S.^#\n#####", 5, 3).unwrap();
+        assert!(layout.hazards == vec![Block::new(3, 1)]);
+        assert!(layout.walls.is_empty());
+        // The cross keeps a hazard block in its open center, for every board size.
+        for (width, height) in BOARD_SIZES {
+            let layout = Level::Cross.layout(width, height - 1, &mut StdRng::seed_from_u64(0));
+            assert_eq!(layout.hazards.len(), 1);
+            assert!(!layout.walls.contains(&layout.hazards[0]));
+        }
+    }
+}
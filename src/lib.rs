@@ -0,0 +1,35 @@
+// Loading in local modules. Also provides linting in those files.
+pub mod audio;
+pub mod autosave;
+pub mod block;
+pub mod breakdown;
+pub mod cell;
+pub mod config;
+pub mod dateformat;
+pub mod direction;
+#[cfg(feature = "gui")]
+pub mod draw;
+pub mod event;
+#[cfg(feature = "gui")]
+pub mod font;
+pub mod food;
+pub mod framedump;
+pub mod game;
+pub mod grid;
+pub mod hazard;
+pub mod levels;
+pub mod locale;
+#[cfg(feature = "gui")]
+pub mod menu;
+pub mod recording;
+pub mod results;
+pub mod score;
+pub mod settings;
+#[cfg(feature = "gui")]
+pub mod settings_screen;
+pub mod snake;
+pub mod storage;
+pub mod textcache;
+pub mod theme;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,135 @@
+// External imports.
+use std::collections::HashMap;
+use std::path::Path;
+
+// Constants.
+pub const DEFAULT_LANGUAGE: &str = "en";
+/// The English strings are compiled in, so every key always has a fallback.
+const FALLBACK_STRINGS: &str = include_str!("../assets/locales/en.json");
+
+/// All user-facing strings of a single language, looked up by key.
+/// Strings can contain named placeholders like `{score}`, so the word order can differ per language.
+#[derive(Debug, Clone)]
+pub struct Locale {
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl Locale {
+    /// Load the strings of a language from the locales folder in an infallible way.
+    /// # Arguments
+    /// * `folder: &Path` - The folder containing a `<language>.json` file per language.
+    /// * `language: &str` - The language code, like `en` or `nl`.
+    /// # Returns
+    /// * `Locale` - The strings of the language, falling back to English for a missing file or keys.
+    pub fn load(folder: &Path, language: &str) -> Locale {
+        let strings = std::fs::read_to_string(folder.join(format!("{}.json", language)))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Locale {
+            strings,
+            ..Locale::default()
+        }
+    }
+
+    /// Look up a string, falling back to English and finally to the key itself.
+    /// # Arguments
+    /// * `key: &str` - The key of the string.
+    /// # Returns
+    /// * `&str` - The string in the current language.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    /// Look up a string and fill in its placeholders.
+    /// # Arguments
+    /// * `key: &str` - The key of the string.
+    /// * `args: &[(&str, &dyn ToString)]` - The placeholder names, without braces, and their values.
+    /// # Returns
+    /// * `String` - The string in the current language, with all given placeholders replaced.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn ToString)]) -> String {
+        args.iter()
+            .fold(self.get(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), &value.to_string())
+            })
+    }
+
+    /// Look up a string with a singular and a plural variant, and fill in its `{count}` placeholder.
+    /// # Arguments
+    /// * `key: &str` - The key of the string, without the `_one` or `_other` suffix of its variants.
+    /// * `count: i64` - The number the string is about.
+    /// # Returns
+    /// * `String` - The `_one` variant for a count of 1, otherwise the `_other` variant.
+    pub fn format_count(&self, key: &str, count: i64) -> String {
+        let suffix = if count == 1 { "one" } else { "other" };
+        self.format(&format!("{}_{}", key, suffix), &[("count", &count)])
+    }
+}
+
+impl Default for Locale {
+    /// The English strings.
+    fn default() -> Self {
+        Locale {
+            strings: HashMap::new(),
+            fallback: serde_json::from_str(FALLBACK_STRINGS).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locales() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/locales")
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english() {
+        let locale = Locale::load(&locales(), "does-not-exist");
+        assert_eq!(locale.get("paused"), "PAUSED");
+        assert_eq!(locale.get("no-such-key"), "no-such-key");
+    }
+
+    #[test]
+    fn placeholders_follow_the_language() {
+        let english = Locale::load(&locales(), DEFAULT_LANGUAGE);
+        let dutch = Locale::load(&locales(), "nl");
+        assert_eq!(
+            english.format("final_score_high", &[("score", &12)]),
+            "12 - HIGHSCORE"
+        );
+        assert_eq!(
+            dutch.format("final_score_high", &[("score", &12)]),
+            "RECORD: 12"
+        );
+    }
+
+    #[test]
+    fn counts_pick_the_plural_variant() {
+        let dutch = Locale::load(&locales(), "nl");
+        assert_eq!(dutch.format_count("time_days_ago", 1), "1 dag geleden");
+        assert_eq!(dutch.format_count("time_days_ago", 3), "3 dagen geleden");
+        assert_eq!(
+            Locale::default().format_count("time_weeks_ago", 2),
+            "2 weeks ago"
+        );
+    }
+
+    #[test]
+    fn every_language_has_all_keys() {
+        let english: HashMap<String, String> = serde_json::from_str(FALLBACK_STRINGS).unwrap();
+        for entry in std::fs::read_dir(locales()).unwrap() {
+            let data = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            let strings: HashMap<String, String> = serde_json::from_str(&data).unwrap();
+            for key in english.keys() {
+                assert!(strings.contains_key(key), "missing key {}", key);
+            }
+        }
+    }
+}
@@ -1,75 +1,438 @@
 #![windows_subsystem = "windows"]
 
-// Loading in local modules. Also provides linting in those files.
-mod block;
-mod dateformat;
-mod direction;
-mod draw;
-mod food;
-mod game;
-mod score;
-mod snake;
-
-use piston_window::types::Color;
-use piston_window::{clear, Button, PistonWindow, PressEvent, UpdateEvent, WindowSettings};
-use score::check_score;
+use piston_window::{
+    clear, AdvancedWindow, Button, CloseEvent, EventLoop, Key, PistonWindow, PressEvent,
+    Transformed, UpdateEvent, Window, WindowSettings,
+};
 use std::env;
+use std::path::PathBuf;
+use std::process;
 
-use draw::to_pixels;
-use game::Game;
+use rust_snake::audio::{Audio, Music, Sound};
+use rust_snake::autosave::{GameSnapshot, AUTOSAVE_INTERVAL};
+use rust_snake::block::Block;
+use rust_snake::config::GameConfig;
+use rust_snake::draw::{
+    draw_speaker_icon, draw_text_at_pixels, fit_font_size, to_pixels, MIN_FONT_SIZE,
+};
+use rust_snake::font::load_custom_glyphs;
+use rust_snake::framedump::FrameDump;
+use rust_snake::game::Game;
+use rust_snake::locale::Locale;
+use rust_snake::menu::{draw_resume_prompt, Menu, MenuAction};
+use rust_snake::recording::GifExport;
+use rust_snake::score;
+use rust_snake::settings::{step_volume, Settings};
+use rust_snake::settings_screen::SettingsScreen;
+use rust_snake::storage::FileStorage;
+use rust_snake::theme::load_themes;
 
-const BACK_COLOR: Color = [0.5, 0.5, 0.5, 1.0];
 const ASSETS_FOLDER: &str = "assets";
 const ASSETS_FONT_NAME: &str = "joystix.monospace-regular.otf";
-const ASSETS_SCORE_NAME: &str = "scores.json";
+const ASSETS_SETTINGS_NAME: &str = "settings.json";
+const ASSETS_AUTOSAVE_NAME: &str = "autosave.json";
+const ASSETS_SOUNDS_FOLDER: &str = "sounds";
+const ASSETS_MUSIC_FOLDER: &str = "music";
+const ASSETS_LOCALES_FOLDER: &str = "locales";
+const ASSETS_THEMES_FOLDER: &str = "themes";
+const ASSETS_RECORDINGS_FOLDER: &str = "recordings";
+
+/// Calculate the window size in pixels.
+/// # Arguments
+/// * `width: i32` - The window width in blocks.
+/// * `height: i32` - The window height in blocks.
+/// * `scale: f64` - The factor everything is scaled up by.
+/// # Returns
+/// * `[u32; 2]` - The window width and height in pixels.
+fn window_size(width: i32, height: i32, scale: f64) -> [u32; 2] {
+    [
+        (to_pixels(width) * scale) as u32,
+        (to_pixels(height) * scale) as u32,
+    ]
+}
 
 fn main() {
     env::set_var("RUST_BACKTRACE", "1");
+    #[cfg(feature = "logging")]
+    env_logger::init();
+    // Loading the settings.
+    // Without an assets folder the game still runs, with the embedded font and default settings.
+    let assets = find_folder::Search::ParentsThenKids(3, 3)
+        .for_folder(ASSETS_FOLDER)
+        .unwrap_or_else(|_| PathBuf::from(ASSETS_FOLDER));
+    let settings_file = &assets.join(ASSETS_SETTINGS_NAME);
+    let mut settings = Settings::load(settings_file);
+    // Falling back to the default game options if the settings file holds ones the game cannot be played with.
+    if let Err(error) = settings.game.validate() {
+        eprintln!("Invalid settings, {error}. Using the default game options.");
+        settings.game = GameConfig::default();
+    }
+    settings.themes = load_themes(&assets.join(ASSETS_THEMES_FOLDER));
+    settings.select_theme(settings.game.theme_file.clone());
+
+    // Printing the high scores of the configured board with --show-scores, without opening a window.
+    if env::args().any(|arg| arg == "--show-scores") {
+        let scores = score::parse_scores(
+            &FileStorage::new(&assets),
+            &score::scores_file_name(
+                (settings.game.width, settings.game.height),
+                settings.game.survival,
+            ),
+        );
+        print!(
+            "{}",
+            score::scores_to_text(&scores, settings.game.utc_timestamps)
+        );
+        return;
+    }
+
     // Creating a PistonWindow.
-    let (width, height) = (20, 20);
-    let mut window: PistonWindow =
-        WindowSettings::new("Snake", [to_pixels(width) as u32, to_pixels(height) as u32])
-            .exit_on_esc(true)
-            .build()
-            .unwrap();
+    let mut window: PistonWindow = WindowSettings::new(
+        "Snake",
+        window_size(
+            settings.game.width,
+            settings.game.height,
+            settings.game.render_scale(),
+        ),
+    )
+    .exit_on_esc(true)
+    .build()
+    .unwrap();
+    window.set_max_fps(settings.max_fps);
 
     // Loading text assets.
-    let assets = find_folder::Search::ParentsThenKids(3, 3)
-        .for_folder(ASSETS_FOLDER)
-        .unwrap();
-    let font = &assets.join(ASSETS_FONT_NAME);
-    let mut glyphs = window.load_font(font).unwrap();
+    // Joining keeps an absolute font path as it is, and resolves a relative one against the assets folder.
+    let custom_font = settings.font.as_ref().map(|font| assets.join(font));
+    let mut glyphs = match load_custom_glyphs(
+        custom_font.as_deref(),
+        &assets.join(ASSETS_FONT_NAME),
+        || window.create_texture_context(),
+    ) {
+        Ok(glyphs) => glyphs,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+    };
+
+    let locale = Locale::load(&assets.join(ASSETS_LOCALES_FOLDER), &settings.language);
 
     // Loading current high-scores
-    let scores_file = &assets.join(ASSETS_SCORE_NAME);
-    let mut scores = score::parse_scores(scores_file);
+    let mut storage = FileStorage::new(&assets);
+    let mut scores = score::parse_scores(
+        &storage,
+        &score::scores_file_name(
+            (settings.game.width, settings.game.height),
+            settings.game.survival,
+        ),
+    );
+    // Loading sound effects and music, which can also be silenced for a single session with the --mute argument.
+    let mut audio = Audio::new(
+        &assets.join(ASSETS_SOUNDS_FOLDER),
+        &assets.join(ASSETS_MUSIC_FOLDER),
+        settings.muted || env::args().any(|arg| arg == "--mute"),
+    );
+    audio.set_effects_volume(settings.effects_volume);
+    audio.set_music_volume(settings.music_volume);
+    // Score files are written on this runtime in the background, which waits for them when dropped on exit.
+    #[cfg(feature = "async")]
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    #[cfg(feature = "async")]
+    let _runtime_guard = runtime.enter();
     // Starting the main loop.
-    let mut game = Game::new(width, height, None, None);
+    let mut game = Game::new(settings.game.clone());
+    // Showing the controls on the first launch, when there are no settings yet.
+    if settings.show_hints {
+        game.show_hints();
+    }
+    // The window is resized whenever the board size or the render scale changes.
+    let mut window_layout = (game.size(), game.render_scale());
+    let mut scores_mode = (game.size(), game.survival());
+    let mut settings_screen: Option<SettingsScreen> = None;
+    // Starting in the main menu, over a static board.
+    let mut menu = Some(Menu::new());
+    // The GIF of the last finished run, while it is being or has been exported.
+    let mut gif_export: Option<GifExport> = None;
+    // An autosave only survives a crash, as clean exits delete it. Offering to continue it before the menu.
+    let autosave_file = &assets.join(ASSETS_AUTOSAVE_NAME);
+    let mut resume_prompt = GameSnapshot::load(autosave_file);
+    let mut autosave_timer = 0.0;
+    let mut autosave_speed_level = game.speed_level();
+    // Whether the current run has an autosave, which is deleted once the run is over.
+    let mut autosaved = false;
+    // Dumping what the game logic sees on every tick with --dump-frames DIR, for bug reports.
+    let mut frame_dump = env::args()
+        .skip_while(|arg| arg != "--dump-frames")
+        .nth(1)
+        .map(|folder| {
+            FrameDump::new(PathBuf::from(folder)).unwrap_or_else(|error| {
+                eprintln!("Cannot dump frames, {error}.");
+                process::exit(1);
+            })
+        });
     while let Some(event) = window.next() {
         // Checking if this score beats any other.
-        if game.game_over() && !game.high_score {
-            game.high_score = check_score(game.score(), &scores).is_some();
-        }
+        game.update_high_score(&scores);
         // Catching game events corresponding to button presses. Handling in-game logic.
         if let Some(Button::Keyboard(k)) = event.press_args() {
+            // Any key dismisses the controls overlay, which is not shown at launch anymore afterwards.
+            if game.hints_shown() {
+                game.key_pressed(k);
+                if settings.show_hints {
+                    settings.show_hints = false;
+                    settings.save(settings_file).unwrap_or_default();
+                }
+                continue;
+            }
+            // Answering whether to continue the autosaved run with <Y> or <N>, ignoring all other keys.
+            if let Some(snapshot) = resume_prompt.take() {
+                match k {
+                    Key::Y => {
+                        game.restore(snapshot);
+                        autosaved = true;
+                        autosave_speed_level = game.speed_level();
+                        menu = None;
+                    }
+                    Key::N => GameSnapshot::remove(autosave_file),
+                    _ => resume_prompt = Some(snapshot),
+                }
+                continue;
+            }
+            // The settings screen takes all keys while open. Changes are saved on exit, and only those to the look and the
+            // accessibility options apply to the running game, the others from the next run on.
+            if let Some(screen) = &mut settings_screen {
+                if screen.key_pressed(k, &mut settings) {
+                    settings_screen = None;
+                    settings.save(settings_file).unwrap_or_default();
+                }
+                game.set_config(settings.game.clone());
+                audio.set_effects_volume(settings.effects_volume);
+                audio.set_music_volume(settings.music_volume);
+                window.set_max_fps(settings.max_fps);
+                continue;
+            }
+            // The main menu takes all keys while open.
+            if let Some(open_menu) = &mut menu {
+                match open_menu.key_pressed(k, &mut settings) {
+                    Some(MenuAction::Play) => {
+                        menu = None;
+                        game.set_config(settings.game.clone());
+                        game.restart();
+                    }
+                    Some(MenuAction::OpenSettings) => settings_screen = Some(SettingsScreen::new()),
+                    Some(MenuAction::Quit) => window.set_should_close(true),
+                    None => (),
+                }
+                if k == Key::Return {
+                    audio.play(Sound::MenuSelect);
+                }
+                settings.save(settings_file).unwrap_or_default();
+                continue;
+            }
+            // Returning to the main menu after a game over instead of restarting, if preferred.
+            if k == Key::Space && game.game_over() && settings.menu_after_game_over {
+                game.key_pressed(k);
+                menu = Some(Menu::new());
+                continue;
+            }
+            // Opening the settings screen with <O> from the pause screen.
+            if k == Key::O && game.paused() {
+                settings_screen = Some(SettingsScreen::new());
+                continue;
+            }
+            // Showing the controls overlay on demand with <F1>.
+            if k == Key::F1 {
+                game.show_hints();
+                continue;
+            }
+            // Exporting the finished run as a GIF with <G>, unless it is typed as part of a name.
+            if k == Key::G && game.game_over() && !game.entering_name() && gif_export.is_none() {
+                let file = chrono::Local::now()
+                    .format("snake-%Y%m%d-%H%M%S.gif")
+                    .to_string();
+                gif_export = Some(GifExport::start(
+                    game.recording().clone(),
+                    game.theme().clone(),
+                    settings.game.gif_max_frames,
+                    assets.join(ASSETS_RECORDINGS_FOLDER).join(file),
+                ));
+                continue;
+            }
+            // Toggling mute with <M>, unless it is typed as part of a name.
+            if k == Key::M && !game.entering_name() {
+                settings.muted = !audio.muted();
+                audio.set_muted(settings.muted);
+                settings.save(settings_file).unwrap_or_default();
+            }
+            // Toggling high contrast mode with <F7>, resizing the window to fit the larger elements.
+            if k == Key::F7 {
+                settings.game.high_contrast = !settings.game.high_contrast;
+                game.set_high_contrast(settings.game.high_contrast);
+                settings.save(settings_file).unwrap_or_default();
+            }
+            // Cycling through the color palettes with <F8>.
+            if k == Key::F8 {
+                settings.game.palette = settings.game.palette.next();
+                game.set_palette(settings.game.palette);
+                settings.save(settings_file).unwrap_or_default();
+            }
             game.key_pressed(k);
-            game.ask_name(k, &mut scores, scores_file);
+            game.ask_name(k, &mut scores, &mut storage);
+            // Adjusting the volumes with <F9>/<F10> for effects and <F11>/<F12> for music.
+            if let Some((music, up)) = match k {
+                Key::F9 => Some((false, false)),
+                Key::F10 => Some((false, true)),
+                Key::F11 => Some((true, false)),
+                Key::F12 => Some((true, true)),
+                _ => None,
+            } {
+                if music {
+                    settings.music_volume = step_volume(settings.music_volume, up);
+                    audio.set_music_volume(settings.music_volume);
+                } else {
+                    settings.effects_volume = step_volume(settings.effects_volume, up);
+                    audio.set_effects_volume(settings.effects_volume);
+                    audio.play(Sound::MenuSelect);
+                }
+                // Losing a volume preference is not worth interrupting the game for.
+                settings.save(settings_file).unwrap_or_default();
+            }
         };
         // Passing _ as OpenGL Device.
         window.draw_2d(&event, |con, g, device| {
             // Clearing the window abd drawing a new one.
-            clear(BACK_COLOR, g);
+            clear(game.theme().background, g);
+            // Scaling everything, including the text, keeps the layout identical at any size.
+            let con = con.scale(game.render_scale(), game.render_scale());
             game.draw(
                 //&scores,
                 &mut glyphs,
                 &con,
                 g,
                 &scores,
+                &locale,
             );
+            // Showing the mute state in the corner of the score strip.
+            draw_speaker_icon(
+                Block::new(0, game.size().1 - 1),
+                audio.muted(),
+                game.theme().hud,
+                game.theme().hud_accent,
+                &con,
+                g,
+            );
+            // The controls overlay stays visible on top of the menu.
+            if resume_prompt.is_some() {
+                draw_resume_prompt(&game, &locale, &mut glyphs, &con, g);
+            } else if let (Some(open_menu), false) = (&menu, game.hints_shown()) {
+                open_menu.draw(&settings, &game, &scores, &locale, &mut glyphs, &con, g);
+            }
+            if let Some(screen) = &settings_screen {
+                screen.draw(&settings, &game, &locale, &mut glyphs, &con, g);
+            }
+            // Showing the GIF export state right above the bottom border of the game over screen.
+            if game.game_over() && menu.is_none() && !game.hints_shown() {
+                let text = match gif_export
+                    .as_mut()
+                    .map(|export| (export.progress(), export.poll()))
+                {
+                    None => locale.get("gif_export").to_string(),
+                    Some((progress, None)) => locale.format(
+                        "gif_exporting",
+                        &[("percent", &((progress * 100.0) as u32))],
+                    ),
+                    Some((_, Some(Ok(path)))) => locale.format(
+                        "gif_saved",
+                        &[(
+                            "file",
+                            &path.file_name().unwrap_or_default().to_string_lossy(),
+                        )],
+                    ),
+                    Some((_, Some(Err(error)))) => locale.format("gif_failed", &[("error", error)]),
+                };
+                let (width, height) = game.size();
+                let font_size =
+                    fit_font_size(&text, MIN_FONT_SIZE, to_pixels(width - 2), &mut glyphs);
+                draw_text_at_pixels(
+                    &text,
+                    to_pixels(1),
+                    to_pixels(height - 2) - f64::from(font_size) * 1.25,
+                    game.theme().overlay_text,
+                    font_size,
+                    &mut glyphs,
+                    &con,
+                    g,
+                );
+            }
             // Clearing the glyphs buffer at the end of the frame drawing.
             glyphs.factory.encoder.flush(device);
         });
         // Update event with anonymous function closure.
-        event.update(|arg| game.update(arg.dt));
+        if let (Some(args), None) = (event.update_args(), &menu) {
+            game.update(args.dt);
+            if let Some(dump) = &mut frame_dump {
+                dump.update(&game).unwrap_or_else(|error| {
+                    eprintln!("Could not dump a frame: {error}");
+                });
+            }
+            // Autosaving right after a completed update, so a snapshot never captures half a tick.
+            if game.game_over() {
+                if autosaved {
+                    GameSnapshot::remove(autosave_file);
+                    autosaved = false;
+                }
+            } else if !game.paused() && !game.hints_shown() {
+                autosave_timer += args.dt;
+                if autosave_timer >= AUTOSAVE_INTERVAL || game.speed_level() != autosave_speed_level
+                {
+                    autosave_timer = 0.0;
+                    autosave_speed_level = game.speed_level();
+                    autosaved = game.snapshot().save(autosave_file).is_ok();
+                }
+            }
+        }
+        // A new run can be exported again once it is over.
+        if !game.game_over()
+            && gif_export
+                .as_mut()
+                .is_some_and(|export| export.poll().is_some())
+        {
+            gif_export = None;
+        }
+        // Playing sounds for everything that happened during this event.
+        for game_event in game.drain_events() {
+            audio.play(Sound::for_event(game_event));
+        }
+        audio.play_music(if game.game_over() {
+            Music::GameOver
+        } else {
+            Music::Game
+        });
+        audio.pause_music(game.paused());
+        // Resizing the window after a board size or high contrast change.
+        let layout = (game.size(), game.render_scale());
+        if layout != window_layout {
+            window_layout = layout;
+            let ((width, height), scale) = layout;
+            window.set_size(window_size(width, height, scale));
+        }
+        // Every board size and scoring mode has its own high scores.
+        if (game.size(), game.survival()) != scores_mode {
+            scores_mode = (game.size(), game.survival());
+            scores = score::parse_scores(
+                &storage,
+                &score::scores_file_name(scores_mode.0, scores_mode.1),
+            );
+        }
+        // Never lose a high score by closing the window during name entry.
+        if event.close_args().is_some() {
+            game.write_pending_score(&mut scores, &mut storage);
+        }
     }
+    // Closing with <ESC> ends the loop without a close event.
+    game.write_pending_score(&mut scores, &mut storage);
+    // A clean exit leaves nothing to resume.
+    GameSnapshot::remove(autosave_file);
 }
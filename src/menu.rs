@@ -0,0 +1,307 @@
+// External imports.
+use piston_window::{Context, G2d, Glyphs, Key};
+
+// Local imports.
+use crate::block::Block;
+use crate::draw::{draw_rectangle, draw_text, fit_font_size, show_scores, to_pixels};
+use crate::game::Game;
+use crate::locale::Locale;
+use crate::score::Score;
+use crate::settings::Settings;
+use crate::settings_screen::SettingsOption;
+
+// Constants.
+// The text sizes, relative to the block size.
+const MENU_FONT_RATIO: f64 = 0.96;
+const MENU_SCORES_FONT_RATIO: f64 = 0.6;
+
+/// Every entry of the main menu, from top to bottom.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MenuEntry {
+    Play,
+    Difficulty,
+    Level,
+    BoardSize,
+    Settings,
+    HighScores,
+    Quit,
+}
+
+impl MenuEntry {
+    pub const ALL: [MenuEntry; 7] = [
+        MenuEntry::Play,
+        MenuEntry::Difficulty,
+        MenuEntry::Level,
+        MenuEntry::BoardSize,
+        MenuEntry::Settings,
+        MenuEntry::HighScores,
+        MenuEntry::Quit,
+    ];
+
+    /// The locale key of the name of the entry.
+    pub fn label_key(&self) -> &'static str {
+        match *self {
+            MenuEntry::Play => "menu_play",
+            MenuEntry::Difficulty => "option_difficulty",
+            MenuEntry::Level => "option_level",
+            MenuEntry::BoardSize => "option_board_size",
+            MenuEntry::Settings => "menu_settings",
+            MenuEntry::HighScores => "menu_high_scores",
+            MenuEntry::Quit => "menu_quit",
+        }
+    }
+
+    /// The setting the entry changes in place, if any.
+    pub fn option(&self) -> Option<SettingsOption> {
+        match *self {
+            MenuEntry::Difficulty => Some(SettingsOption::Difficulty),
+            MenuEntry::Level => Some(SettingsOption::Level),
+            MenuEntry::BoardSize => Some(SettingsOption::BoardSize),
+            _ => None,
+        }
+    }
+}
+
+/// Everything the main menu can ask of the rest of the game.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Close the menu and start a new game.
+    Play,
+    /// Open the settings screen on top of the menu.
+    OpenSettings,
+    /// Close the window.
+    Quit,
+}
+
+/// The main menu, shown at launch on top of the static board.
+#[derive(Debug, Default)]
+pub struct Menu {
+    /// The index of the highlighted entry in `MenuEntry::ALL`.
+    selected: usize,
+    /// Whether the high score table is shown instead of the entries.
+    showing_scores: bool,
+}
+
+impl Menu {
+    pub fn new() -> Menu {
+        Menu::default()
+    }
+
+    pub fn selected(&self) -> MenuEntry {
+        MenuEntry::ALL[self.selected]
+    }
+
+    /// React to a keypress: <UP>/<DOWN> select an entry and <RETURN> confirms it.
+    /// # Arguments
+    /// * `key: Key` - The key being pressed.
+    /// * `settings: &mut Settings` - The settings holding the difficulty and level, which are changed in place.
+    /// # Returns
+    /// * `Option<MenuAction>` - What the rest of the game should do, if anything.
+    pub fn key_pressed(&mut self, key: Key, settings: &mut Settings) -> Option<MenuAction> {
+        // Any key returns from the high score table.
+        if self.showing_scores {
+            self.showing_scores = false;
+            return None;
+        }
+        let count = MenuEntry::ALL.len();
+        match (key, self.selected()) {
+            (Key::Up, _) => self.selected = (self.selected + count - 1) % count,
+            (Key::Down, _) => self.selected = (self.selected + 1) % count,
+            (Key::Return, MenuEntry::Play) => return Some(MenuAction::Play),
+            (Key::Return, MenuEntry::Settings) => return Some(MenuAction::OpenSettings),
+            (Key::Return, MenuEntry::HighScores) => self.showing_scores = true,
+            (Key::Return, MenuEntry::Quit) => return Some(MenuAction::Quit),
+            (Key::Left | Key::Right | Key::Return, entry) => {
+                if let Some(option) = entry.option() {
+                    option.cycle(settings, key != Key::Left)
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    /// Draw the menu on top of a dimmed playing field.
+    /// # Arguments
+    /// * `settings: &Settings` - The settings holding the difficulty.
+    /// * `game: &Game` - The game drawn underneath, providing the colors and the board size.
+    /// * `scores: &[Score]` - The high scores, shown when selected.
+    /// * `locale: &Locale` - The strings of the language to draw all text in.
+    /// * `glyphs: &mut piston_window::Glyphs` - The characters to use for drawing.
+    /// * `con: &piston_window::Context` - The context in which to draw.
+    /// * `g: &mut G2d` - The 2d graphics driver to use.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &self,
+        settings: &Settings,
+        game: &Game,
+        scores: &[Score],
+        locale: &Locale,
+        glyphs: &mut Glyphs,
+        con: &Context,
+        g: &mut G2d,
+    ) {
+        let (width, height) = game.size();
+        let color = game.theme().overlay_text;
+        // Covering everything but the score strip.
+        draw_rectangle(
+            game.theme().game_over_overlay,
+            Block::new(0, 0),
+            width,
+            height - 1,
+            con,
+            g,
+        );
+        if self.showing_scores {
+            let font_size = fit_font_size(
+                locale.get("menu_high_scores"),
+                game.config().font_size(MENU_FONT_RATIO),
+                to_pixels(width - 2),
+                glyphs,
+            );
+            draw_text(
+                locale.get("menu_high_scores"),
+                Block::new(1, 1),
+                color,
+                font_size,
+                glyphs,
+                con,
+                g,
+            );
+            show_scores(
+                scores,
+                settings.game.timestamp_format,
+                settings.game.utc_timestamps,
+                locale,
+                Block::new(1, 3),
+                color,
+                game.config().font_size(MENU_SCORES_FONT_RATIO),
+                glyphs,
+                con,
+                g,
+            );
+            draw_text(
+                locale.get("menu_back"),
+                Block::new(1, height - 3),
+                color,
+                game.config().font_size(MENU_SCORES_FONT_RATIO),
+                glyphs,
+                con,
+                g,
+            );
+            return;
+        }
+        let mut text = format!("{}\n\n", locale.get("menu_title"));
+        for entry in MenuEntry::ALL {
+            let marker = if entry == self.selected() { ">" } else { " " };
+            let label = locale.get(entry.label_key());
+            text.push_str(&match entry.option() {
+                Some(option) => format!(
+                    "{} {}: {}\n",
+                    marker,
+                    label,
+                    option.value(settings, game.size(), locale)
+                ),
+                None => format!("{} {}\n", marker, label),
+            });
+        }
+        text.push('\n');
+        text.push_str(locale.get("scoring_rule"));
+        text.push_str("\n\n");
+        text.push_str(locale.get("menu_controls"));
+        let font_size = fit_font_size(
+            &text,
+            game.config().font_size(MENU_FONT_RATIO),
+            to_pixels(width - 2),
+            glyphs,
+        );
+        draw_text(&text, Block::new(1, 1), color, font_size, glyphs, con, g);
+    }
+}
+
+/// Draw the question whether to continue the autosaved run, shown at launch before the menu.
+/// # Arguments
+/// * `game: &Game` - The game drawn underneath, providing the colors and the board size.
+/// * `locale: &Locale` - The strings of the language to draw all text in.
+/// * `glyphs: &mut piston_window::Glyphs` - The characters to use for drawing.
+/// * `con: &piston_window::Context` - The context in which to draw.
+/// * `g: &mut G2d` - The 2d graphics driver to use.
+pub fn draw_resume_prompt(
+    game: &Game,
+    locale: &Locale,
+    glyphs: &mut Glyphs,
+    con: &Context,
+    g: &mut G2d,
+) {
+    let (width, height) = game.size();
+    draw_rectangle(
+        game.theme().game_over_overlay,
+        Block::new(0, 0),
+        width,
+        height - 1,
+        con,
+        g,
+    );
+    let text = locale.get("resume_prompt");
+    let font_size = fit_font_size(
+        text,
+        game.config().font_size(MENU_FONT_RATIO),
+        to_pixels(width - 2),
+        glyphs,
+    );
+    draw_text(
+        text,
+        Block::new(1, height / 2 - 1),
+        game.theme().overlay_text,
+        font_size,
+        glyphs,
+        con,
+        g,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Difficulty;
+
+    #[test]
+    fn entries_are_confirmed_with_return() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+        assert_eq!(
+            menu.key_pressed(Key::Return, &mut settings),
+            Some(MenuAction::Play)
+        );
+        menu.key_pressed(Key::Up, &mut settings);
+        assert_eq!(menu.selected(), MenuEntry::Quit);
+        assert_eq!(
+            menu.key_pressed(Key::Return, &mut settings),
+            Some(MenuAction::Quit)
+        );
+    }
+
+    #[test]
+    fn difficulty_changes_in_place() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+        menu.key_pressed(Key::Down, &mut settings);
+        assert_eq!(menu.key_pressed(Key::Right, &mut settings), None);
+        assert_eq!(settings.game.difficulty, Difficulty::Hard);
+        menu.key_pressed(Key::Left, &mut settings);
+        menu.key_pressed(Key::Left, &mut settings);
+        assert_eq!(settings.game.difficulty, Difficulty::Easy);
+    }
+
+    #[test]
+    fn high_scores_close_on_any_key() {
+        let mut menu = Menu::new();
+        let mut settings = Settings::default();
+        menu.selected = 5;
+        menu.key_pressed(Key::Return, &mut settings);
+        assert!(menu.showing_scores);
+        // The key closing the table does nothing else.
+        assert_eq!(menu.key_pressed(Key::Return, &mut settings), None);
+        assert!(!menu.showing_scores);
+    }
+}
@@ -0,0 +1,398 @@
+// External imports.
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+// Local imports.
+use crate::block::Block;
+use crate::food::FoodKind;
+use crate::theme::Theme;
+
+// Constants.
+/// The size of a single block in an exported GIF, in pixels.
+pub const GIF_BLOCK_SIZE: usize = 8;
+/// The time every recorded tick is shown in an exported GIF, in hundredths of a second.
+const GIF_FRAME_DELAY: u16 = 10;
+
+// Indices into the GIF palette.
+const BACKGROUND: u8 = 0;
+const BORDER: u8 = 1;
+const SNAKE_BODY: u8 = 2;
+const SNAKE_HEAD: u8 = 3;
+const FOOD: u8 = 4;
+const BONUS_FOOD: u8 = 5;
+
+/// The board at a single tick, stored as Blocks rather than pixels to keep recording cheap.
+#[derive(Clone, PartialEq)]
+pub struct Frame {
+    /// The Snake, head first.
+    pub snake: Vec<Block>,
+    pub food: Option<(Block, FoodKind)>,
+}
+
+/// Every tick of a single run, on a board of a fixed size.
+#[derive(Clone, Default, PartialEq)]
+pub struct Recording {
+    /// The board width in blocks, including the borders.
+    width: i32,
+    /// The board height in blocks, including the borders but excluding the score strip.
+    height: i32,
+    /// The walls of the level, which do not change during a run.
+    walls: Vec<Block>,
+    frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn new(width: i32, height: i32, walls: Vec<Block>) -> Recording {
+        Recording {
+            width,
+            height,
+            walls,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Pick the frames to export, skipping ticks evenly for runs that are too long.
+    /// # Arguments
+    /// * `max_frames: usize` - The maximum number of frames to pick.
+    /// # Returns
+    /// * `Vec<&Frame>` - The picked frames in order, always ending on the last tick.
+    pub fn sampled(&self, max_frames: usize) -> Vec<&Frame> {
+        if self.frames.len() <= max_frames.max(1) {
+            return self.frames.iter().collect();
+        }
+        let step = self.frames.len().div_ceil(max_frames.max(1));
+        let mut frames: Vec<&Frame> = self.frames.iter().rev().step_by(step).collect();
+        frames.reverse();
+        frames
+    }
+}
+
+/// A pixel buffer independent of any graphics backend, by default of palette indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster<P = u8> {
+    width: usize,
+    height: usize,
+    pixels: Vec<P>,
+}
+
+impl Raster {
+    /// Create a raster filled with the background.
+    pub fn new(width: usize, height: usize) -> Raster {
+        Raster::filled(width, height, BACKGROUND)
+    }
+}
+
+impl<P: Copy> Raster<P> {
+    /// Create a raster with every pixel set to the same value.
+    /// # Arguments
+    /// * `width: usize` - The width in pixels.
+    /// * `height: usize` - The height in pixels.
+    /// * `pixel: P` - The value of every pixel.
+    pub fn filled(width: usize, height: usize, pixel: P) -> Raster<P> {
+        Raster {
+            width,
+            height,
+            pixels: vec![pixel; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The value of every pixel, row by row.
+    pub fn pixels(&self) -> &[P] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> P {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Set a single pixel, ignoring pixels outside the raster.
+    /// # Arguments
+    /// * `x: usize` - The column of the pixel.
+    /// * `y: usize` - The row of the pixel.
+    /// * `pixel: P` - The new value.
+    pub fn set_pixel(&mut self, x: usize, y: usize, pixel: P) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = pixel;
+        }
+    }
+
+    /// Fill the square covered by a Block, clipping anything outside the raster.
+    /// # Arguments
+    /// * `block: Block` - The Block to fill.
+    /// * `block_size: usize` - The size of a Block in pixels.
+    /// * `pixel: P` - The value to fill with, like a palette index.
+    pub fn fill_block(&mut self, block: Block, block_size: usize, pixel: P) {
+        if block.x < 0 || block.y < 0 {
+            return;
+        }
+        let (left, top) = (block.x as usize * block_size, block.y as usize * block_size);
+        for y in top..(top + block_size).min(self.height) {
+            for x in left..(left + block_size).min(self.width) {
+                self.pixels[y * self.width + x] = pixel;
+            }
+        }
+    }
+}
+
+/// Draw a single recorded tick into a raster.
+/// # Arguments
+/// * `frame: &Frame` - The tick to draw.
+/// * `width: i32` - The board width in blocks.
+/// * `height: i32` - The board height in blocks.
+/// * `walls: &[Block]` - The walls of the level, drawn like the border.
+/// * `block_size: usize` - The size of a Block in pixels.
+/// # Returns
+/// * `Raster` - The board, with its borders and walls, the food and the Snake.
+pub fn render_frame(
+    frame: &Frame,
+    width: i32,
+    height: i32,
+    walls: &[Block],
+    block_size: usize,
+) -> Raster {
+    let mut raster = Raster::new(width as usize * block_size, height as usize * block_size);
+    for x in 0..width {
+        raster.fill_block(Block::new(x, 0), block_size, BORDER);
+        raster.fill_block(Block::new(x, height - 1), block_size, BORDER);
+    }
+    for y in 0..height {
+        raster.fill_block(Block::new(0, y), block_size, BORDER);
+        raster.fill_block(Block::new(width - 1, y), block_size, BORDER);
+    }
+    for wall in walls {
+        raster.fill_block(*wall, block_size, BORDER);
+    }
+    if let Some((food, kind)) = frame.food {
+        let index = match kind {
+            FoodKind::Normal => FOOD,
+            FoodKind::Bonus => BONUS_FOOD,
+        };
+        raster.fill_block(food, block_size, index);
+    }
+    // Drawing the head last, so it stays visible when it overlaps the tail.
+    for block in frame.snake.iter().skip(1) {
+        raster.fill_block(*block, block_size, SNAKE_BODY);
+    }
+    if let Some(head) = frame.snake.first() {
+        raster.fill_block(*head, block_size, SNAKE_HEAD);
+    }
+    raster
+}
+
+/// Build the GIF color table matching the palette indices of a raster.
+/// # Arguments
+/// * `theme: &Theme` - The colors the game is drawn with.
+/// # Returns
+/// * `Vec<u8>` - The red, green and blue value of every palette index.
+fn gif_palette(theme: &Theme) -> Vec<u8> {
+    [
+        theme.background,
+        theme.border,
+        theme.snake_body,
+        theme.snake_head,
+        theme.food,
+        theme.bonus_food,
+    ]
+    .iter()
+    .flat_map(|color| {
+        color[..3]
+            .iter()
+            .map(|channel| (channel * 255.0).round() as u8)
+    })
+    .collect()
+}
+
+/// Encode a recording as an endlessly looping animated GIF.
+/// # Arguments
+/// * `recording: &Recording` - The run to encode.
+/// * `theme: &Theme` - The colors to draw the run with.
+/// * `max_frames: usize` - The maximum number of frames, beyond which ticks are skipped.
+/// * `writer: W` - Where to write the GIF to.
+/// * `progress: impl FnMut(usize, usize)` - Called with the number of encoded and total frames after every frame.
+/// # Returns
+/// * `Result<(), gif::EncodingError>` - Whether the GIF could be written.
+pub fn encode_gif<W: Write>(
+    recording: &Recording,
+    theme: &Theme,
+    max_frames: usize,
+    writer: W,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<(), gif::EncodingError> {
+    let width = (recording.width as usize * GIF_BLOCK_SIZE) as u16;
+    let height = (recording.height as usize * GIF_BLOCK_SIZE) as u16;
+    let mut encoder = gif::Encoder::new(writer, width, height, &gif_palette(theme))?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    let frames = recording.sampled(max_frames);
+    for (i, frame) in frames.iter().enumerate() {
+        let raster = render_frame(
+            frame,
+            recording.width,
+            recording.height,
+            &recording.walls,
+            GIF_BLOCK_SIZE,
+        );
+        let mut gif_frame = gif::Frame::from_indexed_pixels(width, height, raster.pixels, None);
+        gif_frame.delay = GIF_FRAME_DELAY;
+        encoder.write_frame(&gif_frame)?;
+        progress(i + 1, frames.len());
+    }
+    Ok(())
+}
+
+/// A GIF being encoded in the background, so the game keeps drawing its progress.
+pub struct GifExport {
+    /// The number of frames encoded so far.
+    encoded: Arc<AtomicUsize>,
+    total: usize,
+    handle: Option<JoinHandle<Result<PathBuf, String>>>,
+    result: Option<Result<PathBuf, String>>,
+}
+
+impl GifExport {
+    /// Start encoding a recording to a file.
+    /// # Arguments
+    /// * `recording: Recording` - The run to encode.
+    /// * `theme: Theme` - The colors to draw the run with.
+    /// * `max_frames: usize` - The maximum number of frames, beyond which ticks are skipped.
+    /// * `path: PathBuf` - The GIF file, of which missing parent folders are created.
+    /// # Returns
+    /// * `GifExport` - The running export.
+    pub fn start(
+        recording: Recording,
+        theme: Theme,
+        max_frames: usize,
+        path: PathBuf,
+    ) -> GifExport {
+        let encoded = Arc::new(AtomicUsize::new(0));
+        let total = recording.sampled(max_frames).len();
+        let counter = Arc::clone(&encoded);
+        let handle = thread::spawn(move || {
+            if let Some(folder) = path.parent() {
+                fs::create_dir_all(folder).map_err(|error| error.to_string())?;
+            }
+            let file = File::create(&path).map_err(|error| error.to_string())?;
+            encode_gif(
+                &recording,
+                &theme,
+                max_frames,
+                BufWriter::new(file),
+                |done, _| counter.store(done, Ordering::Relaxed),
+            )
+            .map_err(|error| error.to_string())?;
+            Ok(path)
+        });
+        GifExport {
+            encoded,
+            total,
+            handle: Some(handle),
+            result: None,
+        }
+    }
+
+    /// Get the fraction of the frames that has been encoded.
+    pub fn progress(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.encoded.load(Ordering::Relaxed) as f64 / self.total as f64
+        }
+    }
+
+    /// Check whether the export has finished, without waiting for it.
+    /// # Returns
+    /// * `Option<&Result<PathBuf, String>>` - The written file or an error message, or None while still encoding.
+    pub fn poll(&mut self) -> Option<&Result<PathBuf, String>> {
+        if self.handle.as_ref().is_some_and(JoinHandle::is_finished) {
+            let handle = self.handle.take().unwrap();
+            self.result = Some(
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(String::from("the encoder crashed"))),
+            );
+        }
+        self.result.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(x: i32) -> Frame {
+        Frame {
+            snake: vec![Block::new(x, 2), Block::new(x - 1, 2)],
+            food: Some((Block::new(4, 4), FoodKind::Bonus)),
+        }
+    }
+
+    #[test]
+    fn frames_are_drawn_block_by_block() {
+        let raster = render_frame(&frame(3), 6, 6, &[Block::new(2, 4)], 2);
+        assert_eq!((raster.width(), raster.height()), (12, 12));
+        assert_eq!(raster.pixel(0, 0), BORDER);
+        assert_eq!(raster.pixel(11, 11), BORDER);
+        assert_eq!(raster.pixel(2, 2), BACKGROUND);
+        assert_eq!(raster.pixel(6, 4), SNAKE_HEAD);
+        assert_eq!(raster.pixel(5, 5), SNAKE_BODY);
+        assert_eq!(raster.pixel(9, 9), BONUS_FOOD);
+        assert_eq!(raster.pixel(4, 9), BORDER);
+    }
+
+    #[test]
+    fn long_runs_skip_ticks() {
+        let mut recording = Recording::new(10, 10, Vec::new());
+        for x in 0..10 {
+            recording.record(frame(x));
+        }
+        assert_eq!(recording.sampled(20).len(), 10);
+        let sampled = recording.sampled(4);
+        assert!(sampled.len() <= 4);
+        // The final tick is always shown.
+        assert!(*sampled[sampled.len() - 1] == frame(9));
+        assert!(*sampled[0] == frame(0));
+    }
+
+    #[test]
+    fn recordings_encode_as_gif() {
+        let mut recording = Recording::new(6, 6, Vec::new());
+        for x in 2..5 {
+            recording.record(frame(x));
+        }
+        let mut reported = Vec::new();
+        let mut data = Vec::new();
+        encode_gif(
+            &recording,
+            &Theme::default(),
+            2,
+            &mut data,
+            |done, total| reported.push((done, total)),
+        )
+        .unwrap();
+        assert!(data.starts_with(b"GIF89a"));
+        assert_eq!(reported, vec![(1, 2), (2, 2)]);
+    }
+}
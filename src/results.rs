@@ -0,0 +1,136 @@
+// External imports.
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+// Local imports.
+use crate::event::DeathCause;
+use crate::game::Game;
+
+/// The outcome of a single finished run, for comparing headless runs afterwards.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunResult {
+    pub score: i32,
+    pub length: i32,
+    /// What the Snake ran into, or None if the run was stopped before the game was over.
+    pub death_cause: Option<DeathCause>,
+    /// The number of moves the Snake made.
+    pub ticks: u32,
+    /// The seed the run was played with, if it was seeded.
+    pub seed: Option<u64>,
+    /// The final board as plain text, a line per row followed by the status line.
+    pub board: Vec<String>,
+}
+
+impl RunResult {
+    /// Collect the outcome of a run.
+    /// # Arguments
+    /// * `game: &Game` - The run, usually after the game is over.
+    pub fn new(game: &Game) -> RunResult {
+        RunResult {
+            score: game.score(),
+            length: game.snake_length(),
+            death_cause: game.death_cause(),
+            ticks: game.snake_moves(),
+            seed: game.config().seed,
+            board: game.to_string().lines().map(String::from).collect(),
+        }
+    }
+}
+
+/// Numbered JSON files of finished runs in a folder, e.g. `run-00000.json`. Existing files are never overwritten.
+pub struct ResultsWriter {
+    folder: PathBuf,
+    /// The lowest score a run needs to be written, to keep long simulations from writing thousands of files.
+    min_score: Option<i32>,
+    /// The number tried first for the next file.
+    next: usize,
+}
+
+impl ResultsWriter {
+    /// Start writing results into a folder, creating it if needed.
+    /// # Arguments
+    /// * `folder: PathBuf` - The folder to write the files to.
+    /// * `min_score: Option<i32>` - The lowest score a run needs to be written, or None to write every run.
+    pub fn new(folder: PathBuf, min_score: Option<i32>) -> io::Result<ResultsWriter> {
+        fs::create_dir_all(&folder)?;
+        Ok(ResultsWriter {
+            folder,
+            min_score,
+            next: 0,
+        })
+    }
+
+    /// Write the outcome of a run to the first free file number.
+    /// # Arguments
+    /// * `game: &Game` - The finished run.
+    /// # Returns
+    /// * `io::Result<Option<PathBuf>>` - The written file, or None if the score is below the threshold.
+    pub fn write(&mut self, game: &Game) -> io::Result<Option<PathBuf>> {
+        if self
+            .min_score
+            .is_some_and(|min_score| game.score() < min_score)
+        {
+            return Ok(None);
+        }
+        let serialized = serde_json::to_string_pretty(&RunResult::new(game))?;
+        loop {
+            let path = self.folder.join(format!("run-{:05}.json", self.next));
+            self.next += 1;
+            // Only creating new files, so results of earlier simulations in the same folder are kept.
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(serialized.as_bytes())?;
+                    return Ok(Some(path));
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+
+    #[test]
+    fn results_are_numbered_without_overwriting() {
+        let folder = std::env::temp_dir().join("rust-snake-results-test");
+        let _ = fs::remove_dir_all(&folder);
+        let mut game = Game::new(GameConfig {
+            seed: Some(7),
+            ..GameConfig::default()
+        });
+        while !game.game_over() {
+            game.tick();
+        }
+        let mut writer = ResultsWriter::new(folder.clone(), None).unwrap();
+        let first = writer.write(&game).unwrap().unwrap();
+        // A second writer on the same folder skips the file the first one wrote.
+        let mut writer = ResultsWriter::new(folder.clone(), None).unwrap();
+        let second = writer.write(&game).unwrap().unwrap();
+        assert_eq!(first, folder.join("run-00000.json"));
+        assert_eq!(second, folder.join("run-00001.json"));
+
+        let result: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&first).unwrap()).unwrap();
+        assert_eq!(result["death_cause"], "border");
+        assert_eq!(result["seed"], 7);
+        assert_eq!(result["board"][0], "#".repeat(game.size().0 as usize));
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn runs_below_the_threshold_are_skipped() {
+        let folder = std::env::temp_dir().join("rust-snake-results-threshold-test");
+        let _ = fs::remove_dir_all(&folder);
+        let game = Game::new(GameConfig::default());
+        let mut writer = ResultsWriter::new(folder.clone(), Some(1)).unwrap();
+        assert!(writer.write(&game).unwrap().is_none());
+        assert_eq!(fs::read_dir(&folder).unwrap().count(), 0);
+        fs::remove_dir_all(folder).unwrap();
+    }
+}
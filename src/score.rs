@@ -1,20 +1,60 @@
 // External imports.
-use crate::dateformat;
+use crate::config::{DEFAULT_HEIGHT, DEFAULT_WIDTH};
+use crate::dateformat::{self, DisplayFormat};
 use crate::game::Game;
+use crate::locale::Locale;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::io::{BufReader, Read, Write};
-use std::path::{Path, PathBuf};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Local imports.
+use crate::storage::Storage;
 
 // Constants.
 pub const NUMBER_HIGH_SCORES: usize = 10;
 pub const MAX_NAME_LENGTH: usize = 10;
+/// The score file of the default board.
+const SCORES_FILE_NAME: &str = "scores.json";
+
+/// The keys scores are ranked by, in order of importance: the total score, the base score and the longest eating
+/// streak.
+pub type RankKey = (i32, i32, u32);
 
+/// A high score as stored in the score files.
+///
+/// Score files written by older versions lack the fields added since, so every field besides the player, score and
+/// timestamp has a serde default. Fields this version does not know, e.g. from files written by newer versions, are
+/// ignored rather than rejected, so those files keep loading too.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Score {
     player: String,
     score: i32,
+    #[serde(default)]
+    base_score: i32,
+    #[serde(default)]
+    bonus_score: i32,
+    #[serde(default)]
+    max_eat_streak: u32,
+    /// The longest the Snake got during the run.
+    #[serde(default)]
+    peak_length: i32,
+    /// The number of foods eaten during the run, of any kind.
+    #[serde(default)]
+    foods_eaten: u32,
+    /// Whether the run was played in fog of war mode.
+    #[serde(default)]
+    fog_of_war: bool,
+    /// The board width and height the run was played on, unknown for scores from before it was recorded.
+    #[serde(default)]
+    board_size: Option<(i32, i32)>,
+    /// The speed level the run was capped at, unknown for scores from before it was recorded.
+    #[serde(default)]
+    max_speed_level: Option<i32>,
+    /// The time played during the run in seconds, unknown for scores from before it was recorded.
+    #[serde(default)]
+    duration: Option<u32>,
     #[serde(with = "dateformat")]
     timestamp: DateTime<Utc>,
 }
@@ -32,27 +72,87 @@ impl Score {
         self.score
     }
 
+    pub fn base_score(&self) -> i32 {
+        self.base_score
+    }
+
+    pub fn bonus_score(&self) -> i32 {
+        self.bonus_score
+    }
+
+    pub fn max_eat_streak(&self) -> u32 {
+        self.max_eat_streak
+    }
+
+    pub fn peak_length(&self) -> i32 {
+        self.peak_length
+    }
+
+    pub fn fog_of_war(&self) -> bool {
+        self.fog_of_war
+    }
+
+    pub fn board_size(&self) -> Option<(i32, i32)> {
+        self.board_size
+    }
+
+    pub fn max_speed_level(&self) -> Option<i32> {
+        self.max_speed_level
+    }
+
+    pub fn foods_eaten(&self) -> u32 {
+        self.foods_eaten
+    }
+
+    pub fn duration(&self) -> Option<u32> {
+        self.duration
+    }
+
     pub fn timestamp(&self) -> &DateTime<Utc> {
         &self.timestamp
     }
+
+    /// Get the keys this score is ranked by.
+    pub fn rank_key(&self) -> RankKey {
+        (self.score, self.base_score, self.max_eat_streak)
+    }
 }
 
-#[derive(Default)]
 pub struct ScoreBuilder {
     player: String,
     score: i32,
+    base_score: i32,
+    bonus_score: i32,
+    max_eat_streak: u32,
+    peak_length: i32,
+    foods_eaten: u32,
+    fog_of_war: bool,
+    board_size: Option<(i32, i32)>,
+    max_speed_level: Option<i32>,
+    duration: Option<u32>,
     timestamp: DateTime<Utc>,
 }
 
-impl ScoreBuilder {
-    pub fn default() -> Self {
+impl Default for ScoreBuilder {
+    fn default() -> Self {
         Self {
             player: String::from("default"),
             score: 0,
+            base_score: 0,
+            bonus_score: 0,
+            max_eat_streak: 0,
+            peak_length: 0,
+            foods_eaten: 0,
+            fog_of_war: false,
+            board_size: None,
+            max_speed_level: None,
+            duration: None,
             timestamp: chrono::offset::Utc::now(),
         }
     }
+}
 
+impl ScoreBuilder {
     pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
         self.timestamp = timestamp;
         self
@@ -68,30 +168,109 @@ impl ScoreBuilder {
         self
     }
 
+    pub fn base_score(mut self, base_score: i32) -> Self {
+        self.base_score = base_score;
+        self
+    }
+
+    pub fn bonus_score(mut self, bonus_score: i32) -> Self {
+        self.bonus_score = bonus_score;
+        self
+    }
+
+    pub fn max_eat_streak(mut self, max_eat_streak: u32) -> Self {
+        self.max_eat_streak = max_eat_streak;
+        self
+    }
+
+    pub fn peak_length(mut self, peak_length: i32) -> Self {
+        self.peak_length = peak_length;
+        self
+    }
+
+    pub fn foods_eaten(mut self, foods_eaten: u32) -> Self {
+        self.foods_eaten = foods_eaten;
+        self
+    }
+
+    pub fn fog_of_war(mut self, fog_of_war: bool) -> Self {
+        self.fog_of_war = fog_of_war;
+        self
+    }
+
+    pub fn board_size(mut self, board_size: (i32, i32)) -> Self {
+        self.board_size = Some(board_size);
+        self
+    }
+
+    pub fn max_speed_level(mut self, max_speed_level: i32) -> Self {
+        self.max_speed_level = Some(max_speed_level);
+        self
+    }
+
+    pub fn duration(mut self, duration: u32) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
     pub fn build(self) -> Score {
         Score {
             player: self.player,
             score: self.score,
+            base_score: self.base_score,
+            bonus_score: self.bonus_score,
+            max_eat_streak: self.max_eat_streak,
+            peak_length: self.peak_length,
+            foods_eaten: self.foods_eaten,
+            fog_of_war: self.fog_of_war,
+            board_size: self.board_size,
+            max_speed_level: self.max_speed_level,
+            duration: self.duration,
             timestamp: self.timestamp,
         }
     }
 }
 
-/// Parse a vector of scores from the score file in an infallible way.
+/// Get the name of the score file of a board size and scoring mode, as every combination has its own high scores.
+/// The default board in the default mode keeps the original file name, so existing high scores carry over.
 /// # Arguments
-/// * `json: P` - A reference to path-like object, pointing to a score file.
-pub fn parse_scores<P: AsRef<Path>>(json: P) -> Vec<Score> {
-    let mut data = String::new();
-    // Open the file in read-only mode with buffer.
-    if let Ok(f) = File::open(json) {
-        let mut reader = BufReader::new(f);
-        reader.read_to_string(&mut data).unwrap_or_default();
+/// * `board_size: (i32, i32)` - The board width and height in blocks, including the score strip.
+/// * `survival: bool` - Whether the scores count the moves survived (true) or the food eaten (false).
+/// # Returns
+/// * `String` - The file name.
+pub fn scores_file_name(board_size: (i32, i32), survival: bool) -> String {
+    let size = if board_size == (DEFAULT_WIDTH, DEFAULT_HEIGHT) {
+        String::new()
+    } else {
+        format!("-{}x{}", board_size.0, board_size.1)
     };
-    let mut scores: Vec<Score> = serde_json::from_str(&data).unwrap_or_else(|_| {
-        // Generating default map.
-        let map: Vec<Score> = Vec::new();
-        map
-    });
+    let mode = if survival { "-survival" } else { "" };
+    if size.is_empty() && !survival {
+        String::from(SCORES_FILE_NAME)
+    } else {
+        format!("scores{}{}.json", size, mode)
+    }
+}
+
+/// Parse a vector of scores from the score file in an infallible way.
+/// Unreadable entries are skipped, and reported through the `log` crate when the `logging` feature is on.
+/// # Arguments
+/// * `storage: &dyn Storage` - Where the score files are kept.
+/// * `scores_name: &str` - The name of the score file.
+pub fn parse_scores(storage: &dyn Storage, scores_name: &str) -> Vec<Score> {
+    let data = storage.load(scores_name).unwrap_or_default();
+    // Keeping every score that can be read, rather than discarding the whole file over a single one.
+    let mut scores: Vec<Score> = score_entries(&data)
+        .into_iter()
+        .filter_map(|entry| {
+            entry
+                .map_err(|_error| {
+                    #[cfg(feature = "logging")]
+                    log::warn!("Skipping a score in {}: {}", scores_name, _error);
+                })
+                .ok()
+        })
+        .collect();
     // Reserve enough space for all the high scores and populate the map with defaults if not enough are read.
     scores
         .try_reserve_exact(NUMBER_HIGH_SCORES)
@@ -104,13 +283,30 @@ pub fn parse_scores<P: AsRef<Path>>(json: P) -> Vec<Score> {
     scores
 }
 
+/// Parse every entry of a score file on its own.
+/// # Arguments
+/// * `data: &str` - The contents of the score file.
+/// # Returns
+/// * `Vec<Result<Score, String>>` - Every score, or why it cannot be read along with its index in the file. Empty if
+///   the file holds no list at all.
+fn score_entries(data: &str) -> Vec<Result<Score, String>> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(data).unwrap_or_default();
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            serde_json::from_value(entry).map_err(|error| format!("score {}: {}", index, error))
+        })
+        .collect()
+}
+
 /// Binary search for the first score in the reverse sorted arrays of scores that is lower than the new score.
 /// # Arguments
-/// * `score: i32` - The score to search for.
-/// * `scores: &Vec<Score>` - The reverse sorted vector of Score structs.
+/// * `key: RankKey` - The ranking keys of the score to search for.
+/// * `scores: &[Score]` - The reverse sorted slice of Score structs.
 /// # Returns
 /// * `Option<i32>` - The rank of the score as a i32 or None.
-pub fn check_score(score: i32, scores: &Vec<Score>) -> Option<usize> {
+pub fn check_score(key: RankKey, scores: &[Score]) -> Option<usize> {
     if scores.is_empty() {
         return None;
     }
@@ -121,7 +317,7 @@ pub fn check_score(score: i32, scores: &Vec<Score>) -> Option<usize> {
     while low <= high {
         let middle = low + (high - low) / 2;
         if let Some(current) = scores.get(middle as usize) {
-            if current.score >= score {
+            if current.rank_key() >= key {
                 low = middle + 1;
             } else {
                 high = middle - 1;
@@ -147,28 +343,113 @@ pub fn update_scores(rank: usize, score: Score, scores: &mut Vec<Score>) {
     }
 }
 
-pub fn write_scores_to_json<P: AsRef<Path>>(json: P, scores: &Vec<Score>) -> std::io::Result<()> {
-    let serialized: String = serde_json::to_string_pretty(scores).unwrap();
-    let mut buffer = File::create(json)?;
-    buffer.write_all(serialized.as_bytes())?;
-    Ok(())
-}
-
-pub fn write_score(scores: &mut Vec<Score>, name: &str, game: &Game, scores_file: &PathBuf) {
-    if let Some(rank) = check_score(game.score(), scores) {
+/// Rank the final score of a game and write the scores if it made it onto the scoreboard.
+/// # Arguments
+/// * `scores: &mut Vec<Score>` - The current high scores, updated with the new one.
+/// * `name: &str` - The name of the player.
+/// * `game: &Game` - The finished game.
+/// * `storage: &mut dyn Storage` - Where the score files are kept. The file is picked by the board size and scoring
+///   mode of the game.
+pub fn write_score(scores: &mut Vec<Score>, name: &str, game: &Game, storage: &mut dyn Storage) {
+    if let Some(rank) = check_score(game.rank_key(), scores) {
         update_scores(
             rank,
-            ScoreBuilder::default()
+            Score::builder()
                 .player(name)
                 .score(game.score())
+                .base_score(game.base_score())
+                .bonus_score(game.bonus_score())
+                .max_eat_streak(game.max_eat_streak())
+                .peak_length(game.peak_length())
+                .foods_eaten(game.foods_eaten())
+                .fog_of_war(game.fog_of_war())
+                .board_size(game.size())
+                .max_speed_level(game.max_speed_level())
+                .duration(game.play_time().as_secs_f64().round() as u32)
                 .build(),
             scores,
         );
-        match write_scores_to_json(scores_file, scores) {
-            Ok(_) => (),
-            Err(e) => panic!("Could not write scores: {e:?}"),
-        };
+        let scores_name = scores_file_name(game.size(), game.survival());
+        if let Err(e) = write_scores_to_json(storage, &scores_name, scores) {
+            #[cfg(feature = "logging")]
+            log::error!("Could not write scores to {scores_name}: {e}");
+            panic!("Could not write scores: {e:?}")
+        }
+    }
+}
+
+/// Format a number of seconds as hours, minutes and seconds.
+/// # Arguments
+/// * `seconds: u32` - The duration to format.
+/// # Returns
+/// * `String` - The duration as `h:mm:ss`.
+fn format_duration(seconds: u32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Format the high scores as a plain text table with fixed-width columns, e.g. for a terminal or a README.
+/// # Arguments
+/// * `scores: &[Score]` - The reverse sorted slice of Score structs.
+/// * `utc: bool` - Whether (true) or not (false) to show dates in UTC rather than the local timezone.
+/// # Returns
+/// * `String` - A header line followed by a line per score. Unknown durations and speed levels are shown as `-`.
+pub fn scores_to_text(scores: &[Score], utc: bool) -> String {
+    // Fitting the highest rank with its period and the longest name, followed by the space between columns.
+    let rank_width = NUMBER_HIGH_SCORES.to_string().len() + 2;
+    let name_width = MAX_NAME_LENGTH + 2;
+    let mut text = format!(
+        "{:<rank_width$}{:<7}{:<name_width$}{:<12}{:<10}{}\n",
+        "#", "Score", "Player", "Date", "Duration", "MaxSpd"
+    );
+    // Absolute dates are the same in every language.
+    let locale = Locale::default();
+    for (rank, score) in scores.iter().enumerate() {
+        let unknown = || String::from("-");
+        text.push_str(&format!(
+            "{:<rank_width$}{:<7}{:<name_width$}{:<12}{:<10}{}\n",
+            format!("{}.", rank + 1),
+            score.score,
+            score.player,
+            DisplayFormat::Absolute.format(&score.timestamp, utc, &locale),
+            score.duration.map_or_else(unknown, format_duration),
+            score
+                .max_speed_level
+                .map_or_else(unknown, |level| level.to_string()),
+        ));
     }
+    text
+}
+
+/// Write the high scores to a score file.
+/// Every score is written with all of its fields, including those that were defaulted because it was read from an
+/// older file. No score is dropped over missing fields, and the file is upgraded to the current shape.
+/// Entries `parse_scores` skipped as unreadable, e.g. with an invalid timestamp, are not part of `scores`, so they are
+/// lost for good once the file is written.
+/// # Arguments
+/// * `storage: &mut dyn Storage` - Where the score file is kept.
+/// * `scores_name: &str` - The name of the score file, see `scores_file_name`.
+/// * `scores: &[Score]` - The reverse sorted slice of Score structs.
+pub fn write_scores_to_json(
+    storage: &mut dyn Storage,
+    scores_name: &str,
+    scores: &[Score],
+) -> io::Result<()> {
+    let serialized: String = serde_json::to_string_pretty(scores)?;
+    storage.save(scores_name, serialized)
+}
+
+/// Write the high scores as a plain text table, see `scores_to_text`.
+/// # Arguments
+/// * `path: P` - A reference to path-like object, pointing to the text file.
+/// * `scores: &[Score]` - The reverse sorted slice of Score structs.
+pub fn write_scores_to_text<P: AsRef<Path>>(path: P, scores: &[Score]) -> io::Result<()> {
+    // Writing dates in UTC, like the scores file itself, as the table may be read in another timezone.
+    fs::write(path, scores_to_text(scores, true))
 }
 
 pub fn create_empty_name() -> String {
@@ -176,3 +457,185 @@ pub fn create_empty_name() -> String {
     s.reserve_exact(MAX_NAME_LENGTH);
     s
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breakdown::ScoreReason;
+    use crate::config::GameConfig;
+    use crate::food::FoodKind;
+    use crate::storage::MemoryStorage;
+
+    fn score(score: i32, base_score: i32, max_eat_streak: u32) -> Score {
+        Score::builder()
+            .score(score)
+            .base_score(base_score)
+            .max_eat_streak(max_eat_streak)
+            .build()
+    }
+
+    #[test]
+    fn check_score_breaks_ties_on_base_score_and_streak() {
+        let scores = vec![
+            score(10, 10, 2),
+            score(10, 7, 3),
+            score(10, 7, 1),
+            score(5, 5, 0),
+        ];
+        assert_eq!(check_score((11, 0, 0), &scores), Some(0));
+        assert_eq!(check_score((10, 8, 0), &scores), Some(1));
+        assert_eq!(check_score((10, 7, 2), &scores), Some(2));
+        assert_eq!(check_score((10, 7, 1), &scores), Some(3));
+        assert_eq!(check_score((4, 4, 9), &scores), None);
+    }
+
+    #[test]
+    fn unreadable_scores_are_reported_by_index() {
+        let entry = |timestamp: &str| {
+            let mut entry = serde_json::to_value(Score::builder().player("A").build()).unwrap();
+            entry["timestamp"] = serde_json::Value::from(timestamp);
+            entry
+        };
+        let data = serde_json::to_string(&[
+            entry("2024/02/29 23:59:58"),
+            entry("2024-02-29T23:59:58Z"),
+            entry("yesterday"),
+            entry("2024-02-29 23:59:58"),
+        ])
+        .unwrap();
+        let entries = score_entries(&data);
+        assert_eq!(entries.len(), 4);
+        for i in [0, 1, 3] {
+            assert_eq!(
+                entries[i].as_ref().unwrap().timestamp(),
+                entries[0].as_ref().unwrap().timestamp()
+            );
+        }
+        let error = entries[2].as_ref().unwrap_err();
+        assert!(
+            error.starts_with("score 2:") && error.contains("\"yesterday\""),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn scores_round_trip_through_storage() {
+        let mut storage = MemoryStorage::default();
+        let name = scores_file_name((DEFAULT_WIDTH, DEFAULT_HEIGHT), false);
+        let scores = parse_scores(&storage, &name);
+        assert_eq!(scores.len(), NUMBER_HIGH_SCORES);
+        let mut game = Game::new(GameConfig::default());
+        game.add_score(12, ScoreReason::Food(FoodKind::Normal));
+        let mut scores = scores;
+        write_score(&mut scores, "PLAYER", &game, &mut storage);
+        assert_eq!(parse_scores(&storage, &name)[0].score, 12);
+        assert_eq!(parse_scores(&storage, &name)[0].player, "PLAYER");
+    }
+
+    #[test]
+    fn saved_scores_keep_the_base_and_bonus_split() {
+        let mut storage = MemoryStorage::default();
+        let name = scores_file_name((DEFAULT_WIDTH, DEFAULT_HEIGHT), false);
+        let mut scores = parse_scores(&storage, &name);
+        let mut game = Game::new(GameConfig::default());
+        game.add_score(9, ScoreReason::Food(FoodKind::Normal));
+        game.add_score(4, ScoreReason::Combo);
+        write_score(&mut scores, "PLAYER", &game, &mut storage);
+        let saved = &parse_scores(&storage, &name)[0];
+        assert_eq!(saved.score(), 13);
+        assert_eq!(saved.base_score(), 9);
+        assert_eq!(saved.bonus_score(), 4);
+    }
+
+    #[test]
+    fn historical_score_files_keep_loading() {
+        let folder = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/scores");
+        let mut paths: Vec<_> = fs::read_dir(folder)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        assert!(paths.len() >= 10);
+        for path in paths {
+            let data = fs::read_to_string(&path).unwrap();
+            let scores: Vec<Score> = score_entries(&data)
+                .into_iter()
+                .map(|entry| entry.unwrap())
+                .collect();
+            let players: Vec<_> = scores
+                .iter()
+                .map(|score| (score.player(), score.score()))
+                .collect();
+            assert_eq!(players, [("ALICE", 42), ("BOB", 7)], "{path:?}");
+            assert_eq!(
+                scores[0].timestamp().format(dateformat::FORMAT).to_string(),
+                "2023/05/01 18:30:00"
+            );
+            // Writing the scores back keeps every entry and every known field, filling in the missing ones.
+            let mut storage = MemoryStorage::default();
+            write_scores_to_json(&mut storage, "scores.json", &scores).unwrap();
+            let written: Vec<serde_json::Value> =
+                serde_json::from_str(&storage.load("scores.json").unwrap()).unwrap();
+            let original: Vec<serde_json::Value> = serde_json::from_str(&data).unwrap();
+            assert_eq!(written.len(), original.len());
+            for (written, original) in written.iter().zip(&original) {
+                let written = written.as_object().unwrap();
+                assert_eq!(written.len(), 12, "{path:?}");
+                for (key, value) in original.as_object().unwrap() {
+                    if let Some(kept) = written.get(key) {
+                        assert_eq!(kept, value, "{path:?} {key}");
+                    } else {
+                        assert!(["mode", "length"].contains(&key.as_str()), "{path:?} {key}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scores_are_written_as_an_aligned_table() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let scores = vec![
+            Score::builder()
+                .player("Alice")
+                .score(42)
+                .duration(202)
+                .max_speed_level(8)
+                .timestamp(timestamp)
+                .build(),
+            Score::builder()
+                .player("ANON")
+                .score(7)
+                .timestamp(timestamp)
+                .build(),
+        ];
+        let path = std::env::temp_dir().join("rust-snake-scores.txt");
+        write_scores_to_text(&path, &scores).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "#   Score  Player      Date        Duration  MaxSpd\n\
+             1.  42     Alice       2024/06/01  0:03:22   8\n\
+             2.  7      ANON        2024/06/01  -         -\n"
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn every_board_size_has_its_own_scores() {
+        assert_eq!(
+            scores_file_name((DEFAULT_WIDTH, DEFAULT_HEIGHT), false),
+            "scores.json"
+        );
+        assert_eq!(scores_file_name((40, 30), false), "scores-40x30.json");
+        assert_eq!(
+            scores_file_name((DEFAULT_WIDTH, DEFAULT_HEIGHT), true),
+            "scores-survival.json"
+        );
+        assert_eq!(
+            scores_file_name((40, 30), true),
+            "scores-40x30-survival.json"
+        );
+    }
+}
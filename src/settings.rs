@@ -0,0 +1,163 @@
+// External imports.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+// Local imports.
+use crate::config::GameConfig;
+use crate::locale::DEFAULT_LANGUAGE;
+use crate::theme::Theme;
+
+// Constants.
+pub const MAX_VOLUME: u8 = 100;
+pub const VOLUME_STEP: u8 = 10;
+/// The frame rates the game can be capped at.
+pub const FPS_CAPS: [u64; 4] = [30, 60, 120, 144];
+
+/// All user preferences, persisted in the settings file.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The options every new game is created from.
+    pub game: GameConfig,
+    /// The volume of the background music, from 0 to `MAX_VOLUME`.
+    pub music_volume: u8,
+    /// The volume of the sound effects, from 0 to `MAX_VOLUME`.
+    pub effects_volume: u8,
+    /// Whether (true) or not (false) all music and sound effects are silenced.
+    pub muted: bool,
+    /// The language of all on-screen text, matching a file in the locales folder.
+    pub language: String,
+    /// Whether (true) or not (false) the controls overlay is shown at launch. Only on by default without a settings
+    /// file, and turned off once it is dismissed.
+    pub show_hints: bool,
+    /// The maximum number of frames drawn per second.
+    pub max_fps: u64,
+    /// Whether (true) <SPACE> returns to the main menu after a game over, or (false) starts a new game directly.
+    pub menu_after_game_over: bool,
+    /// The font file all text is drawn with, either absolute or relative to the assets folder. The bundled font is
+    /// used if this is None, or if the file cannot be loaded.
+    pub font: Option<String>,
+    /// The theme files found in the themes folder at launch, by name.
+    #[serde(skip)]
+    pub themes: BTreeMap<String, Theme>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            game: GameConfig::default(),
+            music_volume: 50,
+            effects_volume: 80,
+            muted: false,
+            language: String::from(DEFAULT_LANGUAGE),
+            show_hints: false,
+            max_fps: 60,
+            menu_after_game_over: false,
+            font: None,
+            themes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Parse the settings from the settings file in an infallible way, falling back to defaults.
+    /// # Arguments
+    /// * `json: P` - A reference to path-like object, pointing to a settings file.
+    /// # Returns
+    /// * `Settings` - The parsed settings.
+    pub fn load<P: AsRef<Path>>(json: P) -> Settings {
+        let Ok(f) = File::open(json) else {
+            // Showing the controls on the very first launch only.
+            return Settings {
+                show_hints: true,
+                ..Settings::default()
+            };
+        };
+        let mut data = String::new();
+        let mut reader = BufReader::new(f);
+        reader.read_to_string(&mut data).unwrap_or_default();
+        serde_json::from_str(&data).unwrap_or_default()
+    }
+
+    /// Draw the game with one of the loaded theme files, or with the palette again.
+    /// # Arguments
+    /// * `name: Option<String>` - The name of the theme file, or None for the palette. Falls back to the palette if no
+    ///   theme file of this name was loaded.
+    pub fn select_theme(&mut self, name: Option<String>) {
+        self.game.theme_colors = name
+            .as_ref()
+            .and_then(|name| self.themes.get(name))
+            .cloned();
+        if let (Some(_name), None) = (&name, &self.game.theme_colors) {
+            #[cfg(feature = "logging")]
+            log::warn!("Theme {} not found, using the palette", _name);
+        }
+        self.game.theme_file = name;
+    }
+
+    /// Write the settings to the settings file.
+    /// # Arguments
+    /// * `json: P` - A reference to path-like object, pointing to a settings file.
+    pub fn save<P: AsRef<Path>>(&self, json: P) -> std::io::Result<()> {
+        let serialized: String = serde_json::to_string_pretty(self).unwrap();
+        let mut buffer = File::create(json)?;
+        buffer.write_all(serialized.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Raise or lower a volume by a single step, staying within 0 and `MAX_VOLUME`.
+/// # Arguments
+/// * `volume: u8` - The current volume.
+/// * `up: bool` - Whether to raise (true) or lower (false) the volume.
+/// # Returns
+/// * `u8` - The new volume.
+pub fn step_volume(volume: u8, up: bool) -> u8 {
+    if up {
+        volume.saturating_add(VOLUME_STEP).min(MAX_VOLUME)
+    } else {
+        volume.saturating_sub(VOLUME_STEP)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_volume_stays_in_range() {
+        assert_eq!(step_volume(95, true), MAX_VOLUME);
+        assert_eq!(step_volume(5, false), 0);
+        assert_eq!(step_volume(50, true), 60);
+        assert_eq!(step_volume(50, false), 40);
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let path = std::env::temp_dir().join("rust-snake-settings-round-trip.json");
+        let settings = Settings {
+            music_volume: 30,
+            ..Settings::default()
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path), settings);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_keys_use_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"music_volume": 10}"#).unwrap();
+        assert_eq!(settings.music_volume, 10);
+        assert_eq!(settings.effects_volume, Settings::default().effects_volume);
+        assert!(!settings.show_hints);
+    }
+
+    #[test]
+    fn hints_are_shown_without_a_settings_file() {
+        let path = std::env::temp_dir().join("rust-snake-settings-missing.json");
+        assert!(Settings::load(path).show_hints);
+    }
+}
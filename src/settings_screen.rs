@@ -0,0 +1,351 @@
+// External imports.
+use piston_window::{Context, G2d, Glyphs, Key};
+
+// Local imports.
+use crate::block::Block;
+use crate::config::{BoardPreset, Difficulty, BOARD_SIZES, MAX_SPEED_LEVELS};
+use crate::draw::{draw_rectangle, draw_text, fit_font_size, to_pixels};
+use crate::game::Game;
+use crate::levels::Level;
+use crate::locale::Locale;
+use crate::settings::{step_volume, Settings, FPS_CAPS};
+use crate::theme::Palette;
+
+// Constants.
+// The text size, relative to the block size.
+const SETTINGS_FONT_RATIO: f64 = 0.8;
+
+/// Every row of the settings screen, from top to bottom.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SettingsOption {
+    Palette,
+    Theme,
+    HighContrast,
+    GradientBackground,
+    EffectsVolume,
+    MusicVolume,
+    FpsCap,
+    ReduceMotion,
+    ShowHints,
+    Difficulty,
+    MaxSpeed,
+    Level,
+    Arcade,
+    FogOfWar,
+    HazardRows,
+    Survival,
+    Practice,
+    BoardSize,
+    MenuAfterGameOver,
+}
+
+impl SettingsOption {
+    /// The rows of the settings screen. The board size is only picked from the main menu, so it never changes mid-run.
+    pub const ALL: [SettingsOption; 18] = [
+        SettingsOption::Palette,
+        SettingsOption::Theme,
+        SettingsOption::HighContrast,
+        SettingsOption::GradientBackground,
+        SettingsOption::EffectsVolume,
+        SettingsOption::MusicVolume,
+        SettingsOption::FpsCap,
+        SettingsOption::ReduceMotion,
+        SettingsOption::ShowHints,
+        SettingsOption::Difficulty,
+        SettingsOption::MaxSpeed,
+        SettingsOption::Level,
+        SettingsOption::Arcade,
+        SettingsOption::FogOfWar,
+        SettingsOption::HazardRows,
+        SettingsOption::Survival,
+        SettingsOption::Practice,
+        SettingsOption::MenuAfterGameOver,
+    ];
+
+    /// The locale key of the name of the option.
+    pub fn label_key(&self) -> &'static str {
+        match *self {
+            SettingsOption::Palette => "option_palette",
+            SettingsOption::Theme => "option_theme",
+            SettingsOption::HighContrast => "option_high_contrast",
+            SettingsOption::GradientBackground => "option_gradient_background",
+            SettingsOption::EffectsVolume => "option_effects_volume",
+            SettingsOption::MusicVolume => "option_music_volume",
+            SettingsOption::FpsCap => "option_fps_cap",
+            SettingsOption::ReduceMotion => "option_reduce_motion",
+            SettingsOption::ShowHints => "option_show_hints",
+            SettingsOption::Difficulty => "option_difficulty",
+            SettingsOption::MaxSpeed => "option_max_speed",
+            SettingsOption::Level => "option_level",
+            SettingsOption::Arcade => "option_arcade",
+            SettingsOption::FogOfWar => "option_fog_of_war",
+            SettingsOption::HazardRows => "option_hazard_rows",
+            SettingsOption::Survival => "option_survival",
+            SettingsOption::Practice => "option_practice",
+            SettingsOption::BoardSize => "option_board_size",
+            SettingsOption::MenuAfterGameOver => "option_menu_after_game_over",
+        }
+    }
+
+    /// Describe the current value of the option.
+    /// # Arguments
+    /// * `settings: &Settings` - The settings holding the value.
+    /// * `board_size: (i32, i32)` - The size of the board currently played on.
+    /// * `locale: &Locale` - The strings of the language to describe the value in.
+    /// # Returns
+    /// * `String` - The value, as shown on the settings screen.
+    pub fn value(&self, settings: &Settings, board_size: (i32, i32), locale: &Locale) -> String {
+        let on_off = |on: bool| locale.get(if on { "on" } else { "off" }).to_string();
+        match *self {
+            SettingsOption::Palette => locale
+                .get(match settings.game.palette {
+                    Palette::Classic => "palette_classic",
+                    Palette::Colorblind => "palette_colorblind",
+                })
+                .to_string(),
+            SettingsOption::Theme => match &settings.game.theme_file {
+                Some(name) => name.to_uppercase(),
+                None => locale.get("theme_none").to_string(),
+            },
+            SettingsOption::HighContrast => on_off(settings.game.high_contrast),
+            SettingsOption::GradientBackground => on_off(settings.game.gradient_background),
+            SettingsOption::EffectsVolume => format!("{}%", settings.effects_volume),
+            SettingsOption::MusicVolume => format!("{}%", settings.music_volume),
+            SettingsOption::FpsCap => settings.max_fps.to_string(),
+            SettingsOption::ReduceMotion => on_off(settings.game.reduce_motion),
+            SettingsOption::ShowHints => on_off(settings.show_hints),
+            SettingsOption::Difficulty => locale
+                .get(match settings.game.difficulty {
+                    Difficulty::Easy => "difficulty_easy",
+                    Difficulty::Normal => "difficulty_normal",
+                    Difficulty::Hard => "difficulty_hard",
+                })
+                .to_string(),
+            SettingsOption::MaxSpeed => settings.game.max_speed_level.to_string(),
+            SettingsOption::Level => locale.get(settings.game.level.label_key()).to_string(),
+            SettingsOption::Arcade => on_off(settings.game.arcade),
+            SettingsOption::FogOfWar => on_off(settings.game.fog_of_war),
+            SettingsOption::HazardRows => on_off(settings.game.hazard_rows),
+            SettingsOption::Survival => on_off(settings.game.survival),
+            SettingsOption::Practice => on_off(settings.game.practice),
+            SettingsOption::MenuAfterGameOver => on_off(settings.menu_after_game_over),
+            SettingsOption::BoardSize => {
+                let size = (settings.game.width, settings.game.height);
+                let value = match BoardPreset::from_size(size) {
+                    Some(preset) => {
+                        format!("{} {}X{}", locale.get(preset.label_key()), size.0, size.1)
+                    }
+                    None => format!("{}X{}", size.0, size.1),
+                };
+                // A new board size only applies to the next game.
+                if size == board_size {
+                    value
+                } else {
+                    format!("{} {}", value, locale.get("next_game"))
+                }
+            }
+        }
+    }
+
+    /// Change the option to its next or previous value.
+    /// # Arguments
+    /// * `settings: &mut Settings` - The settings holding the value.
+    /// * `forward: bool` - Whether to pick the next (true) or previous (false) value.
+    pub fn cycle(&self, settings: &mut Settings, forward: bool) {
+        if *self == SettingsOption::Theme {
+            // Listing the theme files by name, after the palette itself.
+            let names: Vec<Option<String>> = std::iter::once(None)
+                .chain(settings.themes.keys().cloned().map(Some))
+                .collect();
+            let name = cycle(&names, settings.game.theme_file.clone(), forward);
+            settings.select_theme(name);
+            return;
+        }
+        let game = &mut settings.game;
+        match *self {
+            SettingsOption::Palette => game.palette = cycle(&Palette::ALL, game.palette, forward),
+            SettingsOption::Theme => (),
+            SettingsOption::HighContrast => game.high_contrast = !game.high_contrast,
+            SettingsOption::GradientBackground => {
+                game.gradient_background = !game.gradient_background
+            }
+            SettingsOption::EffectsVolume => {
+                settings.effects_volume = step_volume(settings.effects_volume, forward)
+            }
+            SettingsOption::MusicVolume => {
+                settings.music_volume = step_volume(settings.music_volume, forward)
+            }
+            SettingsOption::FpsCap => {
+                settings.max_fps = cycle(&FPS_CAPS, settings.max_fps, forward)
+            }
+            SettingsOption::ReduceMotion => game.reduce_motion = !game.reduce_motion,
+            SettingsOption::ShowHints => settings.show_hints = !settings.show_hints,
+            SettingsOption::Difficulty => {
+                game.difficulty = cycle(&Difficulty::ALL, game.difficulty, forward)
+            }
+            SettingsOption::MaxSpeed => {
+                game.max_speed_level = cycle(&MAX_SPEED_LEVELS, game.max_speed_level, forward)
+            }
+            SettingsOption::Level => game.level = cycle(&Level::ALL, game.level, forward),
+            SettingsOption::Arcade => game.arcade = !game.arcade,
+            SettingsOption::FogOfWar => game.fog_of_war = !game.fog_of_war,
+            SettingsOption::HazardRows => game.hazard_rows = !game.hazard_rows,
+            SettingsOption::Survival => game.survival = !game.survival,
+            SettingsOption::Practice => game.practice = !game.practice,
+            SettingsOption::MenuAfterGameOver => {
+                settings.menu_after_game_over = !settings.menu_after_game_over
+            }
+            SettingsOption::BoardSize => {
+                (game.width, game.height) = cycle(&BOARD_SIZES, (game.width, game.height), forward)
+            }
+        }
+    }
+}
+
+/// Pick the value next to the current one, wrapping around at both ends.
+/// # Arguments
+/// * `values: &[T]` - All values to pick from, in order.
+/// * `current: T` - The current value. The first value is picked if it is not one of the values.
+/// * `forward: bool` - Whether to pick the next (true) or previous (false) value.
+/// # Returns
+/// * `T` - The picked value.
+fn cycle<T: Clone + PartialEq>(values: &[T], current: T, forward: bool) -> T {
+    let Some(index) = values.iter().position(|value| *value == current) else {
+        return values[0].clone();
+    };
+    let step = if forward { 1 } else { values.len() - 1 };
+    values[(index + step) % values.len()].clone()
+}
+
+/// A keyboard-driven list of all settings, opened from the pause screen or the main menu.
+#[derive(Debug, Default)]
+pub struct SettingsScreen {
+    /// The index of the highlighted option in `SettingsOption::ALL`.
+    selected: usize,
+}
+
+impl SettingsScreen {
+    pub fn new() -> SettingsScreen {
+        SettingsScreen::default()
+    }
+
+    pub fn selected(&self) -> SettingsOption {
+        SettingsOption::ALL[self.selected]
+    }
+
+    /// React to a keypress: <UP>/<DOWN> select an option and <LEFT>/<RIGHT> change it.
+    /// # Arguments
+    /// * `key: Key` - The key being pressed.
+    /// * `settings: &mut Settings` - The settings to change.
+    /// # Returns
+    /// * `bool` - Whether (true) or not (false) the screen should close, on <O> or <RETURN>.
+    pub fn key_pressed(&mut self, key: Key, settings: &mut Settings) -> bool {
+        let count = SettingsOption::ALL.len();
+        match key {
+            Key::Up => self.selected = (self.selected + count - 1) % count,
+            Key::Down => self.selected = (self.selected + 1) % count,
+            Key::Left => self.selected().cycle(settings, false),
+            Key::Right => self.selected().cycle(settings, true),
+            Key::O | Key::Return => return true,
+            _ => (),
+        }
+        false
+    }
+
+    /// Draw the settings screen on top of the playing field.
+    /// # Arguments
+    /// * `settings: &Settings` - The settings to show.
+    /// * `game: &Game` - The game drawn underneath, providing the colors and the board size.
+    /// * `locale: &Locale` - The strings of the language to draw all text in.
+    /// * `glyphs: &mut piston_window::Glyphs` - The characters to use for drawing.
+    /// * `con: &piston_window::Context` - The context in which to draw.
+    /// * `g: &mut G2d` - The 2d graphics driver to use.
+    pub fn draw(
+        &self,
+        settings: &Settings,
+        game: &Game,
+        locale: &Locale,
+        glyphs: &mut Glyphs,
+        con: &Context,
+        g: &mut G2d,
+    ) {
+        let (width, height) = game.size();
+        // Covering everything but the score strip.
+        draw_rectangle(
+            game.theme().game_over_overlay,
+            Block::new(0, 0),
+            width,
+            height - 1,
+            con,
+            g,
+        );
+        let mut text = format!("{}\n\n", locale.get("settings_title"));
+        for option in SettingsOption::ALL {
+            text.push_str(&format!(
+                "{} {}: {}\n",
+                if option == self.selected() { ">" } else { " " },
+                locale.get(option.label_key()),
+                option.value(settings, game.size(), locale)
+            ));
+        }
+        text.push('\n');
+        text.push_str(locale.get("settings_controls"));
+        let font_size = fit_font_size(
+            &text,
+            game.config().font_size(SETTINGS_FONT_RATIO),
+            to_pixels(width - 2),
+            glyphs,
+        );
+        draw_text(
+            &text,
+            Block::new(1, 1),
+            game.theme().overlay_text,
+            font_size,
+            glyphs,
+            con,
+            g,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycle_wraps_around() {
+        assert_eq!(cycle(&FPS_CAPS, 144, true), 30);
+        assert_eq!(cycle(&FPS_CAPS, 30, false), 144);
+        assert_eq!(cycle(&FPS_CAPS, 60, true), 120);
+        // Unknown values jump to the first one.
+        assert_eq!(cycle(&FPS_CAPS, 75, true), 30);
+    }
+
+    #[test]
+    fn keys_select_and_change_options() {
+        let mut screen = SettingsScreen::new();
+        let mut settings = Settings::default();
+        screen.key_pressed(Key::Up, &mut settings);
+        screen.key_pressed(Key::Up, &mut settings);
+        assert_eq!(screen.selected(), SettingsOption::Practice);
+        screen.key_pressed(Key::Right, &mut settings);
+        assert!(settings.game.practice);
+        screen.key_pressed(Key::Down, &mut settings);
+        screen.key_pressed(Key::Down, &mut settings);
+        screen.key_pressed(Key::Left, &mut settings);
+        assert_eq!(settings.game.palette, Palette::Colorblind);
+        assert!(screen.key_pressed(Key::O, &mut settings));
+    }
+
+    #[test]
+    fn board_size_notes_the_next_game() {
+        let locale = Locale::default();
+        let settings = Settings::default();
+        let size = (settings.game.width, settings.game.height);
+        let value = SettingsOption::BoardSize.value(&settings, size, &locale);
+        assert!(!value.contains(locale.get("next_game")));
+        let value = SettingsOption::BoardSize.value(&settings, (15, 15), &locale);
+        assert!(value.contains(locale.get("next_game")));
+        assert!(value.contains(locale.get("board_classic")));
+    }
+}
@@ -1,19 +1,15 @@
 // External imports.
-use piston_window::types::Color;
-use piston_window::{Context, G2d};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{vec_deque, HashMap, VecDeque};
+use std::iter::Copied;
 
 // Importing local modules from the crate root.
 use crate::block::Block;
 use crate::direction::Direction;
-use crate::draw::{
-    draw_block, get_offset_size_digesting, get_offset_size_regular, BLOCK_SIZE, SNAKE_BLOCK_SIZE,
-};
 
-const SNAKE_HEAD_COLOR: Color = [0.00, 0.60, 0.00, 1.00];
-const SNAKE_BODY_COLOR: Color = [0.00, 0.80, 0.00, 1.00];
+#[cfg(feature = "gui")]
+mod render;
 
-const SNAKE_STARTING_LENGTH: i32 = 3;
+pub const SNAKE_STARTING_LENGTH: i32 = 3;
 
 pub struct Snake {
     /// The current and next direction in which the snake is travelling.
@@ -54,103 +50,44 @@ impl Snake {
                 y: y + dy,
             })
         }
+        Snake::from_blocks(body, direction.unwrap_or(Direction::Right))
+    }
+
+    /// Instantiate a Snake from a known set of body Blocks.
+    /// # Arguments
+    /// * `blocks: impl IntoIterator<Item = Block>` - The body Blocks, ordered from head to tail.
+    /// * `direction: Direction` - The current direction of the Snake.
+    /// # Returns
+    /// * `Snake` - The new Snake instance.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = Block>, direction: Direction) -> Snake {
         // Completing the Snake struct with a direction and absent tail.
         Snake {
-            current_direction: direction.unwrap_or(Direction::Right),
-            body,
+            current_direction: direction,
+            body: blocks.into_iter().collect(),
             tail: None,
             digesting: HashMap::new(),
         }
     }
 
     /// Get the length of the Snake body VecDeque.
+    #[deprecated(note = "use `body_len`, which returns the length as a usize")]
     pub fn len(&self) -> i32 {
         self.body.len() as i32
     }
 
-    pub fn _get_offset_size(&self, delta: i32) -> [f64; 2] {
-        match delta {
-            0 => [(BLOCK_SIZE - SNAKE_BLOCK_SIZE) / 2.0, SNAKE_BLOCK_SIZE],
-            1 => [-(BLOCK_SIZE - SNAKE_BLOCK_SIZE) / 2.0, BLOCK_SIZE],
-            -1 => [(BLOCK_SIZE - SNAKE_BLOCK_SIZE) / 2.0, BLOCK_SIZE],
-            _ => [0.0, BLOCK_SIZE],
-        }
+    /// Get the number of Blocks in the Snake body.
+    pub fn body_len(&self) -> usize {
+        self.body.len()
     }
 
-    /// Draw all blocks in the Snakes body inside the context using the graphics engine.
-    /// The game happens on a 2D array of Blocks of side length BLOCK_SIZE.
-    /// The snake body blocks, however, are smaller i.e., of side length SNAKE_BLOCK_SIZE.
-    /// In order to properly connect these smaller blocks, shifts in the size (the blocks' side length) and offset (the
-    /// blocks' starting point in the top left corner) are applied along 1 dimension. As a result, the snakes' body
-    /// blocks are rectangles rather than squares.
-    ///
-    /// Below, a three part snake is drawn in a grid, with the larger grid block corners denoted by `x`. Conversely, the
-    /// smaller snake body blocks' corners are denoted by an `o` and are colored in with `.`.
-    ///```
-    /// x_______x_______x_______x
-    /// | o-------o-----|.......|
-    /// | |.............|.......|
-    /// | o-------o-----|.......|
-    /// x_______x_______x_______x
-    ///```
-    pub fn draw(&mut self, con: &Context, g: &mut G2d) {
-        for (i, block) in self.body.iter().enumerate() {
-            // Drawing body part.
-            if i > 0 {
-                // Drawing body part on location where food was eaten.
-                if self.digesting.get(block).is_some() {
-                    draw_block(
-                        *block,
-                        SNAKE_BODY_COLOR,
-                        [0.0, 0.0],
-                        [BLOCK_SIZE, BLOCK_SIZE],
-                        con,
-                        g,
-                    );
-                }
-                // Drawing other body part.
-                else {
-                    let current = self.body.get(i).unwrap();
-                    let previous = self.body.get(i - 1).unwrap();
-
-                    let (x_offset_size, y_offset_size) = match self.body.get(i + 1) {
-                        // There is a following block. Formatting to be decided.
-                        Some(next) => {
-                            if self.digesting.get(next).is_some() {
-                                // The following block is digesting. Format the current based on both.
-                                get_offset_size_digesting(*current, *previous, *next)
-                            } else {
-                                // The following block is not digesting. Format the current based only on previous.
-                                get_offset_size_regular(*current, *previous)
-                            }
-                        }
-                        // There is no following block. Format the current based only on previous.
-                        None => get_offset_size_regular(*current, *previous),
-                    };
-
-                    // Calculate offsets and connections.
-                    // let (x_offset_size, y_offset_size) = get_offset_size(*current, *previous);
-                    draw_block(
-                        *block,
-                        SNAKE_BODY_COLOR,
-                        [x_offset_size[0], y_offset_size[0]],
-                        [x_offset_size[1], y_offset_size[1]],
-                        con,
-                        g,
-                    )
-                }
-            // Drawing head.
-            } else {
-                draw_block(
-                    *block,
-                    SNAKE_HEAD_COLOR,
-                    [0.0, 0.0],
-                    [BLOCK_SIZE, BLOCK_SIZE],
-                    con,
-                    g,
-                )
-            }
-        }
+    /// Check whether the Snake body VecDeque is empty.
+    pub fn is_empty(&self) -> bool {
+        self.body.is_empty()
+    }
+
+    /// Iterate over all body Blocks, from head to tail.
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        self.into_iter()
     }
 
     /// Find the head position of the snake.
@@ -163,6 +100,26 @@ impl Snake {
         self.current_direction
     }
 
+    /// Start digesting food at the head, for as many moves as the Snake is long.
+    /// When already digesting the maximum number of foods, the food closest to being digested is dropped first.
+    /// # Arguments
+    /// * `max_digesting: usize` - The maximum number of foods digested at once.
+    pub fn start_digesting(&mut self, max_digesting: usize) {
+        while !self.digesting.is_empty() && self.digesting.len() >= max_digesting {
+            let oldest = self
+                .digesting
+                .iter()
+                .min_by_key(|(block, count)| (**count, block.x, block.y))
+                .map(|(block, _)| *block)
+                .unwrap();
+            self.digesting.remove(&oldest);
+        }
+        if max_digesting > 0 {
+            self.digesting
+                .insert(self.head_position(), self.body_len() as i32);
+        }
+    }
+
     /// Move the Snake forward in the current direction.
     /// This method modifies the Snakes body, so requires a mutable reference to self.
     /// # Arguments
@@ -244,25 +201,169 @@ impl Snake {
         self.body.push_back(self.tail.unwrap())
     }
 
+    /// Remove blocks from the end of the body, always keeping the head.
+    /// # Arguments
+    /// * `count: usize` - The number of blocks to remove.
+    /// # Returns
+    /// * `usize` - The number of blocks actually removed.
+    pub fn shed_tail(&mut self, count: usize) -> usize {
+        let removed = count.min(self.body.len().saturating_sub(1));
+        for block in self.body.split_off(self.body.len() - removed) {
+            // A block may still be stacked elsewhere in the body while the Snake unfolds.
+            if !self.body.contains(&block) {
+                self.digesting.remove(&block);
+            }
+        }
+        removed
+    }
+
     /// Check if a block overlaps with the Snake body.
     /// # Arguments
     /// * `block: Block` - The block to check overlap for.
     /// # Returns
     /// * `bool` - Whether (true) or not (false) this block overlaps.
     pub fn overlap_tail(&self, block: Block) -> bool {
-        // VecDeque does not support slicing of the back, which would be more convenient for .contains.
-        let mut counter = 0;
-        for body_part in &self.body {
-            // Checking if the overlapping part could be the tail, which is ok as it will move anyway.
-            counter += 1;
-            if counter == self.body.len() {
-                break;
-            }
-            // The overlapping bodypart is not the tail.
-            else if *body_part == block {
-                return true;
+        // Skipping the tail, which is ok to overlap as it will move anyway.
+        self.into_iter()
+            .take(self.body.len().saturating_sub(1))
+            .any(|body_part| body_part == block)
+    }
+}
+
+/// Consume the Snake into its body Blocks, from head to tail.
+impl IntoIterator for Snake {
+    type Item = Block;
+    type IntoIter = vec_deque::IntoIter<Block>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.into_iter()
+    }
+}
+
+/// Iterate over the body Blocks, from head to tail.
+impl<'a> IntoIterator for &'a Snake {
+    type Item = Block;
+    type IntoIter = Copied<vec_deque::Iter<'a, Block>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.body.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn snake(blocks: &[(i32, i32)]) -> Snake {
+        Snake::from_blocks(
+            blocks.iter().map(|&(x, y)| Block::new(x, y)),
+            Direction::Right,
+        )
+    }
+
+    #[test]
+    fn overlap_tail_single_block_body() {
+        // A Snake consisting of only a head has no body besides its tail.
+        let snake = snake(&[(5, 5)]);
+        assert!(!snake.overlap_tail(Block::new(5, 5)));
+        assert!(!snake.overlap_tail(Block::new(6, 5)));
+    }
+
+    #[test]
+    fn overlap_tail_body_block() {
+        let snake = snake(&[(5, 5), (4, 5), (3, 5), (2, 5)]);
+        assert!(snake.overlap_tail(Block::new(5, 5)));
+        assert!(snake.overlap_tail(Block::new(4, 5)));
+    }
+
+    #[test]
+    fn overlap_tail_excludes_tail() {
+        let snake = snake(&[(5, 5), (4, 5), (3, 5), (2, 5)]);
+        assert!(!snake.overlap_tail(Block::new(2, 5)));
+    }
+
+    #[test]
+    fn overlap_tail_second_to_last_block() {
+        let snake = snake(&[(5, 5), (4, 5), (3, 5), (2, 5)]);
+        assert!(snake.overlap_tail(Block::new(3, 5)));
+    }
+
+    #[test]
+    fn overlap_tail_wrapped_around_head() {
+        // The body coils clockwise around the head at (5, 5), ending just left of it.
+        let snake = snake(&[
+            (5, 5),
+            (5, 4),
+            (6, 4),
+            (6, 5),
+            (6, 6),
+            (5, 6),
+            (4, 6),
+            (4, 5),
+        ]);
+        assert!(snake.overlap_tail(snake.next_head(Some(Direction::Up))));
+        assert!(snake.overlap_tail(snake.next_head(Some(Direction::Right))));
+        assert!(snake.overlap_tail(snake.next_head(Some(Direction::Down))));
+        // Moving into the tail is allowed, as the tail moves away.
+        assert!(!snake.overlap_tail(snake.next_head(Some(Direction::Left))));
+    }
+
+    #[test]
+    fn overlap_tail_consistent_with_hashset() {
+        let mut snake = snake(&[(5, 5), (4, 5), (3, 5), (3, 6), (3, 7), (4, 7)]);
+        snake.digesting.insert(Block::new(3, 6), 2);
+        let body: Vec<Block> = snake.body.iter().copied().collect();
+        let occupied: HashSet<Block> = body[..body.len() - 1].iter().copied().collect();
+        for x in 0..10 {
+            for y in 0..10 {
+                let block = Block::new(x, y);
+                assert_eq!(snake.overlap_tail(block), occupied.contains(&block));
             }
         }
-        false
+    }
+
+    #[test]
+    fn iterating_goes_from_head_to_tail() {
+        let snake = snake(&[(5, 5), (4, 5), (4, 6)]);
+        let mut borrowed = Vec::new();
+        for block in &snake {
+            borrowed.push(block);
+        }
+        assert_eq!(borrowed[0], snake.head_position());
+        let owned: Vec<Block> = snake.into_iter().collect();
+        assert_eq!(owned, borrowed);
+        assert_eq!(
+            owned,
+            [Block::new(5, 5), Block::new(4, 5), Block::new(4, 6)]
+        );
+    }
+
+    #[test]
+    fn digesting_is_capped() {
+        let mut snake = snake(&[(5, 5), (4, 5), (3, 5)]);
+        for _ in 0..10 {
+            snake.start_digesting(SNAKE_STARTING_LENGTH as usize);
+            assert!(snake.digesting.len() <= SNAKE_STARTING_LENGTH as usize);
+            snake.move_forward(None);
+            snake.restore_tail();
+        }
+        // The food eaten last is still being digested.
+        assert!(snake
+            .digesting
+            .values()
+            .any(|count| *count == snake.body_len() as i32 - 2));
+    }
+
+    #[test]
+    fn shedding_keeps_the_head() {
+        let mut snake = snake(&[(5, 5), (4, 5), (3, 5), (2, 5)]);
+        assert_eq!(snake.shed_tail(2), 2);
+        assert_eq!(
+            snake.blocks().collect::<Vec<_>>(),
+            [Block::new(5, 5), Block::new(4, 5)]
+        );
+        assert_eq!(snake.shed_tail(5), 1);
+        assert_eq!(snake.body_len(), 1);
     }
 }
@@ -0,0 +1,159 @@
+// External imports.
+use piston_window::{Context, G2d};
+
+// Local imports.
+use super::Snake;
+use crate::direction::Direction;
+use crate::draw::{
+    draw_block, draw_marker, draw_outlined_block, get_offset_size_digesting,
+    get_offset_size_regular, BLOCK_SIZE,
+};
+use crate::theme::Theme;
+
+/// The width and height of an eye, in pixels.
+const EYE_SIZE: f64 = 4.0;
+/// How far the eyes sit ahead of the center of the head, in pixels.
+const EYE_FORWARD: f64 = 6.0;
+/// How far each eye sits to the side of the center of the head, in pixels.
+const EYE_SPACING: f64 = 6.0;
+/// The width of the darker edge around the head, in pixels.
+const HEAD_OUTLINE_WIDTH: f64 = 2.0;
+
+impl Snake {
+    /// Draw all blocks in the Snakes body inside the context using the graphics engine.
+    /// The game happens on a 2D array of Blocks of side length BLOCK_SIZE.
+    /// The snake body blocks, however, are smaller i.e., of side length SNAKE_BLOCK_SIZE.
+    /// In order to properly connect these smaller blocks, shifts in the size (the blocks' side length) and offset (the
+    /// blocks' starting point in the top left corner) are applied along 1 dimension. As a result, the snakes' body
+    /// blocks are rectangles rather than squares.
+    ///
+    /// Below, a three part snake is drawn in a grid, with the larger grid block corners denoted by `x`. Conversely, the
+    /// smaller snake body blocks' corners are denoted by an `o` and are colored in with `.`.
+    ///```text
+    /// x_______x_______x_______x
+    /// | o-------o-----|.......|
+    /// | |.............|.......|
+    /// | o-------o-----|.......|
+    /// x_______x_______x_______x
+    ///```
+    ///
+    /// # Arguments
+    /// * `theme: &Theme` - The colors to draw with.
+    /// * `eyes_open: bool` - Whether (true) or not (false) the eyes are drawn, as they are not while blinking.
+    /// * `con: &piston_window::Context` - A reference to the games context.
+    /// * `g: &mut piston_window::G2d` - A mutable reference to the graphics engine used for drawing.
+    pub fn draw(&mut self, theme: &Theme, eyes_open: bool, con: &Context, g: &mut G2d) {
+        for i in 1..self.body.len().saturating_sub(1) {
+            self.draw_body_segment(i, theme, con, g);
+        }
+        self.draw_tail(theme, con, g);
+        self.draw_head(theme, eyes_open, con, g);
+    }
+
+    /// Draw the head, filling its entire block, with the eyes looking in the direction of movement.
+    fn draw_head(&self, theme: &Theme, eyes_open: bool, con: &Context, g: &mut G2d) {
+        let head = self.head_position();
+        draw_outlined_block(
+            head,
+            theme.snake_head,
+            theme.snake_head_outline,
+            HEAD_OUTLINE_WIDTH,
+            con,
+            g,
+        );
+        if let Some(pattern) = theme.snake_head_pattern {
+            draw_marker(head, pattern, theme.marker_ratio, con, g);
+        }
+        if eyes_open {
+            for offset in eye_offsets(self.current_direction) {
+                draw_block(head, theme.snake_eyes, offset, [EYE_SIZE, EYE_SIZE], con, g);
+            }
+        }
+    }
+
+    /// Draw the last body block, connected only to the block in front of it.
+    fn draw_tail(&self, theme: &Theme, con: &Context, g: &mut G2d) {
+        if self.body.len() < 2 {
+            return;
+        }
+        let tail = self.body[self.body.len() - 1];
+        let previous = self.body[self.body.len() - 2];
+        let (x_offset_size, y_offset_size) = if self.digesting.contains_key(&tail) {
+            ([0.0, BLOCK_SIZE], [0.0, BLOCK_SIZE])
+        } else {
+            get_offset_size_regular(tail, previous, BLOCK_SIZE, theme.snake_block_ratio)
+        };
+        draw_block(
+            tail,
+            theme.snake_body,
+            [x_offset_size[0], y_offset_size[0]],
+            [x_offset_size[1], y_offset_size[1]],
+            con,
+            g,
+        )
+    }
+
+    /// Draw a body block between the head and the tail, connected to the blocks on both sides.
+    /// # Arguments
+    /// * `i: usize` - The index of the block in the body, from 1 up to but excluding the tail.
+    fn draw_body_segment(&self, i: usize, theme: &Theme, con: &Context, g: &mut G2d) {
+        let current = self.body[i];
+        let previous = self.body[i - 1];
+        let next = self.body[i + 1];
+        let (x_offset_size, y_offset_size) = if self.digesting.contains_key(&current) {
+            // Drawing body part on location where food was eaten.
+            ([0.0, BLOCK_SIZE], [0.0, BLOCK_SIZE])
+        } else if self.digesting.contains_key(&next) {
+            // The following block is digesting. Format the current based on both.
+            get_offset_size_digesting(current, previous, next, BLOCK_SIZE, theme.snake_block_ratio)
+        } else {
+            // The following block is not digesting. Format the current based only on previous.
+            get_offset_size_regular(current, previous, BLOCK_SIZE, theme.snake_block_ratio)
+        };
+        draw_block(
+            current,
+            theme.snake_body,
+            [x_offset_size[0], y_offset_size[0]],
+            [x_offset_size[1], y_offset_size[1]],
+            con,
+            g,
+        )
+    }
+}
+
+/// Calculate where the eyes are drawn on the head, side by side towards the front.
+/// # Arguments
+/// * `direction: Direction` - The direction the Snake is moving in.
+/// # Returns
+/// * `[[f64; 2]; 2]` - The offset of the top left corner of both eyes from the top left corner of the head, in pixels.
+fn eye_offsets(direction: Direction) -> [[f64; 2]; 2] {
+    let [dx, dy] = direction.offset().map(f64::from);
+    let center = (BLOCK_SIZE - EYE_SIZE) / 2.0;
+    // Spreading the eyes along the axis perpendicular to the direction of movement.
+    [-1.0, 1.0].map(|side| {
+        [
+            center + dx * EYE_FORWARD - dy * side * EYE_SPACING,
+            center + dy * EYE_FORWARD + dx * side * EYE_SPACING,
+        ]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eyes_look_ahead() {
+        let [left, right] = eye_offsets(Direction::Right);
+        // Both eyes are in the front half of the head, one above the other.
+        assert!(left[0] > BLOCK_SIZE / 2.0 - EYE_SIZE && left[0] == right[0]);
+        assert!(left[1] != right[1]);
+        let [up_left, up_right] = eye_offsets(Direction::Up);
+        assert!(up_left[1] < BLOCK_SIZE / 2.0 - EYE_SIZE && up_left[1] == up_right[1]);
+        for [x, y] in eye_offsets(Direction::Down) {
+            assert!(
+                x >= 0.0 && y >= 0.0 && x + EYE_SIZE <= BLOCK_SIZE && y + EYE_SIZE <= BLOCK_SIZE
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// External imports.
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Where the game keeps its files, like the high scores. Keeps the game logic free of file IO, so it also runs
+/// without a file system, e.g. in a web page.
+pub trait Storage {
+    /// Read a stored file.
+    /// # Arguments
+    /// * `name: &str` - The name of the file.
+    /// # Returns
+    /// * `Option<String>` - The contents, or None if the file does not exist or cannot be read.
+    fn load(&self, name: &str) -> Option<String>;
+
+    /// Write a file, replacing it if it already exists.
+    /// # Arguments
+    /// * `name: &str` - The name of the file.
+    /// * `data: String` - The new contents.
+    fn save(&mut self, name: &str, data: String) -> io::Result<()>;
+}
+
+/// Files in a folder on disk.
+pub struct FileStorage {
+    folder: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(folder: impl Into<PathBuf>) -> FileStorage {
+        FileStorage {
+            folder: folder.into(),
+        }
+    }
+
+    /// Get the location of a file on disk.
+    /// # Arguments
+    /// * `name: &str` - The name of the file.
+    /// # Returns
+    /// * `PathBuf` - The file inside the storage folder.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.folder.join(name)
+    }
+}
+
+impl Storage for FileStorage {
+    fn load(&self, name: &str) -> Option<String> {
+        fs::read_to_string(self.path(name)).ok()
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn save(&mut self, name: &str, data: String) -> io::Result<()> {
+        fs::write(self.path(name), data)
+    }
+
+    /// Writing in the background on the runtime the game loop entered, so the render thread never waits on disk.
+    /// Failures can therefore only be reported, not returned.
+    #[cfg(feature = "async")]
+    fn save(&mut self, name: &str, data: String) -> io::Result<()> {
+        let path = self.path(name);
+        // Blocking tasks are waited for when the runtime is dropped on exit, so no write is lost.
+        tokio::task::spawn_blocking(move || {
+            if let Err(_error) = fs::write(&path, data) {
+                #[cfg(feature = "logging")]
+                log::error!("Could not write {}: {}", path.display(), _error);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Files kept in memory, for tests and hosts without a file system. Everything is lost when it is dropped.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    files: HashMap<String, String>,
+}
+
+impl Storage for MemoryStorage {
+    fn load(&self, name: &str) -> Option<String> {
+        self.files.get(name).cloned()
+    }
+
+    fn save(&mut self, name: &str, data: String) -> io::Result<()> {
+        self.files.insert(name.to_string(), data);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_storage_round_trips() {
+        let mut storage = MemoryStorage::default();
+        assert_eq!(storage.load("scores.json"), None);
+        storage.save("scores.json", "[]".to_string()).unwrap();
+        assert_eq!(storage.load("scores.json").as_deref(), Some("[]"));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_file_writes_finish_when_the_runtime_is_dropped() {
+        let mut storage = FileStorage::new(std::env::temp_dir());
+        let name = "rust-snake-async-storage.json";
+        {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap();
+            let _guard = runtime.enter();
+            storage.save(name, "[]".to_string()).unwrap();
+        }
+        assert_eq!(storage.load(name).as_deref(), Some("[]"));
+        fs::remove_file(storage.path(name)).unwrap();
+    }
+}
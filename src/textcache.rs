@@ -0,0 +1,71 @@
+/// A formatted text that is only rebuilt when the values it shows change, rather than on every frame.
+#[derive(Debug, Clone)]
+pub struct TextCache<K> {
+    /// The values the text was last built from, or None before it was built.
+    key: Option<K>,
+    text: String,
+    /// The number of times the text was built.
+    builds: usize,
+}
+
+impl<K> Default for TextCache<K> {
+    fn default() -> Self {
+        TextCache {
+            key: None,
+            text: String::new(),
+            builds: 0,
+        }
+    }
+}
+
+impl<K: PartialEq> TextCache<K> {
+    pub fn new() -> TextCache<K> {
+        TextCache::default()
+    }
+
+    /// Check whether the text has to be rebuilt to show new values.
+    /// # Arguments
+    /// * `key: &K` - The values the text should show.
+    /// # Returns
+    /// * `bool` - Whether (true) or not (false) the text was built from different values, or not built at all.
+    pub fn is_stale(&self, key: &K) -> bool {
+        self.key.as_ref() != Some(key)
+    }
+
+    /// Replace the text with one built from new values.
+    /// # Arguments
+    /// * `key: K` - The values the text was built from.
+    /// * `text: String` - The new text.
+    pub fn store(&mut self, key: K, text: String) {
+        self.key = Some(key);
+        self.text = text;
+        self.builds += 1;
+    }
+
+    /// Get the text, which is empty before it was built.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the number of times the text was built.
+    pub fn builds(&self) -> usize {
+        self.builds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texts_are_only_rebuilt_for_new_values() {
+        let mut cache = TextCache::new();
+        for score in [1, 1, 1, 2, 2] {
+            if cache.is_stale(&score) {
+                cache.store(score, format!("SCORE: {score}"));
+            }
+        }
+        assert_eq!(cache.text(), "SCORE: 2");
+        assert_eq!(cache.builds(), 2);
+    }
+}
@@ -0,0 +1,388 @@
+// External imports.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// An RGBA color with channels between 0 and 1, the same layout the graphics engine uses.
+pub type Color = [f32; 4];
+
+// The side length of a snake body block, relative to the block size.
+pub const SNAKE_BLOCK_RATIO: f64 = 0.8;
+// The side length of a marker, relative to the block size.
+pub const MARKER_RATIO: f64 = 0.4;
+
+/// The built-in color palettes a game can be drawn in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Palette {
+    /// The original red food on a green snake.
+    #[default]
+    Classic,
+    /// Blue food on an orange snake, with shape markers on the food and snake head so nothing relies on hue alone.
+    Colorblind,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 2] = [Palette::Classic, Palette::Colorblind];
+
+    /// Get all colors of this palette.
+    pub fn theme(&self) -> Theme {
+        match *self {
+            Palette::Classic => Theme::classic(),
+            Palette::Colorblind => Theme::colorblind(),
+        }
+    }
+
+    /// Get the palette that follows this one, wrapping around after the last.
+    pub fn next(&self) -> Palette {
+        match *self {
+            Palette::Classic => Palette::Colorblind,
+            Palette::Colorblind => Palette::Classic,
+        }
+    }
+}
+
+/// Every color the game is drawn with. Theme files only need to hold the colors they change, the others are taken
+/// from the classic palette.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// The playing field.
+    pub background: Color,
+    /// The bottom of the playing field, which fades from the background color when the gradient is on.
+    pub background_bottom: Color,
+    /// The borders around the playing field and the score strip.
+    pub border: Color,
+    pub snake_head: Color,
+    /// The darker edge around the snake head.
+    pub snake_head_outline: Color,
+    /// An optional smaller square drawn on top of the snake head.
+    pub snake_head_pattern: Option<Color>,
+    pub snake_body: Color,
+    /// The eyes on the snake head.
+    pub snake_eyes: Color,
+    pub food: Color,
+    pub bonus_food: Color,
+    /// An optional smaller square drawn on top of every food.
+    pub food_marker: Option<Color>,
+    /// The outline around food the Snake is heading straight for.
+    pub food_highlight: Color,
+    /// The points lost to a penalty, popping up where it was taken.
+    pub penalty: Color,
+    /// The star multiplying the points of the next foods.
+    pub star: Color,
+    /// The characters drawn on top of special food.
+    pub food_glyph: Color,
+    /// The text and icons in the score strip.
+    pub hud: Color,
+    /// Secondary details in the score strip, like the mute cross.
+    pub hud_accent: Color,
+    /// The speed bar in the score strip, once the game cannot speed up any further.
+    pub max_speed: Color,
+    /// The length bar in the score strip, for a Snake filling up to a quarter, half, three quarters and all of the
+    /// playing field.
+    pub length_bar: [Color; 4],
+    /// A door that is still closed, drawn like a wall of a different color.
+    pub door: Color,
+    /// An opened door, which the Snake can move through.
+    pub door_open: Color,
+    /// The key opening a door.
+    pub key: Color,
+    /// The row about to turn lethal, drawn translucent while flashing as a warning and opaque as it strikes.
+    pub hazard: Color,
+    /// The translucent overlay covering the playing field after a game over.
+    pub game_over_overlay: Color,
+    /// All text drawn on top of the playing field.
+    pub overlay_text: Color,
+    /// The side length of a snake body block, relative to the block size.
+    pub snake_block_ratio: f64,
+    /// The side length of the markers on food, keys and the snake head, relative to the block size.
+    pub marker_ratio: f64,
+}
+
+/// The reasons a theme file cannot be used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThemeError {
+    /// The file cannot be read.
+    Read(String),
+    /// The file is no valid theme, e.g. a color without four channels or an unknown key.
+    Parse(String),
+    /// A color channel lies outside 0 to 1, or a ratio outside (0, 1], with the key it belongs to.
+    OutOfRange { key: String, value: f64 },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Read(error) => write!(f, "cannot read the theme file: {}", error),
+            ThemeError::Parse(error) => write!(f, "invalid theme file: {}", error),
+            ThemeError::OutOfRange { key, value } => {
+                write!(f, "{} of {} lies outside 0 to 1", value, key)
+            }
+        }
+    }
+}
+
+/// Check that every number in a theme file lies in the range it is drawn with.
+/// # Arguments
+/// * `key: &str` - The key the value belongs to, also for the channels of a color.
+/// * `value: &serde_json::Value` - The value to check, recursing into colors and objects.
+fn check_range(key: &str, value: &serde_json::Value) -> Result<(), ThemeError> {
+    match value {
+        serde_json::Value::Number(number) => {
+            let number = number.as_f64().unwrap_or(f64::NAN);
+            // A ratio of 0 would make the blocks it sizes disappear.
+            let in_range = if key.ends_with("_ratio") {
+                number > 0.0 && number <= 1.0
+            } else {
+                (0.0..=1.0).contains(&number)
+            };
+            if in_range {
+                Ok(())
+            } else {
+                Err(ThemeError::OutOfRange {
+                    key: key.to_string(),
+                    value: number,
+                })
+            }
+        }
+        serde_json::Value::Array(values) => values.iter().try_for_each(|v| check_range(key, v)),
+        serde_json::Value::Object(values) => values.iter().try_for_each(|(k, v)| check_range(k, v)),
+        _ => Ok(()),
+    }
+}
+
+impl Theme {
+    pub fn classic() -> Theme {
+        Theme {
+            background: [0.50, 0.50, 0.50, 1.00],
+            background_bottom: [0.30, 0.30, 0.35, 1.00],
+            border: [0.00, 0.00, 0.00, 1.00],
+            snake_head: [0.00, 0.60, 0.00, 1.00],
+            snake_head_outline: [0.00, 0.35, 0.00, 1.00],
+            snake_head_pattern: None,
+            snake_body: [0.00, 0.80, 0.00, 1.00],
+            snake_eyes: [0.00, 0.00, 0.00, 1.00],
+            food: [0.80, 0.00, 0.00, 1.00],
+            bonus_food: [1.00, 0.80, 0.00, 1.00],
+            food_marker: None,
+            food_highlight: [1.00, 1.00, 1.00, 1.00],
+            penalty: [1.00, 0.15, 0.15, 1.00],
+            star: [0.60, 0.20, 0.80, 1.00],
+            food_glyph: [0.00, 0.00, 0.00, 1.00],
+            hud: [0.80, 0.00, 0.00, 1.00],
+            hud_accent: [1.00, 1.00, 1.00, 1.00],
+            max_speed: [1.00, 0.84, 0.00, 1.00],
+            length_bar: [
+                [0.00, 0.80, 0.00, 1.00],
+                [1.00, 0.90, 0.00, 1.00],
+                [1.00, 0.55, 0.00, 1.00],
+                [0.90, 0.00, 0.00, 1.00],
+            ],
+            door: [0.45, 0.25, 0.05, 1.00],
+            door_open: [0.60, 0.45, 0.30, 1.00],
+            key: [1.00, 0.84, 0.00, 1.00],
+            hazard: [1.00, 0.40, 0.00, 1.00],
+            game_over_overlay: [0.90, 0.00, 0.00, 0.50],
+            overlay_text: [1.00, 1.00, 1.00, 0.90],
+            snake_block_ratio: SNAKE_BLOCK_RATIO,
+            marker_ratio: MARKER_RATIO,
+        }
+    }
+
+    pub fn colorblind() -> Theme {
+        Theme {
+            background: [0.18, 0.20, 0.28, 1.00],
+            background_bottom: [0.08, 0.09, 0.16, 1.00],
+            border: [0.00, 0.00, 0.00, 1.00],
+            snake_head: [0.80, 0.45, 0.00, 1.00],
+            snake_head_outline: [0.50, 0.25, 0.00, 1.00],
+            snake_head_pattern: Some([0.00, 0.00, 0.00, 1.00]),
+            snake_body: [0.95, 0.60, 0.00, 1.00],
+            // The head pattern is already black, so the eyes stand out against it.
+            snake_eyes: [1.00, 1.00, 1.00, 1.00],
+            food: [0.20, 0.55, 1.00, 1.00],
+            bonus_food: [0.85, 0.85, 1.00, 1.00],
+            food_marker: Some([1.00, 1.00, 1.00, 1.00]),
+            food_highlight: [1.00, 1.00, 1.00, 1.00],
+            penalty: [1.00, 0.45, 0.70, 1.00],
+            star: [0.00, 0.80, 0.80, 1.00],
+            food_glyph: [0.00, 0.00, 0.00, 1.00],
+            hud: [1.00, 0.85, 0.00, 1.00],
+            hud_accent: [1.00, 1.00, 1.00, 1.00],
+            max_speed: [1.00, 0.84, 0.00, 1.00],
+            length_bar: [
+                [0.20, 0.55, 1.00, 1.00],
+                [0.85, 0.85, 1.00, 1.00],
+                [1.00, 0.85, 0.00, 1.00],
+                [0.95, 0.60, 0.00, 1.00],
+            ],
+            door: [0.55, 0.55, 0.55, 1.00],
+            door_open: [0.30, 0.32, 0.40, 1.00],
+            key: [1.00, 1.00, 1.00, 1.00],
+            hazard: [1.00, 0.85, 0.00, 1.00],
+            game_over_overlay: [0.10, 0.10, 0.40, 0.60],
+            overlay_text: [1.00, 1.00, 1.00, 0.95],
+            snake_block_ratio: SNAKE_BLOCK_RATIO,
+            marker_ratio: MARKER_RATIO,
+        }
+    }
+
+    /// Parse a theme from JSON, checking that all colors and ratios are in range.
+    /// # Arguments
+    /// * `json: &str` - The contents of a theme file.
+    /// # Returns
+    /// * `Result<Theme, ThemeError>` - The theme, with every missing key taken from the classic palette.
+    pub fn from_json(json: &str) -> Result<Theme, ThemeError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|error| ThemeError::Parse(error.to_string()))?;
+        check_range("theme", &value)?;
+        serde_json::from_value(value).map_err(|error| ThemeError::Parse(error.to_string()))
+    }
+
+    /// Load a theme from a theme file.
+    /// # Arguments
+    /// * `json: P` - A reference to path-like object, pointing to a theme file.
+    /// # Returns
+    /// * `Result<Theme, ThemeError>` - The theme, with every missing key taken from the classic palette.
+    pub fn load<P: AsRef<Path>>(json: P) -> Result<Theme, ThemeError> {
+        let data = fs::read_to_string(json).map_err(|error| ThemeError::Read(error.to_string()))?;
+        Theme::from_json(&data)
+    }
+
+    /// Turn this theme into its high contrast variant: a black playing field inside a white frame.
+    /// # Returns
+    /// * `Theme` - The theme with the background, border and score strip colors replaced.
+    pub fn with_high_contrast(self) -> Theme {
+        Theme {
+            background: [0.00, 0.00, 0.00, 1.00],
+            background_bottom: [0.00, 0.00, 0.00, 1.00],
+            border: [1.00, 1.00, 1.00, 1.00],
+            // The score strip shares the border color, so its contents turn dark.
+            hud: [0.00, 0.00, 0.00, 1.00],
+            hud_accent: [0.40, 0.40, 0.40, 1.00],
+            ..self
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+/// Load every theme file in a folder, skipping the ones that cannot be used.
+/// # Arguments
+/// * `folder: &Path` - The folder holding the theme files, e.g. `assets/themes`.
+/// # Returns
+/// * `BTreeMap<String, Theme>` - The themes by file name without the `.json` extension. Empty if the folder does not
+///   exist.
+pub fn load_themes(folder: &Path) -> BTreeMap<String, Theme> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return BTreeMap::new();
+    };
+    let mut themes = BTreeMap::new();
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        match Theme::load(&path) {
+            Ok(theme) => {
+                themes.insert(name.to_string(), theme);
+            }
+            Err(_error) => {
+                #[cfg(feature = "logging")]
+                log::warn!("Skipping theme {}: {}", path.display(), _error);
+            }
+        }
+    }
+    themes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_cycles_through_all_palettes() {
+        assert_eq!(Palette::Classic.next(), Palette::Colorblind);
+        assert_eq!(Palette::Classic.next().next(), Palette::Classic);
+    }
+
+    #[test]
+    fn colorblind_palette_avoids_pure_red() {
+        let theme = Palette::Colorblind.theme();
+        for color in [theme.food, theme.bonus_food, theme.game_over_overlay] {
+            assert!(color[0] < 0.9 || color[1] > 0.5 || color[2] > 0.5);
+        }
+        assert!(theme.food_marker.is_some());
+        assert!(theme.snake_head_pattern.is_some());
+    }
+
+    #[test]
+    fn high_contrast_keeps_the_palette_colors() {
+        let theme = Theme::colorblind().with_high_contrast();
+        assert_eq!(theme.food, Theme::colorblind().food);
+        assert_ne!(theme.background, Theme::colorblind().background);
+    }
+
+    #[test]
+    fn flat_backgrounds_have_no_gradient() {
+        use crate::config::GameConfig;
+
+        let theme = GameConfig::default().theme();
+        assert_eq!(theme.background, theme.background_bottom);
+        let theme = GameConfig {
+            gradient_background: true,
+            ..GameConfig::default()
+        }
+        .theme();
+        assert_ne!(theme.background, theme.background_bottom);
+    }
+
+    #[test]
+    fn partial_theme_files_fill_in_the_classic_colors() {
+        let theme =
+            Theme::from_json(r#"{"food": [0.0, 0.0, 1.0, 1.0], "marker_ratio": 0.5}"#).unwrap();
+        assert_eq!(theme.food, [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(theme.marker_ratio, 0.5);
+        assert_eq!(theme.snake_body, Theme::classic().snake_body);
+        assert_eq!(theme.snake_block_ratio, SNAKE_BLOCK_RATIO);
+    }
+
+    #[test]
+    fn invalid_theme_files_are_rejected() {
+        assert_eq!(
+            Theme::from_json(r#"{"food": [0.0, 2.0, 1.0, 1.0]}"#),
+            Err(ThemeError::OutOfRange {
+                key: String::from("food"),
+                value: 2.0
+            })
+        );
+        assert!(matches!(
+            Theme::from_json(r#"{"snake_block_ratio": 0.0}"#),
+            Err(ThemeError::OutOfRange { .. })
+        ));
+        // Colors need all four channels, and typos in the keys are reported rather than ignored.
+        assert!(matches!(
+            Theme::from_json(r#"{"food": [0.0, 0.0, 1.0]}"#),
+            Err(ThemeError::Parse(_))
+        ));
+        assert!(matches!(
+            Theme::from_json(r#"{"fod": [0.0, 0.0, 1.0, 1.0]}"#),
+            Err(ThemeError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn bundled_themes_load() {
+        let themes = load_themes(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/themes"));
+        assert!(themes.contains_key("midnight"));
+        assert!(load_themes(Path::new("no/such/folder")).is_empty());
+    }
+}
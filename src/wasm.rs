@@ -0,0 +1,117 @@
+// External imports.
+use wasm_bindgen::prelude::wasm_bindgen;
+
+// Local imports.
+use crate::block::Block;
+use crate::config::GameConfig;
+use crate::direction::Direction;
+use crate::game::Game;
+
+/// A game stepped from JavaScript, which also draws it, e.g. on a canvas:
+///
+/// ```js
+/// const game = new WebGame(Date.now() >>> 0);
+/// addEventListener("keydown", (event) => game.key_pressed(event.key));
+/// let last = performance.now();
+/// requestAnimationFrame(function frame(now) {
+///     game.update((now - last) / 1000);
+///     last = now;
+///     const colors = game.cells();
+///     // Drawing game.width() by game.height() cells, row by row.
+///     requestAnimationFrame(frame);
+/// });
+/// ```
+#[wasm_bindgen]
+pub struct WebGame {
+    game: Game,
+}
+
+#[wasm_bindgen]
+impl WebGame {
+    /// Start a game on the default board.
+    /// # Arguments
+    /// * `seed: u32` - The seed of the random number generator, so the host decides where randomness comes from.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> WebGame {
+        WebGame {
+            game: Game::new(GameConfig {
+                seed: Some(seed.into()),
+                ..GameConfig::default()
+            }),
+        }
+    }
+
+    /// Get the width of the board in cells.
+    pub fn width(&self) -> i32 {
+        self.game.size().0
+    }
+
+    /// Get the height of the board in cells, including the score strip.
+    pub fn height(&self) -> i32 {
+        self.game.size().1
+    }
+
+    /// React to a keypress.
+    /// # Arguments
+    /// * `key: &str` - The `key` of a JavaScript `KeyboardEvent`. Allows the arrow keys, "p" and space.
+    pub fn key_pressed(&mut self, key: &str) {
+        if self.game.dismiss_hints() {
+            return;
+        }
+        match key {
+            "ArrowUp" => self.game.steer(Direction::Up),
+            "ArrowDown" => self.game.steer(Direction::Down),
+            "ArrowLeft" => self.game.steer(Direction::Left),
+            "ArrowRight" => self.game.steer(Direction::Right),
+            "p" => self.game.toggle_pause(),
+            " " => self.game.play_again(),
+            _ => (),
+        }
+    }
+
+    /// Advance the game by the time passed since the last update.
+    /// # Arguments
+    /// * `delta_time: f64` - The time passed, in seconds.
+    pub fn update(&mut self, delta_time: f64) {
+        self.game.update(delta_time);
+        // Events are for sound effects, which the page does not play yet.
+        self.game.drain_events().for_each(drop);
+    }
+
+    pub fn score(&self) -> i32 {
+        self.game.score()
+    }
+
+    pub fn game_over(&self) -> bool {
+        self.game.game_over()
+    }
+
+    /// Get the color of every cell, row by row.
+    /// # Returns
+    /// * `Vec<u32>` - The colors as `0xRRGGBB`, `width()` times `height()` of them.
+    pub fn cells(&self) -> Vec<u32> {
+        let (width, height) = self.game.size();
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| Block::new(x, y)))
+            .map(|block| {
+                let color = self.game.cell(block).color(self.game.theme());
+                color[..3].iter().fold(0, |rgb, channel| {
+                    rgb << 8 | (channel * 255.0).round() as u32
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_cover_the_whole_board() {
+        let game = WebGame::new(42);
+        assert_eq!(game.cells().len(), (game.width() * game.height()) as usize);
+        // The top left corner is a black border.
+        assert_eq!(game.cells()[0], 0x000000);
+    }
+}